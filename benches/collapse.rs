@@ -0,0 +1,54 @@
+//! Times a hot loop of edge collapses.
+//!
+//! Run it once with and once without the default `tracing` feature to compare both builds:
+//!
+//! ```sh
+//! cargo bench --bench collapse
+//! cargo bench --bench collapse --no-default-features
+//! ```
+//!
+//! The printed face and vertex counts are the same for both builds.
+
+use std::time::{Duration, Instant};
+
+use mesh_graph::{MeshGraph, primitives::IcoSphere};
+
+const RUNS: usize = 10;
+
+fn collapse_all(mesh_graph: &mut MeshGraph) -> usize {
+    let he_ids = mesh_graph.halfedges.keys().collect::<Vec<_>>();
+
+    he_ids
+        .into_iter()
+        .filter(|&he_id| {
+            mesh_graph.halfedges.contains_key(he_id)
+                && mesh_graph.collapse_edge_if_valid(he_id).is_some()
+        })
+        .count()
+}
+
+fn main() {
+    let sphere = MeshGraph::from(IcoSphere {
+        radius: 1.0,
+        subdivisions: 4,
+    });
+
+    let mut best = Duration::MAX;
+    let mut result = (0, 0, 0);
+
+    for _ in 0..RUNS {
+        let mut mesh_graph = sphere.clone();
+
+        let start = Instant::now();
+        let collapsed = collapse_all(&mut mesh_graph);
+        best = best.min(start.elapsed());
+
+        result = (collapsed, mesh_graph.faces.len(), mesh_graph.vertices.len());
+    }
+
+    let (collapsed, faces, vertices) = result;
+    println!(
+        "tracing: {}, collapsed {collapsed} edges ({faces} faces, {vertices} vertices left), best of {RUNS}: {best:?}",
+        cfg!(feature = "tracing")
+    );
+}
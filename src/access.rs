@@ -1,10 +1,11 @@
-use tracing::{error, instrument};
+use crate::utils::error;
+use glam::Vec3;
 
 use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, error_none, utils::unwrap_or_return};
 
 impl MeshGraph {
     /// Return the halfedge or it's twin depending on which one is boundary, or `None` if both are not boundary.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn boundary_he(&self, he_id: HalfedgeId) -> Option<HalfedgeId> {
         let he = *self
             .halfedges
@@ -33,7 +34,7 @@ impl MeshGraph {
     /// Useful when adding faces on boundaries.
     ///
     /// If the edge is not boundary, it always returns `[v_id2, v_id1]`.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn boundary_vertex_order(&mut self, v_id1: VertexId, v_id2: VertexId) -> [VertexId; 2] {
         let he_id = unwrap_or_return!(
             self.halfedge_from_to(v_id1, v_id2),
@@ -52,7 +53,7 @@ impl MeshGraph {
     }
 
     /// Returns the halfedge from the start vertex to the end vertex, if it exists. `None` otherwise.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn halfedge_from_to(
         &self,
         start_vertex_id: VertexId,
@@ -72,7 +73,7 @@ impl MeshGraph {
 
     /// Returns all the halfedges from the start vertex to the end vertex.
     /// In a valid manifold mesh, this should return exactly one halfedge.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn halfedges_from_to(
         &self,
         start_vertex_id: VertexId,
@@ -130,7 +131,7 @@ impl MeshGraph {
     /// In contrast to `Vertex.faces()` it doesn't guarantee a specific order. But it
     /// uses `MeshGraph::outgoing_halfedges` so it can continue to work with non-manifold
     /// mesh graphs while `Vertex.faces()` only works with manifold ones.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn vertex_adjacent_faces(&self, vertex_id: VertexId) -> Vec<FaceId> {
         let Some(he_ids) = self.outgoing_halfedges.get(vertex_id) else {
             error!("No outgoing halfedges entry for vertex {vertex_id:?}");
@@ -147,4 +148,220 @@ impl MeshGraph {
             })
             .collect()
     }
+
+    /// Returns the halfedge of `face_id1` whose twin belongs to `face_id2`, or `None` if the
+    /// two faces are not adjacent through an edge.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn shared_halfedge(&self, face_id1: FaceId, face_id2: FaceId) -> Option<HalfedgeId> {
+        let face1 = self
+            .faces
+            .get(face_id1)
+            .or_else(error_none!("Face 1 not found"))?;
+
+        face1.halfedges(self).find(|&he_id| {
+            self.halfedges
+                .get(he_id)
+                .and_then(|he| he.twin)
+                .and_then(|twin_id| self.halfedges.get(twin_id))
+                .is_some_and(|twin| twin.face == Some(face_id2))
+        })
+    }
+
+    /// Iterates over all vertices together with their position and (if computed) their normal.
+    ///
+    /// Vertices without a position are skipped.
+    pub fn vertices_with_attrs(&self) -> impl Iterator<Item = (VertexId, Vec3, Option<Vec3>)> {
+        self.vertices.keys().filter_map(|vertex_id| {
+            let pos = *self
+                .positions
+                .get(vertex_id)
+                .or_else(error_none!("Position not found for {vertex_id:?}"))?;
+            let normal = self
+                .vertex_normals
+                .as_ref()
+                .and_then(|normals| normals.get(vertex_id))
+                .copied();
+
+            Some((vertex_id, pos, normal))
+        })
+    }
+
+    /// Iterates over all undirected edges. Yields exactly one halfedge per edge which is the
+    /// one with the smaller id of the twin pair. Boundary edges are included.
+    pub fn edges(&self) -> impl Iterator<Item = HalfedgeId> {
+        self.halfedges
+            .iter()
+            .filter(|(he_id, he)| he.twin.is_none_or(|twin_id| *he_id < twin_id))
+            .map(|(he_id, _)| he_id)
+    }
+
+    /// Returns the vertex with the smallest key or `None` if the mesh graph has no vertices.
+    ///
+    /// In contrast to `self.vertices.keys().next()` this is deterministic for identically built
+    /// mesh graphs. Useful as a well-defined starting point for traversals and in tests.
+    pub fn any_vertex(&self) -> Option<VertexId> {
+        self.vertices.keys().min()
+    }
+
+    /// Returns the face with the smallest key or `None` if the mesh graph has no faces.
+    ///
+    /// See [`MeshGraph::any_vertex`].
+    pub fn any_face(&self) -> Option<FaceId> {
+        self.faces.keys().min()
+    }
+
+    /// Returns the halfedge with the smallest key or `None` if the mesh graph has no halfedges.
+    ///
+    /// See [`MeshGraph::any_vertex`].
+    pub fn any_halfedge(&self) -> Option<HalfedgeId> {
+        self.halfedges.keys().min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+    use itertools::Itertools;
+
+    use crate::primitives::{IcoSphere, Quad};
+
+    use super::*;
+
+    #[test]
+    fn test_shared_halfedge() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let face_id = mesh_graph.faces.keys().next().unwrap();
+        let face = mesh_graph.faces[face_id];
+        let he_id = face.halfedge;
+        let other_face_id = mesh_graph.halfedges[mesh_graph.halfedges[he_id].twin.unwrap()]
+            .face
+            .unwrap();
+
+        let shared_he_id = mesh_graph.shared_halfedge(face_id, other_face_id).unwrap();
+        assert_eq!(shared_he_id, he_id);
+        assert_eq!(
+            mesh_graph.halfedges[mesh_graph.halfedges[shared_he_id].twin.unwrap()].face,
+            Some(other_face_id)
+        );
+
+        let far_face_id = mesh_graph
+            .faces
+            .keys()
+            .find(|&id| {
+                face.vertices(&mesh_graph)
+                    .all(|v| mesh_graph.faces[id].vertices(&mesh_graph).all(|w| w != v))
+            })
+            .unwrap();
+        assert_eq!(mesh_graph.shared_halfedge(face_id, far_face_id), None);
+    }
+
+    #[test]
+    fn test_any_element_is_deterministic() {
+        let build = || {
+            MeshGraph::from(IcoSphere {
+                radius: 1.0,
+                subdivisions: 2,
+            })
+        };
+
+        let mesh_graph1 = build();
+        let mesh_graph2 = build();
+
+        assert!(mesh_graph1.any_vertex().is_some());
+        assert_eq!(mesh_graph1.any_vertex(), mesh_graph2.any_vertex());
+        assert_eq!(mesh_graph1.any_face(), mesh_graph2.any_face());
+        assert_eq!(mesh_graph1.any_halfedge(), mesh_graph2.any_halfedge());
+
+        assert_eq!(MeshGraph::new().any_vertex(), None);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let edges = mesh_graph.edges().collect::<HashSet<_>>();
+
+        assert_eq!(edges.len(), mesh_graph.halfedges.len() / 2);
+        assert_eq!(mesh_graph.edges().count(), edges.len());
+
+        for he in mesh_graph.halfedges.values() {
+            let twin_id = he.twin.unwrap();
+            let twin_twin_id = mesh_graph.halfedges[twin_id].twin.unwrap();
+            assert!(edges.contains(&twin_id) != edges.contains(&twin_twin_id));
+        }
+
+        // boundary edges are included
+        let quad = MeshGraph::from(Quad(Vec3::ZERO, Vec3::X, Vec3::ONE, Vec3::Y));
+        assert_eq!(quad.edges().count(), 5);
+    }
+
+    #[test]
+    fn test_halfedge_from_to_shared() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+        let shared = &mesh_graph;
+
+        // every face can be walked through its vertices while the mesh graph is borrowed
+        let face_halfedges = shared
+            .faces
+            .values()
+            .map(|face| {
+                let [a, b, c] = face.vertices(shared).collect_array().unwrap();
+                [(a, b), (b, c), (c, a)].map(|(start, end)| shared.halfedge_from_to(start, end))
+            })
+            .collect_vec();
+
+        for (face, halfedges) in shared.faces.values().zip(face_halfedges) {
+            for he_id in halfedges {
+                assert_eq!(shared.halfedges[he_id.unwrap()].face, Some(face.id));
+            }
+        }
+
+        // an unknown vertex returns `None` instead of inserting anything
+        let mut removed = mesh_graph.clone();
+        let vertex_id = removed.vertices.keys().next().unwrap();
+        let other_id = removed.vertices[vertex_id]
+            .neighbours(&removed)
+            .next()
+            .unwrap();
+        removed.outgoing_halfedges.remove(vertex_id);
+
+        assert_eq!(removed.halfedge_from_to(vertex_id, other_id), None);
+        assert!(!removed.outgoing_halfedges.contains_key(vertex_id));
+    }
+
+    #[test]
+    fn test_vertices_with_attrs() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        assert_eq!(
+            mesh_graph.vertices_with_attrs().count(),
+            mesh_graph.vertices.len()
+        );
+        assert!(mesh_graph.vertices_with_attrs().all(
+            |(vertex_id, pos, normal)| mesh_graph.positions[vertex_id] == pos && normal.is_none()
+        ));
+
+        mesh_graph.vertex_normals = Some(Default::default());
+        mesh_graph.compute_vertex_normals();
+
+        let normals = mesh_graph.vertex_normals.as_ref().unwrap();
+        assert!(
+            mesh_graph
+                .vertices_with_attrs()
+                .all(|(vertex_id, _, normal)| normal == Some(normals[vertex_id]))
+        );
+    }
 }
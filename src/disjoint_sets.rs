@@ -0,0 +1,27 @@
+/// Minimal union-find over the indices `0..len`
+pub(crate) struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    pub fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            // path halving
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+
+        index
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a.max(b)] = a.min(b);
+    }
+}
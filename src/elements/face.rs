@@ -1,7 +1,7 @@
+use crate::utils::error;
 use glam::Vec3;
 use itertools::Itertools;
 use parry3d::bounding_volume::Aabb;
-use tracing::{error, instrument};
 
 use crate::{CircularHalfedgesIterator, MeshGraph, error_none};
 
@@ -23,7 +23,7 @@ pub struct Face {
 
 impl Face {
     /// Returns the three halfedges that form this face
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn halfedges<'a>(&self, mesh_graph: &'a MeshGraph) -> CircularHalfedgesIterator<'a> {
         CircularHalfedgesIterator::new(
             Some(self.halfedge),
@@ -40,7 +40,7 @@ impl Face {
     }
 
     /// Returns the three corner vertices of this face.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn vertices(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = VertexId> {
         self.halfedges(mesh_graph).filter_map(|he| {
             mesh_graph
@@ -51,14 +51,46 @@ impl Face {
         })
     }
 
+    /// Returns the faces that share an edge with this face.
+    ///
+    /// Boundary edges are skipped so for an interior face this yields exactly three faces.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn adjacent_faces(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = FaceId> {
+        self.halfedges(mesh_graph).filter_map(|he_id| {
+            let twin_id = mesh_graph
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge not found"))?
+                .twin
+                .or_else(error_none!("Twin missing"))?;
+
+            mesh_graph
+                .halfedges
+                .get(twin_id)
+                .or_else(error_none!("Twin not found"))?
+                .face
+        })
+    }
+
     /// Center positions of this face.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn center(&self, mesh_graph: &MeshGraph) -> Vec3 {
         self.vertex_positions(mesh_graph).sum::<Vec3>() / 3.0
     }
 
+    /// Area of this triangle. Degenerate triangles have an area of zero.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn area(&self, mesh_graph: &MeshGraph) -> f32 {
+        let Some([a, b, c]) = self.vertex_positions(mesh_graph).collect_array() else {
+            error!("Face has less than 3 vertex positions");
+            return 0.0;
+        };
+
+        (b - a).cross(c - a).length() * 0.5
+    }
+
     /// Compute the parry Aabb of this triangle
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn aabb(&self, mesh_graph: &MeshGraph) -> Aabb {
         Aabb::from_points(
             self.vertex_positions(mesh_graph)
@@ -67,7 +99,7 @@ impl Face {
     }
 
     /// Returns an iterator over the vertex positions of this face.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn vertex_positions(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = Vec3> {
         self.vertices(mesh_graph).filter_map(|v| {
             mesh_graph
@@ -78,9 +110,26 @@ impl Face {
         })
     }
 
-    /// Compute the normal of this triangle
-    #[instrument(skip(mesh_graph))]
+    /// Normal of this triangle.
+    ///
+    /// Returns the cached normal if [`MeshGraph::face_normals`] has been computed
+    /// (see [`MeshGraph::compute_face_normals`]), otherwise it is computed from the positions.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn normal(&self, mesh_graph: &MeshGraph) -> Option<Vec3> {
+        if let Some(normal) = mesh_graph
+            .face_normals
+            .as_ref()
+            .and_then(|normals| normals.get(self.id))
+        {
+            return Some(*normal);
+        }
+
+        self.compute_normal(mesh_graph)
+    }
+
+    /// Compute the normal of this triangle from the current vertex positions
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn compute_normal(&self, mesh_graph: &MeshGraph) -> Option<Vec3> {
         let positions = self.vertex_positions(mesh_graph).collect_vec();
 
         if positions.len() < 3 {
@@ -100,7 +149,7 @@ impl Face {
     }
 
     /// Wether this triangle is degenerate.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn is_degenerate(&self, mesh_graph: &MeshGraph, epsilon_sqr: f32) -> bool {
         let positions = self.vertex_positions(mesh_graph).collect_vec();
 
@@ -130,7 +179,7 @@ impl Face {
         cross.length_squared() < epsilon_sqr
     }
 
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn halfedge_between(
         &self,
         vertex_id1: VertexId,
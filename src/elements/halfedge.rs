@@ -1,6 +1,6 @@
-use tracing::{error, instrument};
+use crate::utils::error;
 
-use crate::{MeshGraph, error_none};
+use crate::{MeshGraph, error_none, ops::cotangent_at};
 
 use super::{FaceId, HalfedgeId, VertexId};
 
@@ -39,7 +39,7 @@ impl Halfedge {
     /// Start vertex from which this halfedge points away
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/halfedge/start_vertex.svg" alt="Connectivity" style="max-width: 28em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn start_vertex(&self, mesh_graph: &MeshGraph) -> Option<VertexId> {
         mesh_graph
             .halfedges
@@ -51,7 +51,7 @@ impl Halfedge {
     /// Previous halfedge that shares the same face. `None` if `self` is a boundary halfedge.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/halfedge/prev.svg" alt="Connectivity" style="max-width: 28em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn prev(&self, mesh_graph: &MeshGraph) -> Option<HalfedgeId> {
         // TODO : this only works for triangle meshes
         self.next.and_then(|next_id| {
@@ -66,7 +66,7 @@ impl Halfedge {
     /// In counter-clockwise order next halfedge that has the same start vertex
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/halfedge/ccw_rotated_neighbour.svg" alt="Connectivity" style="max-width: 28em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn ccw_rotated_neighbour(&self, mesh_graph: &MeshGraph) -> Option<HalfedgeId> {
         self.prev(mesh_graph).and_then(|prev| {
             mesh_graph
@@ -80,7 +80,7 @@ impl Halfedge {
     /// In clockwise order next halfedge that has the same start vertex
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/halfedge/cw_rotated_neighbour.svg" alt="Connectivity" style="max-width: 28em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn cw_rotated_neighbour(&self, mesh_graph: &MeshGraph) -> Option<HalfedgeId> {
         mesh_graph
             .halfedges
@@ -89,7 +89,7 @@ impl Halfedge {
     }
 
     /// Length of the halfedge squared.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn length_squared(&self, mesh_graph: &MeshGraph) -> f32 {
         self.len_sqr_inner(mesh_graph).unwrap_or_else(|| {
             error!("Halfedge invalid. Defaulting to zero length");
@@ -110,6 +110,39 @@ impl Halfedge {
         self.length_squared(mesh_graph).sqrt()
     }
 
+    /// Cotangent weight `(cot(α) + cot(β)) / 2` of this edge where `α` and `β` are the angles
+    /// opposite to it in the two adjacent faces. For a boundary edge only the angle of the one
+    /// existing face is used.
+    ///
+    /// The cotangents are clamped so nearly degenerate triangles don't produce huge weights.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn cotangent_weight(&self, mesh_graph: &MeshGraph) -> f32 {
+        self.cotangent_weight_inner(mesh_graph).unwrap_or_else(|| {
+            error!("Halfedge invalid. Defaulting to zero weight");
+            0.0
+        })
+    }
+
+    fn cotangent_weight_inner(&self, mesh_graph: &MeshGraph) -> Option<f32> {
+        let twin = mesh_graph.halfedges.get(self.twin?)?;
+
+        let start = *mesh_graph.positions.get(twin.end_vertex)?;
+        let end = *mesh_graph.positions.get(self.end_vertex)?;
+
+        let mut weight = 0.0;
+
+        for he in [self, twin] {
+            if he.is_boundary() {
+                continue;
+            }
+
+            let opposite = *mesh_graph.positions.get(he.opposite_vertex(mesh_graph)?)?;
+            weight += cotangent_at(opposite, start, end);
+        }
+
+        Some(weight * 0.5)
+    }
+
     /// Returns `true` if there is no face adjacent to this halfedge.
     #[inline]
     pub fn is_boundary(&self) -> bool {
@@ -117,7 +150,7 @@ impl Halfedge {
     }
 
     /// Returns `true` if the start or end vertex is a boundary vertex.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn is_adjacent_to_boundary(&self, mesh_graph: &MeshGraph) -> bool {
         if let Some(start_vertex) = self.start_vertex(mesh_graph) {
             mesh_graph
@@ -138,6 +171,46 @@ impl Halfedge {
         }
     }
 
+    /// Signed angle between the normals of the two faces adjacent to this edge.
+    ///
+    /// It is `0` for a flat edge, positive for a convex edge and negative for a concave edge.
+    /// Returns `None` if the edge is a boundary edge.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn dihedral_angle(&self, mesh_graph: &MeshGraph) -> Option<f32> {
+        let twin = mesh_graph
+            .halfedges
+            .get(self.twin.or_else(error_none!("Twin missing"))?)
+            .or_else(error_none!("Twin not found"))?;
+
+        let normal = mesh_graph
+            .faces
+            .get(self.face?)
+            .or_else(error_none!("Face not found"))?
+            .compute_normal(mesh_graph)?;
+        let twin_normal = mesh_graph
+            .faces
+            .get(twin.face?)
+            .or_else(error_none!("Twin face not found"))?
+            .compute_normal(mesh_graph)?;
+
+        let angle = normal.angle_between(twin_normal);
+
+        let start_pos = mesh_graph
+            .positions
+            .get(twin.end_vertex)
+            .or_else(error_none!("Start vertex position not found"))?;
+        let twin_opposite_pos = mesh_graph
+            .positions
+            .get(twin.opposite_vertex(mesh_graph)?)
+            .or_else(error_none!("Opposite vertex position not found"))?;
+
+        if (twin_opposite_pos - start_pos).dot(normal) > 0.0 {
+            Some(-angle)
+        } else {
+            Some(angle)
+        }
+    }
+
     /// Returns the face vertex opposite to this halfedge.
     /// Same as `self.next.end_vertex` (pseudo-code).
     pub fn opposite_vertex(&self, mesh_graph: &MeshGraph) -> Option<VertexId> {
@@ -149,3 +222,40 @@ impl Halfedge {
         Some(next_he.end_vertex)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_cotangent_weight() {
+        // all faces of an icosahedron are equilateral
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 0,
+        });
+
+        let cot_60 = 1.0 / 3.0_f32.sqrt();
+
+        for he in mesh_graph.halfedges.values() {
+            assert!((he.cotangent_weight(&mesh_graph) - cot_60).abs() < 1e-5);
+        }
+
+        let mesh_graph = MeshGraph::indexed_triangles(
+            &[
+                Vec3::ZERO,
+                Vec3::X,
+                Vec3::new(0.5, 3.0_f32.sqrt() * 0.5, 0.0),
+            ],
+            &[0, 1, 2],
+        );
+
+        for he in mesh_graph.halfedges.values() {
+            assert!((he.cotangent_weight(&mesh_graph) - cot_60 * 0.5).abs() < 1e-5);
+        }
+    }
+}
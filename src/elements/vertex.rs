@@ -1,4 +1,7 @@
-use tracing::{error, instrument};
+use crate::utils::error;
+
+use glam::Vec3;
+use hashbrown::HashSet;
 
 use crate::{CircularHalfedgesIterator, MeshGraph, error_none, utils::unwrap_or_return};
 
@@ -23,7 +26,7 @@ impl Vertex {
     /// One of the incoming halfedges of this vertex.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/incoming_halfedge.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn incoming_halfedge(&self, mesh_graph: &MeshGraph) -> Option<HalfedgeId> {
         mesh_graph
             .halfedges
@@ -38,7 +41,7 @@ impl Vertex {
     /// Returns all halfedges that point away from this vertex.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/outgoing_halfedges.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn outgoing_halfedges<'a>(
         &self,
         mesh_graph: &'a MeshGraph,
@@ -60,7 +63,7 @@ impl Vertex {
     /// Returns all halfedges that point towards this vertex
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/incoming_halfedges.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn incoming_halfedges(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = HalfedgeId> {
         self.outgoing_halfedges(mesh_graph).filter_map(|he_id| {
             mesh_graph
@@ -75,7 +78,7 @@ impl Vertex {
     /// Returns all faces incident to this vertex.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/faces.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn faces(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = FaceId> {
         self.outgoing_halfedges(mesh_graph).filter_map(|he| {
             mesh_graph
@@ -86,10 +89,34 @@ impl Vertex {
         })
     }
 
+    /// Returns all faces incident to this vertex together with the interior angle
+    /// of the face at this vertex in radians.
+    ///
+    /// This is the basis for angle weighted normals or the angle deficit (Gaussian curvature).
+    /// See also [`MeshGraph::corners`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn corner_angles<'a>(
+        &self,
+        mesh_graph: &'a MeshGraph,
+    ) -> impl Iterator<Item = (FaceId, f32)> + 'a {
+        self.outgoing_halfedges(mesh_graph).filter_map(|he_id| {
+            let he = mesh_graph
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge is None"))?;
+            let face = he.face?;
+            let incoming = he.prev(mesh_graph)?;
+
+            mesh_graph
+                .corner(face, incoming)
+                .map(|corner| (corner.face, corner.angle))
+        })
+    }
+
     /// Returns all neighbouring (connected through an edge) vertices of this vertex.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/neighbours.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn neighbours(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = VertexId> {
         self.outgoing_halfedges(mesh_graph).filter_map(|he| {
             mesh_graph
@@ -100,15 +127,49 @@ impl Vertex {
         })
     }
 
+    /// Returns all vertices that can be reached from this vertex in at most `n` edge hops,
+    /// excluding this vertex itself.
+    ///
+    /// The 1-ring is the same set as [`Vertex::neighbours`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn n_ring(&self, mesh_graph: &MeshGraph, n: usize) -> HashSet<VertexId> {
+        let Some(center_id) = self
+            .outgoing_halfedge
+            .and_then(|he_id| mesh_graph.halfedges.get(he_id))
+            .and_then(|he| he.start_vertex(mesh_graph))
+        else {
+            return HashSet::new();
+        };
+
+        let mut visited = HashSet::from([center_id]);
+        let mut frontier = vec![center_id];
+
+        for _ in 0..n {
+            frontier = frontier
+                .into_iter()
+                .filter_map(|vertex_id| mesh_graph.vertices.get(vertex_id))
+                .flat_map(|vertex| vertex.neighbours(mesh_graph))
+                .filter(|neighbour_id| visited.insert(*neighbour_id))
+                .collect();
+
+            if frontier.is_empty() {
+                break;
+            }
+        }
+
+        visited.remove(&center_id);
+        visited
+    }
+
     /// The degree of this vertex, i.e., the number of edges incident to it. Sometimes called the valence.
     #[inline]
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn degree(&self, mesh_graph: &MeshGraph) -> usize {
         self.neighbours(mesh_graph).count()
     }
 
     /// Returns true if this vertex is a boundary vertex, i.e., if it is incident to a boundary edge.
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn is_boundary(&self, mesh_graph: &MeshGraph) -> bool {
         if let Some(outgoing_he) = self.outgoing_halfedge {
             mesh_graph
@@ -123,11 +184,92 @@ impl Vertex {
         }
     }
 
+    /// Returns the outgoing halfedge of this vertex that is a boundary halfedge.
+    ///
+    /// For a manifold boundary vertex this halfedge is unique. Returns `None` for interior vertices.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn outgoing_boundary_halfedge(&self, mesh_graph: &MeshGraph) -> Option<HalfedgeId> {
+        let start_he_id = self
+            .outgoing_halfedge
+            .or_else(error_none!("Vertex has no outgoing halfedge"))?;
+
+        // Rotating clockwise stops at the boundary, so we have to rotate counter-clockwise
+        let mut he_id = start_he_id;
+        for _ in 0..mesh_graph.halfedges.len() {
+            let he = mesh_graph
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+
+            if he.is_boundary() {
+                return Some(he_id);
+            }
+
+            he_id = he.ccw_rotated_neighbour(mesh_graph)?;
+
+            if he_id == start_he_id {
+                return None;
+            }
+        }
+
+        error!("Outgoing halfedges don't form a loop");
+        None
+    }
+
+    /// Cotangent weighted Laplacian of the position at this vertex
+    ///
+    /// `Δp = 1 / (2A) * Σ (cot(α) + cot(β)) * (p_j - p)`
+    ///
+    /// where `A` is the Voronoi area of the vertex (Meyer et al. 2003). It points along the
+    /// mean curvature normal and its length is twice the mean curvature.
+    /// Returns `None` for boundary vertices.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn laplacian(&self, mesh_graph: &MeshGraph) -> Option<Vec3> {
+        if self.outgoing_boundary_halfedge(mesh_graph).is_some() {
+            return None;
+        }
+
+        let vertex_id = mesh_graph
+            .halfedges
+            .get(self.incoming_halfedge(mesh_graph)?)
+            .or_else(error_none!("Incoming halfedge not found"))?
+            .end_vertex;
+        let pos = *mesh_graph
+            .positions
+            .get(vertex_id)
+            .or_else(error_none!("Position not found"))?;
+
+        let mut sum = Vec3::ZERO;
+        let mut area = 0.0;
+
+        for he_id in self.outgoing_halfedges(mesh_graph) {
+            let he = mesh_graph
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+            let neighbour_pos = *mesh_graph
+                .positions
+                .get(he.end_vertex)
+                .or_else(error_none!("Neighbour position not found"))?;
+
+            let weight = 2.0 * he.cotangent_weight(mesh_graph);
+
+            sum += weight * (neighbour_pos - pos);
+            area += weight * pos.distance_squared(neighbour_pos) / 8.0;
+        }
+
+        if area <= 0.0 {
+            return None;
+        }
+
+        Some(sum / (2.0 * area))
+    }
+
     /// Returns the halfedges that are opposite to this vertex for every incident face to this vertex.
     /// They are ordered counterclockwise.
     ///
     /// <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/one_ring.svg" alt="Connectivity" style="max-width: 50em" />
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     pub fn one_ring(&self, mesh_graph: &MeshGraph) -> impl Iterator<Item = HalfedgeId> {
         self.incoming_halfedges(mesh_graph)
             .collect::<Vec<_>>()
@@ -160,11 +302,11 @@ impl Vertex {
         );
 
         if !he.is_boundary() {
-            tracing::info!("he {out_he_id:?} is not boundary");
+            crate::utils::info!("he {out_he_id:?} is not boundary");
             return CircularHalfedgesIterator::empty(mesh_graph);
         }
 
-        tracing::info!("he {out_he_id:?} is boundary. {he:#?}");
+        crate::utils::info!("he {out_he_id:?} is boundary. {he:#?}");
 
         CircularHalfedgesIterator::new(
             self.outgoing_halfedge,
@@ -175,24 +317,141 @@ impl Vertex {
                     .get(he_id)
                     .or_else(error_none!("Halfedge not found"))?
                     .end_vertex;
-                let next_he_id = mesh_graph
+
+                mesh_graph
                     .vertices
                     .get(end_v_id)
                     .or_else(error_none!("Vertex not found"))?
-                    .outgoing_halfedge?;
-
-                let next_he = mesh_graph
-                    .halfedges
-                    .get(next_he_id)
-                    .or_else(error_none!("Halfedge not found"))?;
-
-                if next_he.is_boundary() {
-                    Some(next_he_id)
-                } else {
-                    None
-                }
+                    .outgoing_boundary_halfedge(mesh_graph)
             },
             usize::MAX,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::{primitives::IcoSphere, utils::grid};
+
+    use super::*;
+
+    #[test]
+    fn test_outgoing_boundary_halfedge() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let face_id = mesh_graph.faces.keys().next().unwrap();
+        let hole_vertices = mesh_graph.faces[face_id]
+            .vertices(&mesh_graph)
+            .collect::<Vec<_>>();
+        mesh_graph.remove_face(face_id);
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            let boundary_he_id = vertex.outgoing_boundary_halfedge(&mesh_graph);
+
+            if hole_vertices.contains(&vertex_id) {
+                let he = mesh_graph.halfedges[boundary_he_id.unwrap()];
+                assert!(he.is_boundary());
+                assert_eq!(he.start_vertex(&mesh_graph), Some(vertex_id));
+            } else {
+                assert_eq!(boundary_he_id, None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_corner_angles() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        for vertex in mesh_graph.vertices.values() {
+            let corner_angles = vertex.corner_angles(&mesh_graph).collect::<Vec<_>>();
+
+            assert_eq!(
+                corner_angles
+                    .iter()
+                    .map(|(face_id, _)| *face_id)
+                    .collect::<Vec<_>>(),
+                vertex.faces(&mesh_graph).collect::<Vec<_>>()
+            );
+
+            // positive curvature
+            let angle_sum = corner_angles.iter().map(|(_, angle)| angle).sum::<f32>();
+            assert!(angle_sum < TAU);
+            assert!(angle_sum > TAU * 0.95);
+        }
+
+        let (mesh_graph, vertex_ids) = grid(3);
+        let center = mesh_graph.vertices[vertex_ids[4]];
+
+        let angle_sum = center
+            .corner_angles(&mesh_graph)
+            .map(|(_, angle)| angle)
+            .sum::<f32>();
+        assert!((angle_sum - TAU).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_laplacian() {
+        let radius = 2.0;
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius,
+            subdivisions: 3,
+        });
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            let laplacian = vertex.laplacian(&mesh_graph).unwrap();
+            let radial = mesh_graph.positions[vertex_id].normalize();
+
+            assert!(laplacian.normalize().dot(radial) < -0.999);
+            assert!((laplacian.length() - 2.0 / radius).abs() < 0.05 * 2.0 / radius);
+        }
+
+        let face_id = mesh_graph.faces.keys().next().unwrap();
+        let boundary_vertices = mesh_graph.faces[face_id]
+            .vertices(&mesh_graph)
+            .collect::<Vec<_>>();
+        mesh_graph.remove_face(face_id);
+
+        for vertex_id in boundary_vertices {
+            assert_eq!(mesh_graph.vertices[vertex_id].laplacian(&mesh_graph), None);
+        }
+    }
+
+    #[test]
+    fn test_n_ring() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let vertex = mesh_graph
+            .vertices
+            .values()
+            .find(|vertex| vertex.degree(&mesh_graph) == 6)
+            .unwrap();
+
+        let one_ring = vertex.n_ring(&mesh_graph, 1);
+        assert_eq!(one_ring.len(), 6);
+        assert_eq!(
+            one_ring,
+            vertex.neighbours(&mesh_graph).collect::<HashSet<_>>()
+        );
+
+        let two_ring = vertex.n_ring(&mesh_graph, 2);
+        assert!(two_ring.len() > one_ring.len());
+        assert!(two_ring.is_superset(&one_ring));
+
+        assert!(vertex.n_ring(&mesh_graph, 0).is_empty());
+        assert_eq!(
+            vertex.n_ring(&mesh_graph, 100).len(),
+            mesh_graph.vertices.len() - 1
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Geometric helpers for triangles that are shared by several operations.
+
+use glam::Vec3;
+use itertools::Itertools;
+
+/// Computes the barycentric coordinates of `p` with respect to the triangle `tri`.
+///
+/// If `p` doesn't lie in the plane of the triangle, the coordinates of its orthogonal
+/// projection onto the plane are returned. The coordinates always sum up to one and
+/// `tri[0] * u + tri[1] * v + tri[2] * w` gives back the (projected) point.
+///
+/// For degenerate triangles (collinear or coincident vertices) all coordinates are `NaN`.
+pub fn barycentric(p: Vec3, tri: [Vec3; 3]) -> Vec3 {
+    let [a, b, c] = tri;
+
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+
+    let d00 = ab.dot(ab);
+    let d01 = ab.dot(ac);
+    let d11 = ac.dot(ac);
+    let d20 = ap.dot(ab);
+    let d21 = ap.dot(ac);
+
+    let denominator = d00 * d11 - d01 * d01;
+    if denominator <= f32::EPSILON * d00 * d11 {
+        return Vec3::NAN;
+    }
+
+    let v = (d11 * d20 - d01 * d21) / denominator;
+    let w = (d00 * d21 - d01 * d20) / denominator;
+
+    Vec3::new(1.0 - v - w, v, w)
+}
+
+/// Returns `true` if `p` lies inside of the triangle `tri` or within `tolerance` of its edges.
+///
+/// `p` is projected onto the plane of the triangle first, so its distance to that plane is
+/// ignored. Degenerate triangles never contain any point.
+pub fn point_in_triangle(p: Vec3, tri: [Vec3; 3], tolerance: f32) -> bool {
+    let coords = barycentric(p, tri);
+
+    if coords.is_nan() {
+        return false;
+    }
+
+    if coords.min_element() >= 0.0 {
+        return true;
+    }
+
+    let projected = tri[0] * coords.x + tri[1] * coords.y + tri[2] * coords.z;
+
+    tri.iter().circular_tuple_windows().any(|(start, end)| {
+        closest_point_on_segment(projected, *start, *end).distance_squared(projected)
+            <= tolerance * tolerance
+    })
+}
+
+/// Returns the point on the segment from `start` to `end` that is closest to `p`.
+pub fn closest_point_on_segment(p: Vec3, start: Vec3, end: Vec3) -> Vec3 {
+    start.lerp(end, closest_parameter_on_segment(p, start, end))
+}
+
+/// Returns the parameter `t` in `[0, 1]` of the point `start.lerp(end, t)` on the segment from
+/// `start` to `end` that is closest to `p`. For a degenerate segment this is `0`.
+pub fn closest_parameter_on_segment(p: Vec3, start: Vec3, end: Vec3) -> f32 {
+    let dir = end - start;
+    let len_sqr = dir.length_squared();
+
+    if len_sqr <= f32::EPSILON {
+        return 0.0;
+    }
+
+    ((p - start).dot(dir) / len_sqr).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRI: [Vec3; 3] = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 1.0),
+    ];
+
+    #[test]
+    fn test_barycentric() {
+        assert!(barycentric(TRI[0], TRI).distance(Vec3::X) < 1e-6);
+        assert!(barycentric(TRI[1], TRI).distance(Vec3::Y) < 1e-6);
+        assert!(barycentric(TRI[2], TRI).distance(Vec3::Z) < 1e-6);
+
+        let centroid = (TRI[0] + TRI[1] + TRI[2]) / 3.0;
+        assert!(barycentric(centroid, TRI).distance(Vec3::splat(1.0 / 3.0)) < 1e-6);
+
+        // points off the plane are projected
+        let normal = (TRI[1] - TRI[0]).cross(TRI[2] - TRI[0]).normalize();
+        assert!(barycentric(centroid + normal * 5.0, TRI).distance(Vec3::splat(1.0 / 3.0)) < 1e-5);
+
+        let outside = barycentric(Vec3::new(-1.0, -1.0, 0.0), TRI);
+        assert!(outside.min_element() < 0.0);
+        assert!((outside.element_sum() - 1.0).abs() < 1e-6);
+
+        let degenerate = [Vec3::ZERO, Vec3::X, Vec3::X * 2.0];
+        assert!(barycentric(Vec3::X, degenerate).is_nan());
+    }
+
+    #[test]
+    fn test_point_in_triangle() {
+        let centroid = (TRI[0] + TRI[1] + TRI[2]) / 3.0;
+        let normal = (TRI[1] - TRI[0]).cross(TRI[2] - TRI[0]).normalize();
+
+        assert!(point_in_triangle(centroid, TRI, 0.0));
+        assert!(point_in_triangle(centroid + normal, TRI, 0.0));
+        assert!(point_in_triangle(TRI[1], TRI, 0.0));
+
+        // just outside of the edge from the first to the second vertex
+        let p = Vec3::new(1.0, -0.01, 0.0);
+        assert!(!point_in_triangle(p, TRI, 0.0));
+        assert!(point_in_triangle(p, TRI, 0.02));
+
+        assert!(!point_in_triangle(Vec3::new(3.0, 3.0, 0.0), TRI, 0.1));
+
+        let degenerate = [Vec3::ZERO, Vec3::X, Vec3::X * 2.0];
+        assert!(!point_in_triangle(Vec3::X, degenerate, 1.0));
+    }
+
+    #[test]
+    fn test_closest_point_on_segment() {
+        let (start, end) = (Vec3::ZERO, Vec3::new(4.0, 0.0, 0.0));
+
+        assert_eq!(
+            closest_parameter_on_segment(Vec3::new(1.0, 2.0, 0.0), start, end),
+            0.25
+        );
+        assert_eq!(
+            closest_point_on_segment(Vec3::new(1.0, 2.0, 0.0), start, end),
+            Vec3::X
+        );
+
+        // clamped to the end points
+        assert_eq!(closest_point_on_segment(Vec3::NEG_X, start, end), start);
+        assert_eq!(closest_point_on_segment(Vec3::X * 5.0, start, end), end);
+
+        assert_eq!(closest_parameter_on_segment(Vec3::ONE, start, start), 0.0);
+    }
+}
@@ -1,29 +1,227 @@
+use anyhow::{Result, anyhow, bail};
 use bevy::{
     asset::RenderAssetUsages,
     mesh::{Indices, PrimitiveTopology},
     prelude::*,
 };
+// the glam version of the crate which might differ from the one in the Bevy prelude
+use glam::Vec3;
+use itertools::Itertools;
 
 use crate::MeshGraph;
 
-use super::VertexIndexBuffers;
+use super::{VertexIndexBuffers, smooth_normals, tangents};
 
-impl From<MeshGraph> for Mesh {
-    fn from(mesh_graph: MeshGraph) -> Self {
-        let buffers: VertexIndexBuffers = mesh_graph.into();
+impl MeshGraph {
+    /// Converts the mesh graph into a Bevy mesh with the [`PrimitiveTopology::TriangleList`]
+    /// topology and `u32` indices (see [`VertexIndexBuffers`]).
+    ///
+    /// If [`MeshGraph::vertex_normals`] is `None`, smooth normals are computed from the
+    /// faces. [`MeshGraph::uvs`] are written to [`Mesh::ATTRIBUTE_UV_0`] together with the
+    /// tangents computed from them if present.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn to_bevy_mesh(&self) -> Mesh {
+        let (positions, mut normals, indices, vertex_id_to_index) =
+            VertexIndexBuffers::<()>::attrs_from_mesh_graph(self);
 
-        Mesh::new(
+        if normals.len() != positions.len() {
+            normals = smooth_normals(self, positions.len(), &vertex_id_to_index);
+        }
+        let tangents = tangents(self, &positions, &normals, &indices, &vertex_id_to_index);
+
+        let mut mesh = Mesh::new(
             PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         )
-        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, buffers.positions)
-        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, buffers.normals)
-        .with_inserted_indices(Indices::U32(buffers.indices))
-
-        // TODO ?
-        // let vertex_count = buffers.positions.len();
-        // if buffers.uvs.len() == vertex_count {
-        //     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, buffers.uvs);
-        // }
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            positions.iter().map(|pos| pos.to_array()).collect_vec(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            normals.iter().map(|normal| normal.to_array()).collect_vec(),
+        )
+        .with_inserted_indices(Indices::U32(indices));
+
+        if !tangents.is_empty() {
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_TANGENT,
+                tangents
+                    .iter()
+                    .map(|tangent| tangent.to_array())
+                    .collect_vec(),
+            );
+        }
+
+        if let Some(uvs) = &self.uvs {
+            let mut uv_buffer = vec![[0.0f32; 2]; positions.len()];
+
+            for (vertex_id, uv) in uvs {
+                if let Some(index) = vertex_id_to_index.get(vertex_id) {
+                    uv_buffer[*index as usize] = uv.to_array();
+                }
+            }
+
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uv_buffer);
+        }
+
+        mesh
+    }
+}
+
+impl From<&MeshGraph> for Mesh {
+    fn from(mesh_graph: &MeshGraph) -> Self {
+        mesh_graph.to_bevy_mesh()
+    }
+}
+
+impl From<MeshGraph> for Mesh {
+    fn from(mesh_graph: MeshGraph) -> Self {
+        mesh_graph.to_bevy_mesh()
+    }
+}
+
+impl TryFrom<&Mesh> for MeshGraph {
+    type Error = anyhow::Error;
+
+    /// Converts a Bevy mesh with the [`PrimitiveTopology::TriangleList`] topology.
+    ///
+    /// With indices, every vertex of the Bevy mesh becomes a vertex of the mesh graph and
+    /// [`Mesh::ATTRIBUTE_NORMAL`] is stored in [`MeshGraph::vertex_normals`]. Without indices,
+    /// coincident positions are merged with [`MeshGraph::triangles`] and, if the Bevy mesh has
+    /// normals, the vertex normals are computed from the faces.
+    fn try_from(mesh: &Mesh) -> Result<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            bail!(
+                "Only triangle lists are supported, got {:?}",
+                mesh.primitive_topology()
+            );
+        }
+
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|values| values.as_float3())
+            .ok_or(anyhow!("Mesh has no Float32x3 positions"))?
+            .iter()
+            .map(|pos| Vec3::from(*pos))
+            .collect_vec();
+        let normals = mesh
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|values| values.as_float3());
+
+        let Some(indices) = mesh.indices() else {
+            let mut mesh_graph = MeshGraph::triangles(&positions);
+
+            if normals.is_some() {
+                mesh_graph.compute_vertex_normals();
+            }
+
+            return Ok(mesh_graph);
+        };
+
+        let indices = indices.iter().collect_vec();
+        if let Some(index) = indices.iter().find(|index| **index >= positions.len()) {
+            bail!("Vertex index {index} out of range");
+        }
+
+        let (mut mesh_graph, vertex_ids) =
+            MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+        if let Some(normals) = normals {
+            mesh_graph.vertex_normals = Some(
+                vertex_ids
+                    .into_iter()
+                    .zip(normals.iter().map(|normal| Vec3::from(*normal)))
+                    .collect(),
+            );
+        }
+
+        Ok(mesh_graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_to_bevy_mesh() {
+        let mut sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let mesh = sphere.to_bevy_mesh();
+
+        assert_eq!(mesh.count_vertices(), sphere.vertices.len());
+        assert!(
+            matches!(mesh.indices(), Some(Indices::U32(indices)) if indices.len() == sphere.faces.len() * 3)
+        );
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_none());
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_none());
+
+        // smooth normals are computed if the mesh graph has none
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|values| values.as_float3())
+            .unwrap();
+        let normals = mesh
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|values| values.as_float3())
+            .unwrap();
+        assert_eq!(normals.len(), positions.len());
+        for (pos, normal) in positions.iter().zip(normals) {
+            assert!(Vec3::from(*normal).dot(Vec3::from(*pos)) > 0.95);
+        }
+
+        sphere.compute_vertex_normals();
+        sphere.uvs = Some(
+            sphere
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, pos.truncate()))
+                .collect(),
+        );
+
+        let mesh = Mesh::from(&sphere);
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_UV_0).is_some());
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_some());
+
+        let round_trip = MeshGraph::try_from(&mesh).unwrap();
+        assert_eq!(round_trip.vertices.len(), sphere.vertices.len());
+        assert_eq!(round_trip.faces.len(), sphere.faces.len());
+        assert!(round_trip.is_closed());
+    }
+
+    #[test]
+    fn test_mesh_graph_from_bevy_mesh() {
+        let mesh = Mesh::from(Cuboid::new(1.0, 2.0, 3.0));
+
+        let mesh_graph = MeshGraph::try_from(&mesh).unwrap();
+
+        assert_eq!(mesh_graph.faces.len(), 12);
+        assert_eq!(mesh_graph.vertices.len(), mesh.count_vertices());
+        assert!((mesh_graph.signed_volume() - 6.0).abs() < 1e-5);
+
+        let vertex_normals = mesh_graph.vertex_normals.as_ref().unwrap();
+        assert_eq!(vertex_normals.len(), mesh_graph.vertices.len());
+        for (vertex_id, normal) in vertex_normals {
+            assert!(normal.dot(mesh_graph.positions[vertex_id]) > 0.0);
+        }
+
+        // without indices coincident corners are merged
+        let mut non_indexed = mesh.clone();
+        non_indexed.duplicate_vertices();
+        let welded = MeshGraph::try_from(&non_indexed).unwrap();
+
+        assert_eq!(welded.faces.len(), 12);
+        assert_eq!(welded.vertices.len(), 8);
+        assert!(welded.is_closed());
+        assert!(welded.vertex_normals.is_some());
+
+        let lines = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+        assert!(MeshGraph::try_from(&lines).is_err());
     }
 }
@@ -1,12 +1,19 @@
 use std::path::Path;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
+use glam::{Mat4, Vec2, Vec3};
 use gltf::{Semantic, accessor::Item};
 use itertools::Itertools;
 use slotmap::SecondaryMap;
 
-use crate::{MeshGraph, VertexId};
+use crate::{MeshGraph, VertexId, integrations::VertexIndexBuffers};
 
+/// Loads the first primitive of the first mesh of a glTF file.
+///
+/// Besides positions and indices, the `NORMAL` attribute is stored in
+/// [`MeshGraph::vertex_normals`] and the `TEXCOORD_0` attribute in [`MeshGraph::uvs`] if they
+/// are present. The global transform of the first node in the scene that references the mesh
+/// is baked into the positions and normals.
 pub fn load(file: impl AsRef<Path>) -> Result<MeshGraph> {
     let (document, buffers, _) = gltf::import(&file).context("Failed to load GLTF file")?;
 
@@ -29,10 +36,62 @@ pub fn load(file: impl AsRef<Path>) -> Result<MeshGraph> {
         .map(|i| i as usize)
         .collect::<Vec<_>>();
 
-    Ok(MeshGraph::indexed_triangles(
+    let (mut mesh_graph, vertex_ids) = MeshGraph::indexed_triangles_and_vertex_ids(
         &pos_iter.map(|p| p.into()).collect_vec(),
         &indices,
-    ))
+    );
+
+    if let Some(normals) = reader.read_normals() {
+        mesh_graph.vertex_normals = Some(
+            vertex_ids
+                .iter()
+                .copied()
+                .zip(normals.map(Vec3::from))
+                .collect(),
+        );
+    }
+
+    if let Some(uvs) = reader.read_tex_coords(0) {
+        mesh_graph.uvs = Some(
+            vertex_ids
+                .iter()
+                .copied()
+                .zip(uvs.into_f32().map(Vec2::from))
+                .collect(),
+        );
+    }
+
+    let transform = mesh_transform(&document, mesh.index());
+    if transform != Mat4::IDENTITY {
+        mesh_graph.transform(transform);
+    }
+
+    Ok(mesh_graph)
+}
+
+/// Global transform of the first node of the default (or first) scene that references the mesh
+/// with `mesh_index`. Returns the identity if there is no such node.
+fn mesh_transform(document: &gltf::Document, mesh_index: usize) -> Mat4 {
+    fn find(node: gltf::Node, parent_transform: Mat4, mesh_index: usize) -> Option<Mat4> {
+        let transform = parent_transform * Mat4::from_cols_array_2d(&node.transform().matrix());
+
+        if node.mesh().is_some_and(|mesh| mesh.index() == mesh_index) {
+            return Some(transform);
+        }
+
+        node.children()
+            .find_map(|child| find(child, transform, mesh_index))
+    }
+
+    document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .and_then(|scene| {
+            scene
+                .nodes()
+                .find_map(|node| find(node, Mat4::IDENTITY, mesh_index))
+        })
+        .unwrap_or(Mat4::IDENTITY)
 }
 
 pub fn load_with_custom_attribute<T>(
@@ -80,3 +139,254 @@ where
         &extras,
     ))
 }
+
+/// Saves the mesh graph as a binary glTF (`.glb`) file. See [`to_glb`].
+pub fn save(mesh_graph: &MeshGraph, file: impl AsRef<Path>) -> Result<()> {
+    std::fs::write(&file, to_glb(mesh_graph)?).context("Failed to write GLB file")
+}
+
+/// Writes the mesh graph as a binary glTF (`.glb`) with a single mesh primitive.
+///
+/// The primitive has the `POSITION` attribute, the `NORMAL` attribute if
+/// [`MeshGraph::vertex_normals`] is `Some` and `u32` triangle indices
+/// (see [`VertexIndexBuffers`]). Fails if the mesh has no vertices because glTF doesn't allow
+/// empty accessors.
+pub fn to_glb(mesh_graph: &MeshGraph) -> Result<Vec<u8>> {
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+
+    let buffers = VertexIndexBuffers::from(mesh_graph);
+
+    if buffers.positions.is_empty() {
+        bail!("Can't save a mesh without vertices as glTF");
+    }
+
+    let mut bin = vec![];
+    let mut buffer_views = vec![];
+    let mut accessors = vec![];
+    let mut attributes = vec![];
+
+    let mut push_vec3s = |name: &str, values: &[Vec3], bin: &mut Vec<u8>| {
+        let min = values.iter().copied().fold(Vec3::INFINITY, Vec3::min);
+        let max = values.iter().copied().fold(Vec3::NEG_INFINITY, Vec3::max);
+
+        buffer_views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{ARRAY_BUFFER}}}"#,
+            bin.len(),
+            values.len() * 12
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{FLOAT},"count":{},"type":"VEC3","min":[{}],"max":[{}]}}"#,
+            buffer_views.len() - 1,
+            values.len(),
+            min.to_array().iter().join(","),
+            max.to_array().iter().join(",")
+        ));
+        attributes.push(format!(r#""{name}":{}"#, accessors.len() - 1));
+
+        for value in values.iter().flat_map(|vec| vec.to_array()) {
+            bin.extend(value.to_le_bytes());
+        }
+    };
+
+    push_vec3s("POSITION", &buffers.positions, &mut bin);
+    if !buffers.normals.is_empty() {
+        push_vec3s("NORMAL", &buffers.normals, &mut bin);
+    }
+
+    let mut primitive = format!(r#""attributes":{{{}}},"mode":4"#, attributes.join(","));
+
+    if !buffers.indices.is_empty() {
+        buffer_views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{ELEMENT_ARRAY_BUFFER}}}"#,
+            bin.len(),
+            buffers.indices.len() * 4
+        ));
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#,
+            buffer_views.len() - 1,
+            buffers.indices.len()
+        ));
+        primitive.push_str(&format!(r#","indices":{}"#, accessors.len() - 1));
+
+        for index in &buffers.indices {
+            bin.extend(index.to_le_bytes());
+        }
+    }
+
+    let json = format!(
+        r#"{{"asset":{{"version":"2.0","generator":"mesh-graph"}},"scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"meshes":[{{"primitives":[{{{primitive}}}]}}],"accessors":[{}],"bufferViews":[{}],"buffers":[{{"byteLength":{}}}]}}"#,
+        accessors.join(","),
+        buffer_views.join(","),
+        bin.len()
+    );
+
+    Ok(glb_container(json, bin))
+}
+
+/// Packs the JSON and the binary buffer into the chunks of a `.glb` file.
+fn glb_container(json: String, mut bin: Vec<u8>) -> Vec<u8> {
+    // chunks have to be 4 byte aligned, JSON is padded with spaces and binary data with zeros
+    let mut json = json.into_bytes();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend(b"glTF");
+    glb.extend(2u32.to_le_bytes());
+    glb.extend((total_length as u32).to_le_bytes());
+
+    glb.extend((json.len() as u32).to_le_bytes());
+    glb.extend(b"JSON");
+    glb.extend(json);
+
+    glb.extend((bin.len() as u32).to_le_bytes());
+    glb.extend(b"BIN\0");
+    glb.extend(bin);
+
+    glb
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let mut sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+        sphere.compute_vertex_normals();
+
+        let file =
+            std::env::temp_dir().join(format!("mesh_graph_round_trip_{}.glb", std::process::id()));
+        save(&sphere, &file).unwrap();
+
+        let (document, _, _) = gltf::import(&file).unwrap();
+        let primitive = document
+            .meshes()
+            .next()
+            .unwrap()
+            .primitives()
+            .next()
+            .unwrap();
+        let position_accessor = primitive.get(&Semantic::Positions).unwrap();
+        assert_eq!(position_accessor.count(), sphere.vertices.len());
+        assert!(primitive.get(&Semantic::Normals).is_some());
+
+        let aabb = sphere.aabb();
+        let min = position_accessor.min().unwrap();
+        let max = position_accessor.max().unwrap();
+        for i in 0..3 {
+            assert_eq!(min[i].as_f64().unwrap() as f32, aabb.mins[i]);
+            assert_eq!(max[i].as_f64().unwrap() as f32, aabb.maxs[i]);
+        }
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.vertices.len(), sphere.vertices.len());
+        assert_eq!(loaded.faces.len(), sphere.faces.len());
+        assert!(loaded.is_closed());
+
+        assert!(to_glb(&MeshGraph::new()).is_err());
+    }
+
+    #[test]
+    fn test_load_normals() {
+        let mut sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+        sphere.compute_vertex_normals();
+
+        let file =
+            std::env::temp_dir().join(format!("mesh_graph_normals_{}.glb", std::process::id()));
+        save(&sphere, &file).unwrap();
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(loaded.vertex_normals.is_some());
+        assert!(loaded.uvs.is_none());
+
+        for (vertex_id, normal) in loaded.vertex_normals.as_ref().unwrap() {
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(normal.dot(loaded.positions[vertex_id]) > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_load_uvs_and_node_transform() {
+        // a unit quad with normals and UVs in a translated node below a scaled node
+        let positions = [
+            [0.0f32, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let normals = [[0.0f32, 0.0, 1.0]; 4];
+        let uvs = [[0.0f32, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        let indices = [0u32, 1, 2, 0, 2, 3];
+
+        let mut bin = vec![];
+        bin.extend(positions.iter().flatten().flat_map(|v| v.to_le_bytes()));
+        bin.extend(normals.iter().flatten().flat_map(|v| v.to_le_bytes()));
+        bin.extend(uvs.iter().flatten().flat_map(|v| v.to_le_bytes()));
+        bin.extend(indices.iter().flat_map(|i| i.to_le_bytes()));
+
+        let json = format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "scene": 0,
+                "scenes": [{{"nodes": [0]}}],
+                "nodes": [
+                    {{"children": [1], "scale": [2, 2, 2]}},
+                    {{"mesh": 0, "translation": [0, 0, 5]}}
+                ],
+                "meshes": [{{"primitives": [{{
+                    "attributes": {{"POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2}},
+                    "indices": 3
+                }}]}}],
+                "accessors": [
+                    {{"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0, 0, 0], "max": [1, 1, 0]}},
+                    {{"bufferView": 1, "componentType": 5126, "count": 4, "type": "VEC3"}},
+                    {{"bufferView": 2, "componentType": 5126, "count": 4, "type": "VEC2"}},
+                    {{"bufferView": 3, "componentType": 5125, "count": 6, "type": "SCALAR"}}
+                ],
+                "bufferViews": [
+                    {{"buffer": 0, "byteOffset": 0, "byteLength": 48}},
+                    {{"buffer": 0, "byteOffset": 48, "byteLength": 48}},
+                    {{"buffer": 0, "byteOffset": 96, "byteLength": 32}},
+                    {{"buffer": 0, "byteOffset": 128, "byteLength": 24}}
+                ],
+                "buffers": [{{"byteLength": {}}}]
+            }}"#,
+            bin.len()
+        );
+
+        let file = std::env::temp_dir().join(format!("mesh_graph_uvs_{}.glb", std::process::id()));
+        std::fs::write(&file, glb_container(json, bin)).unwrap();
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.vertices.len(), 4);
+        assert_eq!(loaded.faces.len(), 2);
+
+        let vertex_normals = loaded.vertex_normals.as_ref().unwrap();
+        let loaded_uvs = loaded.uvs.as_ref().unwrap();
+
+        for (vertex_id, pos) in &loaded.positions {
+            assert_eq!(pos.z, 10.0);
+            assert!(vertex_normals[vertex_id].distance(Vec3::Z) < 1e-5);
+            // the UVs were the unscaled x and y coordinates
+            assert_eq!(loaded_uvs[vertex_id], pos.truncate() / 2.0);
+        }
+    }
+}
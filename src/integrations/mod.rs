@@ -1,16 +1,22 @@
 use std::iter::repeat_n;
 
-use glam::Vec3;
+use crate::utils::error;
+use glam::{Vec2, Vec3, Vec4};
 use hashbrown::HashMap;
 use slotmap::SecondaryMap;
-use tracing::{error, instrument};
 
-use crate::{MeshGraph, VertexId};
+use crate::{MeshGraph, NormalWeighting, VertexId};
 
 #[cfg(feature = "bevy")]
 pub mod bevy;
 #[cfg(feature = "gltf")]
 pub mod gltf;
+#[cfg(feature = "obj")]
+pub mod obj;
+#[cfg(feature = "ply")]
+pub mod ply;
+#[cfg(feature = "stl")]
+pub mod stl;
 
 /// Classical indexed mesh representation
 #[derive(Clone, Debug)]
@@ -22,6 +28,9 @@ pub struct VertexIndexBuffers<T = ()> {
     /// Indices: 3*N where N is the number of triangles. Indices point to
     /// elements of `positions` and `normals`.
     pub indices: Vec<u32>,
+    /// Vertex tangents for normal mapping, one per vertex. `w` is the handedness of the
+    /// bitangent (`±1`). Empty if [`MeshGraph::uvs`] is `None`.
+    pub tangents: Vec<Vec4>,
     /// Potential custom vertex attribute(s)
     pub custom_vertex_attribute: Vec<T>,
 }
@@ -43,10 +52,19 @@ where
             }
         }
 
+        let tangents = tangents(
+            mesh_graph,
+            &positions,
+            &normals,
+            &indices,
+            &vertex_id_to_index,
+        );
+
         Self {
             positions,
             normals,
             indices,
+            tangents,
             custom_vertex_attribute,
         }
     }
@@ -91,15 +109,172 @@ where
 }
 
 impl From<&MeshGraph> for VertexIndexBuffers {
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     fn from(mesh_graph: &MeshGraph) -> VertexIndexBuffers {
-        let (positions, normals, indices, _) = Self::attrs_from_mesh_graph(mesh_graph);
+        let (positions, normals, indices, vertex_id_to_index) =
+            Self::attrs_from_mesh_graph(mesh_graph);
+        let tangents = tangents(
+            mesh_graph,
+            &positions,
+            &normals,
+            &indices,
+            &vertex_id_to_index,
+        );
 
         VertexIndexBuffers {
             indices,
             positions,
             normals,
+            tangents,
             custom_vertex_attribute: vec![],
         }
     }
 }
+
+/// Computes the tangents of the vertices from the UVs of the faces with Lengyel's method.
+///
+/// The tangents of all faces around a vertex are accumulated, then orthogonalized against the
+/// vertex normal. `w` is `-1` if the UVs are mirrored. Returns an empty list if the mesh graph
+/// has no UVs. If `normals` is empty, the normals of [`MeshGraph::compute_vertex_normals`] are used.
+fn tangents(
+    mesh_graph: &MeshGraph,
+    positions: &[Vec3],
+    normals: &[Vec3],
+    indices: &[u32],
+    vertex_id_to_index: &SecondaryMap<VertexId, u32>,
+) -> Vec<Vec4> {
+    let Some(uvs) = &mesh_graph.uvs else {
+        return vec![];
+    };
+
+    let mut uv_buffer = vec![Vec2::ZERO; positions.len()];
+    for (vertex_id, uv) in uvs {
+        if let Some(index) = vertex_id_to_index.get(vertex_id) {
+            uv_buffer[*index as usize] = *uv;
+        }
+    }
+
+    let smooth;
+    let normals = if normals.len() == positions.len() {
+        normals
+    } else {
+        smooth = smooth_normals(mesh_graph, positions.len(), vertex_id_to_index);
+        &smooth
+    };
+
+    let mut tangent_sums = vec![Vec3::ZERO; positions.len()];
+    let mut bitangent_sums = vec![Vec3::ZERO; positions.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| index as usize);
+
+        let edge1 = positions[b] - positions[a];
+        let edge2 = positions[c] - positions[a];
+        let delta_uv1 = uv_buffer[b] - uv_buffer[a];
+        let delta_uv2 = uv_buffer[c] - uv_buffer[a];
+
+        // only skip degenerate UV triangles since tiny UV triangles are perfectly valid
+        let inv_determinant = delta_uv1.perp_dot(delta_uv2).recip();
+        if !inv_determinant.is_finite() {
+            continue;
+        }
+
+        let tangent = (edge1 * delta_uv2.y - edge2 * delta_uv1.y) * inv_determinant;
+        let bitangent = (edge2 * delta_uv1.x - edge1 * delta_uv2.x) * inv_determinant;
+
+        for index in [a, b, c] {
+            tangent_sums[index] += tangent;
+            bitangent_sums[index] += bitangent;
+        }
+    }
+
+    normals
+        .iter()
+        .zip(tangent_sums)
+        .zip(bitangent_sums)
+        .map(|((normal, tangent), bitangent)| {
+            let tangent = (tangent - *normal * normal.dot(tangent)).normalize_or_zero();
+            let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+
+            tangent.extend(handedness)
+        })
+        .collect()
+}
+
+/// The normals of [`MeshGraph::compute_vertex_normals`] in the order of the vertex buffer
+fn smooth_normals(
+    mesh_graph: &MeshGraph,
+    vertex_count: usize,
+    vertex_id_to_index: &SecondaryMap<VertexId, u32>,
+) -> Vec<Vec3> {
+    let mut normals = vec![Vec3::ZERO; vertex_count];
+
+    for (vertex_id, normal) in mesh_graph.weighted_vertex_normals(NormalWeighting::Area) {
+        if let Some(index) = vertex_id_to_index.get(vertex_id) {
+            normals[*index as usize] = normal;
+        }
+    }
+
+    normals
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use crate::primitives::Grid;
+
+    use super::*;
+
+    /// Grid in the XZ plane facing +Y whose UVs are its X and -Z coordinates times `uv_scale`
+    fn uv_grid(uv_scale: Vec2) -> MeshGraph {
+        let mut mesh_graph = MeshGraph::from(Grid {
+            size: Vec2::splat(4.0),
+            subdivisions: UVec2::splat(4),
+        });
+
+        mesh_graph.uvs = Some(
+            mesh_graph
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, Vec2::new(pos.x, -pos.z) * uv_scale))
+                .collect(),
+        );
+
+        mesh_graph
+    }
+
+    #[test]
+    fn test_tangents() {
+        let mesh_graph = uv_grid(Vec2::splat(0.25));
+        let buffers = VertexIndexBuffers::from(&mesh_graph);
+
+        assert_eq!(buffers.tangents.len(), buffers.positions.len());
+        for tangent in &buffers.tangents {
+            assert!(tangent.truncate().dot(Vec3::X) > 0.99);
+            assert_eq!(tangent.w, 1.0);
+        }
+
+        // mirrored UVs flip the tangent and the handedness
+        let mirrored = VertexIndexBuffers::from(&uv_grid(Vec2::new(-0.25, 0.25)));
+        for tangent in &mirrored.tangents {
+            assert!(tangent.truncate().dot(Vec3::NEG_X) > 0.99);
+            assert_eq!(tangent.w, -1.0);
+        }
+
+        // a very fine UV mapping still has tangents
+        let fine = VertexIndexBuffers::from(&uv_grid(Vec2::splat(1e-4)));
+        for tangent in &fine.tangents {
+            assert!(tangent.truncate().dot(Vec3::X) > 0.99);
+            assert_eq!(tangent.w, 1.0);
+        }
+
+        let mut without_uvs = mesh_graph;
+        without_uvs.uvs = None;
+        assert!(VertexIndexBuffers::from(&without_uvs).tangents.is_empty());
+    }
+}
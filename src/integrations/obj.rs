@@ -0,0 +1,296 @@
+use std::{fmt::Write, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+use glam::Vec3;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::{MeshGraph, VertexId, utils::error};
+
+/// Loads a Wavefront OBJ file. See [`parse`].
+pub fn load(file: impl AsRef<Path>) -> Result<MeshGraph> {
+    let source = std::fs::read_to_string(&file).context("Failed to read OBJ file")?;
+    parse(&source)
+}
+
+/// Parses the contents of a Wavefront OBJ file.
+///
+/// Only the `v`, `vn` and `f` statements are used, everything else is ignored. Faces with
+/// more than three vertices are triangulated as a fan. Face vertices can be given as `v`,
+/// `v/vt`, `v//vn` or `v/vt/vn` and indices can be negative to refer to the most recently
+/// defined elements.
+///
+/// If the faces reference normals, the normals of every vertex are averaged and stored in
+/// [`MeshGraph::vertex_normals`].
+pub fn parse(source: &str) -> Result<MeshGraph> {
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut indices = vec![];
+    let mut normal_sums = vec![];
+
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("v") => positions.push(
+                parse_vec3(tokens)
+                    .with_context(|| format!("Invalid vertex in line {line_number}"))?,
+            ),
+            Some("vn") => normals.push(
+                parse_vec3(tokens)
+                    .with_context(|| format!("Invalid normal in line {line_number}"))?,
+            ),
+            Some("f") => {
+                let corners = tokens
+                    .map(|token| parse_face_vertex(token, positions.len(), normals.len()))
+                    .collect::<Result<Vec<_>>>()
+                    .with_context(|| format!("Invalid face in line {line_number}"))?;
+
+                if corners.len() < 3 {
+                    bail!("Face with less than three vertices in line {line_number}");
+                }
+
+                for (position_index, normal_index) in &corners {
+                    if let Some(normal_index) = normal_index {
+                        normal_sums.resize(positions.len(), Vec3::ZERO);
+                        normal_sums[*position_index] += normals[*normal_index];
+                    }
+                }
+
+                for (b, c) in corners[1..].iter().tuple_windows() {
+                    indices.extend([corners[0].0, b.0, c.0]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (mut mesh_graph, vertex_ids) =
+        MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+    if !normal_sums.is_empty() {
+        let vertex_normals = vertex_ids
+            .iter()
+            .zip(normal_sums)
+            .filter_map(|(vertex_id, normal)| Some((*vertex_id, normal.try_normalize()?)))
+            .collect::<SecondaryMap<_, _>>();
+
+        mesh_graph.vertex_normals = Some(vertex_normals);
+    }
+
+    Ok(mesh_graph)
+}
+
+/// Saves the mesh graph as a Wavefront OBJ file. See [`to_obj_string`].
+pub fn save(mesh_graph: &MeshGraph, file: impl AsRef<Path>) -> Result<()> {
+    std::fs::write(&file, to_obj_string(mesh_graph)).context("Failed to write OBJ file")
+}
+
+/// Writes the mesh graph in the Wavefront OBJ format.
+///
+/// Every vertex is written as a `v` line and, if [`MeshGraph::vertex_normals`] is `Some`, also
+/// as a `vn` line with the same index. The vertices of the `f` lines are in the order of the
+/// halfedges of the face, i.e. counter-clockwise when looking at the front of the face.
+pub fn to_obj_string(mesh_graph: &MeshGraph) -> String {
+    let mut obj = String::new();
+
+    let mut vertex_id_to_index = SecondaryMap::<VertexId, usize>::new();
+
+    for (vertex_id, pos) in &mesh_graph.positions {
+        vertex_id_to_index.insert(vertex_id, vertex_id_to_index.len() + 1);
+        // writing to a string can't fail
+        let _ = writeln!(obj, "v {} {} {}", pos.x, pos.y, pos.z);
+    }
+
+    let vertex_normals = mesh_graph.vertex_normals.as_ref();
+    if let Some(vertex_normals) = vertex_normals {
+        for vertex_id in mesh_graph.positions.keys() {
+            let normal = vertex_normals.get(vertex_id).copied().unwrap_or_else(|| {
+                error!("Normal not found");
+                Vec3::ZERO
+            });
+            let _ = writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z);
+        }
+    }
+
+    for face in mesh_graph.faces.values() {
+        let Some(indices) = face
+            .vertices(mesh_graph)
+            .map(|vertex_id| vertex_id_to_index.get(vertex_id).copied())
+            .collect::<Option<Vec<_>>>()
+        else {
+            error!("Vertex of face {:?} not found", face.id);
+            continue;
+        };
+
+        let corners = indices
+            .iter()
+            .map(|index| {
+                if vertex_normals.is_some() {
+                    format!("{index}//{index}")
+                } else {
+                    index.to_string()
+                }
+            })
+            .join(" ");
+        let _ = writeln!(obj, "f {corners}");
+    }
+
+    obj
+}
+
+fn parse_vec3<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Result<Vec3> {
+    let mut coordinate = || -> Result<f32> {
+        Ok(tokens
+            .next()
+            .ok_or(anyhow!("Missing coordinate"))?
+            .parse::<f32>()?)
+    };
+
+    Ok(Vec3::new(coordinate()?, coordinate()?, coordinate()?))
+}
+
+/// Parses a face vertex like `1`, `1/2`, `1//3` or `1/2/3` into the zero based position index
+/// and normal index (if any).
+fn parse_face_vertex(
+    token: &str,
+    position_count: usize,
+    normal_count: usize,
+) -> Result<(usize, Option<usize>)> {
+    let mut parts = token.split('/');
+
+    let position_index = resolve_index(
+        parts.next().ok_or(anyhow!("Missing vertex index"))?,
+        position_count,
+    )?;
+
+    let normal_index = match parts.nth(1) {
+        Some(part) if !part.is_empty() => Some(resolve_index(part, normal_count)?),
+        _ => None,
+    };
+
+    Ok((position_index, normal_index))
+}
+
+/// Converts a one based or negative (relative to the end) OBJ index into a zero based index.
+fn resolve_index(part: &str, count: usize) -> Result<usize> {
+    let index = part.parse::<isize>()?;
+
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as isize + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= count as isize {
+        bail!("Index {index} out of range");
+    }
+
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    const CUBE: &str = "\
+# unit cube
+o cube
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 1 1 1
+v 0 1 1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 -1
+vn 0 0 1
+vn 0 -1 0
+vn 0 1 0
+vn -1 0 0
+vn 1 0 0
+s off
+f 1/1/1 4/4/1 3/3/1 2/2/1
+f 5/1/2 6/2/2 7/3/2 8/4/2
+f 1//3 2//3 6//3 5//3
+f -6 -5 -1 -2
+f 1/1 5/2 8/3 4/4
+f -7/1/-1 -6/2/-1 -2/3/-1 -3/4/-1
+";
+
+    #[test]
+    fn test_parse_cube() {
+        let mesh_graph = parse(CUBE).unwrap();
+
+        assert_eq!(mesh_graph.vertices.len(), 8);
+        assert_eq!(mesh_graph.faces.len(), 12);
+        assert!(mesh_graph.is_closed());
+        assert!((mesh_graph.volume() - 1.0).abs() < 1e-5);
+        assert!(mesh_graph.signed_volume() > 0.0);
+
+        let center = Vec3::splat(0.5);
+        let vertex_normals = mesh_graph.vertex_normals.as_ref().unwrap();
+        for (vertex_id, pos) in &mesh_graph.positions {
+            let normal = vertex_normals[vertex_id];
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(normal.dot(*pos - center) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("v 0 0 0\nv 1 0 0\nf 1 2 3\n").is_err());
+        assert!(parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2\n").is_err());
+        assert!(parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n").is_err());
+        assert!(parse("v 0 0\n").is_err());
+
+        let mesh_graph = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n").unwrap();
+        assert_eq!(mesh_graph.faces.len(), 1);
+        assert!(mesh_graph.vertex_normals.is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+        sphere.compute_vertex_normals();
+
+        let file =
+            std::env::temp_dir().join(format!("mesh_graph_round_trip_{}.obj", std::process::id()));
+        save(&sphere, &file).unwrap();
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.vertices.len(), sphere.vertices.len());
+        assert_eq!(loaded.halfedges.len(), sphere.halfedges.len());
+        assert_eq!(loaded.faces.len(), sphere.faces.len());
+        assert!(loaded.is_closed());
+        assert!(loaded.verify_twins().is_empty());
+        assert!((loaded.signed_volume() - sphere.signed_volume()).abs() < 1e-5);
+
+        let vertex_normals = loaded.vertex_normals.as_ref().unwrap();
+        for (vertex_id, pos) in &loaded.positions {
+            assert!(vertex_normals[vertex_id].dot(*pos) > 0.9);
+        }
+
+        assert!(loaded.positions.values().eq(sphere.positions.values()));
+
+        let without_normals = parse(&to_obj_string(&MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 0,
+        })))
+        .unwrap();
+        assert_eq!(without_normals.faces.len(), 20);
+        assert!(without_normals.vertex_normals.is_none());
+    }
+}
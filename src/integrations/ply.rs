@@ -0,0 +1,521 @@
+use std::{fmt::Write, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+use glam::Vec3;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::{MeshGraph, VertexId, utils::error};
+
+/// Encoding of the data of a PLY file after the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlyFormat {
+    /// Human readable text
+    Ascii,
+    /// Compact binary representation that is used by most scanners
+    #[default]
+    BinaryLittleEndian,
+}
+
+/// Loads a PLY file. See [`from_bytes`].
+pub fn load(file: impl AsRef<Path>) -> Result<MeshGraph> {
+    let bytes = std::fs::read(&file).context("Failed to read PLY file")?;
+    from_bytes(&bytes)
+}
+
+/// Parses the contents of a PLY file in the `ascii` or `binary_little_endian` format.
+///
+/// The `x`, `y` and `z` properties of the `vertex` element are used as positions and the
+/// `nx`, `ny` and `nz` properties (if present) as vertex normals. The `vertex_indices` (or
+/// `vertex_index`) list of the `face` element is triangulated as a fan. All other elements and
+/// properties are skipped.
+pub fn from_bytes(bytes: &[u8]) -> Result<MeshGraph> {
+    let (header, body) = split_header(bytes)?;
+    let (format, elements) = parse_header(header)?;
+
+    let mut reader = match format {
+        PlyFormat::Ascii => Reader::Ascii(
+            std::str::from_utf8(body)
+                .context("PLY body is not valid text")?
+                .split_whitespace(),
+        ),
+        PlyFormat::BinaryLittleEndian => Reader::Binary(body),
+    };
+
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut indices = vec![];
+
+    for element in &elements {
+        let property_index = |name: &str| element.properties.iter().position(|p| p.name == name);
+
+        match element.name.as_str() {
+            "vertex" => {
+                let [x, y, z] = ["x", "y", "z"].map(property_index);
+                let (Some(x), Some(y), Some(z)) = (x, y, z) else {
+                    bail!("Vertex element is missing the x, y or z property");
+                };
+                let normal_properties = match ["nx", "ny", "nz"].map(property_index) {
+                    [Some(nx), Some(ny), Some(nz)] => Some([nx, ny, nz]),
+                    _ => None,
+                };
+
+                for _ in 0..element.count {
+                    let values = element
+                        .properties
+                        .iter()
+                        .map(|property| reader.read_property(property))
+                        .collect::<Result<Vec<_>>>()?;
+                    let vec3 = |[a, b, c]: [usize; 3]| {
+                        Vec3::new(
+                            values[a][0] as f32,
+                            values[b][0] as f32,
+                            values[c][0] as f32,
+                        )
+                    };
+
+                    positions.push(vec3([x, y, z]));
+                    if let Some(normal_properties) = normal_properties {
+                        normals.push(vec3(normal_properties));
+                    }
+                }
+            }
+            "face" => {
+                let list = property_index("vertex_indices")
+                    .or_else(|| property_index("vertex_index"))
+                    .ok_or(anyhow!("Face element has no vertex_indices property"))?;
+
+                for _ in 0..element.count {
+                    let values = element
+                        .properties
+                        .iter()
+                        .map(|property| reader.read_property(property))
+                        .collect::<Result<Vec<_>>>()?;
+                    let face = values[list]
+                        .iter()
+                        .map(|index| *index as usize)
+                        .collect_vec();
+
+                    if face.len() < 3 {
+                        bail!("Face with less than three vertices");
+                    }
+
+                    for (b, c) in face[1..].iter().tuple_windows() {
+                        indices.extend([face[0], *b, *c]);
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        reader.read_property(property)?;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(index) = indices.iter().find(|index| **index >= positions.len()) {
+        bail!("Vertex index {index} out of range");
+    }
+
+    let (mut mesh_graph, vertex_ids) =
+        MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+    if !normals.is_empty() {
+        mesh_graph.vertex_normals = Some(
+            vertex_ids
+                .into_iter()
+                .zip(normals)
+                .collect::<SecondaryMap<_, _>>(),
+        );
+    }
+
+    Ok(mesh_graph)
+}
+
+/// Saves the mesh graph as a PLY file. See [`to_bytes`].
+pub fn save(mesh_graph: &MeshGraph, file: impl AsRef<Path>, format: PlyFormat) -> Result<()> {
+    std::fs::write(&file, to_bytes(mesh_graph, format)).context("Failed to write PLY file")
+}
+
+/// Writes the mesh graph in the PLY format.
+///
+/// Positions are written as the `x`, `y` and `z` properties of the `vertex` element and, if
+/// [`MeshGraph::vertex_normals`] is `Some`, normals as `nx`, `ny` and `nz`. Faces are written
+/// as `vertex_indices` lists in the order of their halfedges.
+pub fn to_bytes(mesh_graph: &MeshGraph, format: PlyFormat) -> Vec<u8> {
+    let mut vertex_id_to_index = SecondaryMap::<VertexId, u32>::new();
+    let mut vertices = Vec::with_capacity(mesh_graph.positions.len());
+
+    for (vertex_id, pos) in &mesh_graph.positions {
+        vertex_id_to_index.insert(vertex_id, vertices.len() as u32);
+
+        let normal = mesh_graph.vertex_normals.as_ref().map(|vertex_normals| {
+            vertex_normals.get(vertex_id).copied().unwrap_or_else(|| {
+                error!("Normal not found");
+                Vec3::ZERO
+            })
+        });
+
+        vertices.push((*pos, normal));
+    }
+
+    let faces = mesh_graph
+        .faces
+        .values()
+        .filter_map(|face| {
+            let face_indices = face
+                .vertices(mesh_graph)
+                .map(|vertex_id| vertex_id_to_index.get(vertex_id).copied())
+                .collect::<Option<Vec<_>>>();
+
+            if face_indices.is_none() {
+                error!("Vertex of face {:?} not found", face.id);
+            }
+
+            face_indices
+        })
+        .collect_vec();
+
+    let format_name = match format {
+        PlyFormat::Ascii => "ascii",
+        PlyFormat::BinaryLittleEndian => "binary_little_endian",
+    };
+
+    // writing to a string can't fail
+    let mut header = String::new();
+    let _ = writeln!(header, "ply\nformat {format_name} 1.0");
+    let _ = writeln!(header, "element vertex {}", vertices.len());
+    let _ = writeln!(
+        header,
+        "property float x\nproperty float y\nproperty float z"
+    );
+    if mesh_graph.vertex_normals.is_some() {
+        let _ = writeln!(
+            header,
+            "property float nx\nproperty float ny\nproperty float nz"
+        );
+    }
+    let _ = writeln!(header, "element face {}", faces.len());
+    let _ = writeln!(
+        header,
+        "property list uchar uint vertex_indices\nend_header"
+    );
+
+    match format {
+        PlyFormat::Ascii => {
+            let mut text = header;
+
+            for (pos, normal) in &vertices {
+                let values = [*pos]
+                    .into_iter()
+                    .chain(*normal)
+                    .flat_map(|vec| vec.to_array());
+                let _ = writeln!(text, "{}", values.format(" "));
+            }
+            for face in &faces {
+                let _ = writeln!(text, "{} {}", face.len(), face.iter().format(" "));
+            }
+
+            text.into_bytes()
+        }
+        PlyFormat::BinaryLittleEndian => {
+            let mut bytes = header.into_bytes();
+
+            for (pos, normal) in &vertices {
+                for value in [*pos]
+                    .into_iter()
+                    .chain(*normal)
+                    .flat_map(|vec| vec.to_array())
+                {
+                    bytes.extend(value.to_le_bytes());
+                }
+            }
+            for face in &faces {
+                bytes.push(face.len() as u8);
+                for index in face {
+                    bytes.extend(index.to_le_bytes());
+                }
+            }
+
+            bytes
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => bail!("Unknown property type `{name}`"),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Property {
+    name: String,
+    /// Type of the length of a list property or `None` for a scalar property
+    list_length_type: Option<ScalarType>,
+    value_type: ScalarType,
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Splits the file into the header (without `end_header`) and the body.
+fn split_header(bytes: &[u8]) -> Result<(&str, &[u8])> {
+    const END_HEADER: &[u8] = b"end_header";
+
+    let end = bytes
+        .windows(END_HEADER.len())
+        .position(|window| window == END_HEADER)
+        .ok_or(anyhow!("PLY header has no end"))?;
+    let body_start = bytes[end..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|newline| end + newline + 1)
+        .unwrap_or(bytes.len());
+
+    let header = std::str::from_utf8(&bytes[..end]).context("PLY header is not valid text")?;
+
+    Ok((header, &bytes[body_start..]))
+}
+
+fn parse_header(header: &str) -> Result<(PlyFormat, Vec<Element>)> {
+    let mut lines = header.lines().map(str::trim);
+
+    if lines.next() != Some("ply") {
+        bail!("Not a PLY file");
+    }
+
+    let mut format = None;
+    let mut elements = Vec::<Element>::new();
+
+    for line in lines {
+        let tokens = line.split_whitespace().collect_vec();
+
+        match tokens.as_slice() {
+            ["format", "ascii", _] => format = Some(PlyFormat::Ascii),
+            ["format", "binary_little_endian", _] => format = Some(PlyFormat::BinaryLittleEndian),
+            ["format", other, ..] => bail!("Unsupported PLY format `{other}`"),
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().context("Invalid element count")?,
+                properties: vec![],
+            }),
+            ["property", "list", length_type, value_type, name] => elements
+                .last_mut()
+                .ok_or(anyhow!("Property before the first element"))?
+                .properties
+                .push(Property {
+                    name: name.to_string(),
+                    list_length_type: Some(ScalarType::parse(length_type)?),
+                    value_type: ScalarType::parse(value_type)?,
+                }),
+            ["property", value_type, name] => elements
+                .last_mut()
+                .ok_or(anyhow!("Property before the first element"))?
+                .properties
+                .push(Property {
+                    name: name.to_string(),
+                    list_length_type: None,
+                    value_type: ScalarType::parse(value_type)?,
+                }),
+            _ => {}
+        }
+    }
+
+    Ok((format.ok_or(anyhow!("PLY header has no format"))?, elements))
+}
+
+/// Reads the values of the body one after the other
+enum Reader<'a> {
+    Ascii(std::str::SplitWhitespace<'a>),
+    Binary(&'a [u8]),
+}
+
+impl Reader<'_> {
+    /// Reads a scalar property as a list with a single value or all values of a list property.
+    fn read_property(&mut self, property: &Property) -> Result<Vec<f64>> {
+        match property.list_length_type {
+            Some(length_type) => {
+                let length = self.read(length_type)? as usize;
+                (0..length)
+                    .map(|_| self.read(property.value_type))
+                    .collect()
+            }
+            None => Ok(vec![self.read(property.value_type)?]),
+        }
+    }
+
+    fn read(&mut self, scalar_type: ScalarType) -> Result<f64> {
+        match self {
+            Reader::Ascii(tokens) => {
+                let token = tokens.next().ok_or(anyhow!("Unexpected end of PLY data"))?;
+
+                // parsing as `f64` first could round `f32` values differently
+                Ok(match scalar_type {
+                    ScalarType::F32 => token.parse::<f32>()? as f64,
+                    _ => token.parse::<f64>()?,
+                })
+            }
+            Reader::Binary(bytes) => {
+                let size = scalar_type.size();
+                if bytes.len() < size {
+                    bail!("Unexpected end of PLY data");
+                }
+
+                let (value, rest) = bytes.split_at(size);
+                *bytes = rest;
+
+                let array = |value: &[u8]| -> [u8; 8] {
+                    let mut array = [0; 8];
+                    array[..value.len()].copy_from_slice(value);
+                    array
+                };
+                let [a, b, c, d, ..] = array(value);
+
+                Ok(match scalar_type {
+                    ScalarType::I8 => a as i8 as f64,
+                    ScalarType::U8 => a as f64,
+                    ScalarType::I16 => i16::from_le_bytes([a, b]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([a, b]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes([a, b, c, d]) as f64,
+                    ScalarType::U32 => u32::from_le_bytes([a, b, c, d]) as f64,
+                    ScalarType::F32 => f32::from_le_bytes([a, b, c, d]) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(array(value)),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    const TETRAHEDRON: &str = "\
+ply
+format ascii 1.0
+comment a regular tetrahedron
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+element face 4
+property list uchar int vertex_indices
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+1 1 1 255
+1 -1 -1 0
+-1 1 -1 0
+-1 -1 1 0
+3 0 2 3
+3 0 3 1
+3 0 1 2
+3 1 3 2
+0 1
+";
+
+    #[test]
+    fn test_load_ascii_tetrahedron() {
+        let mesh_graph = from_bytes(TETRAHEDRON.as_bytes()).unwrap();
+
+        assert_eq!(mesh_graph.vertices.len(), 4);
+        assert_eq!(mesh_graph.faces.len(), 4);
+        assert!(mesh_graph.is_closed());
+        assert!(mesh_graph.verify_twins().is_empty());
+        assert!((mesh_graph.signed_volume() - 8.0 / 3.0).abs() < 1e-5);
+        assert!(mesh_graph.vertex_normals.is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+        sphere.compute_vertex_normals();
+
+        for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian] {
+            let file = std::env::temp_dir().join(format!(
+                "mesh_graph_round_trip_{format:?}_{}.ply",
+                std::process::id()
+            ));
+            save(&sphere, &file, format).unwrap();
+            let loaded = load(&file).unwrap();
+            std::fs::remove_file(&file).unwrap();
+
+            assert_eq!(loaded.vertices.len(), sphere.vertices.len());
+            assert_eq!(loaded.faces.len(), sphere.faces.len());
+            assert!(loaded.is_closed());
+            assert!((loaded.signed_volume() - sphere.signed_volume()).abs() < 1e-5);
+            assert!(loaded.positions.values().eq(sphere.positions.values()));
+            assert!(
+                loaded.vertex_normals.as_ref().unwrap().values().eq(sphere
+                    .vertex_normals
+                    .as_ref()
+                    .unwrap()
+                    .values())
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(from_bytes(b"ply\nformat ascii 1.0\n").is_err());
+        assert!(from_bytes(b"obj\nend_header\n").is_err());
+        assert!(from_bytes(b"ply\nformat binary_big_endian 1.0\nend_header\n").is_err());
+
+        let out_of_range = TETRAHEDRON.replace("3 1 3 2", "3 1 3 4");
+        assert!(from_bytes(out_of_range.as_bytes()).is_err());
+
+        let truncated = to_bytes(
+            &MeshGraph::from(IcoSphere {
+                radius: 1.0,
+                subdivisions: 0,
+            }),
+            PlyFormat::BinaryLittleEndian,
+        );
+        assert!(from_bytes(&truncated[..truncated.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::{MeshGraph, utils::error};
+
+const HEADER_SIZE: usize = 80;
+const TRIANGLE_SIZE: usize = 50;
+
+/// Loads a binary STL file. See [`from_bytes`].
+pub fn load(file: impl AsRef<Path>) -> Result<MeshGraph> {
+    let bytes = std::fs::read(&file).context("Failed to read STL file")?;
+    from_bytes(&bytes)
+}
+
+/// Parses the contents of a binary STL file.
+///
+/// STL stores three separate positions per triangle. These are merged with
+/// [`MeshGraph::triangles`] so that adjacent triangles share their vertices. The stored
+/// normals are ignored, the orientation of a face is given by its winding.
+pub fn from_bytes(bytes: &[u8]) -> Result<MeshGraph> {
+    if bytes.len() < HEADER_SIZE + 4 {
+        bail!("STL file is too short for the header");
+    }
+
+    let triangle_count = read_u32(bytes, HEADER_SIZE) as usize;
+    let expected_len = HEADER_SIZE + 4 + triangle_count * TRIANGLE_SIZE;
+
+    if bytes.len() < expected_len {
+        bail!(
+            "STL file is too short for {triangle_count} triangles: expected {expected_len} bytes, got {}",
+            bytes.len()
+        );
+    }
+
+    let positions = bytes[HEADER_SIZE + 4..expected_len]
+        .chunks_exact(TRIANGLE_SIZE)
+        // skip the normal, positions are followed by the attribute byte count
+        .flat_map(|triangle| (0..3).map(move |i| read_vec3(triangle, 12 + i * 12)))
+        .collect_vec();
+
+    Ok(MeshGraph::triangles(&positions))
+}
+
+/// Saves the mesh graph as a binary STL file. See [`to_bytes`].
+pub fn save(mesh_graph: &MeshGraph, file: impl AsRef<Path>) -> Result<()> {
+    std::fs::write(&file, to_bytes(mesh_graph)).context("Failed to write STL file")
+}
+
+/// Writes the mesh graph in the binary STL format.
+///
+/// Every face is written with its three positions in the order of its halfedges and its normal
+/// computed from this winding. The header is filled with zeros.
+pub fn to_bytes(mesh_graph: &MeshGraph) -> Vec<u8> {
+    let triangles = mesh_graph
+        .faces
+        .values()
+        .filter_map(|face| {
+            let positions = face.vertex_positions(mesh_graph).collect_vec();
+
+            if positions.len() != 3 {
+                error!("Face {:?} doesn't have three positions", face.id);
+                return None;
+            }
+
+            let normal = face.compute_normal(mesh_graph).unwrap_or(Vec3::ZERO);
+
+            Some((normal, positions))
+        })
+        .collect_vec();
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + 4 + triangles.len() * TRIANGLE_SIZE);
+    bytes.resize(HEADER_SIZE, 0);
+    bytes.extend((triangles.len() as u32).to_le_bytes());
+
+    for (normal, positions) in triangles {
+        for vec in [normal].into_iter().chain(positions) {
+            for coordinate in vec.to_array() {
+                bytes.extend(coordinate.to_le_bytes());
+            }
+        }
+        // attribute byte count
+        bytes.extend(0u16.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn read_vec3(bytes: &[u8], offset: usize) -> Vec3 {
+    let coordinate = |i: usize| f32::from_bits(read_u32(bytes, offset + i * 4));
+    Vec3::new(coordinate(0), coordinate(1), coordinate(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::Cuboid;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cube = MeshGraph::from(Cuboid {
+            half_extents: Vec3::new(1.0, 0.5, 2.0),
+        });
+
+        let file =
+            std::env::temp_dir().join(format!("mesh_graph_round_trip_{}.stl", std::process::id()));
+        save(&cube, &file).unwrap();
+
+        let bytes = std::fs::read(&file).unwrap();
+        assert_eq!(bytes.len(), 84 + 12 * 50);
+        assert_eq!(read_u32(&bytes, 80), 12);
+
+        let loaded = load(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(loaded.vertices.len(), 8);
+        assert_eq!(loaded.faces.len(), 12);
+        assert!(loaded.is_closed());
+        assert!(loaded.verify_twins().is_empty());
+        assert!((loaded.signed_volume() - 8.0).abs() < 1e-5);
+
+        // the stored normals point outwards
+        let first_normal = read_vec3(&bytes, 84);
+        let first_center = (0..3)
+            .map(|i| read_vec3(&bytes, 84 + 12 + i * 12))
+            .sum::<Vec3>()
+            / 3.0;
+        assert!(first_normal.dot(first_center) > 0.0);
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        assert!(from_bytes(&[0; 10]).is_err());
+
+        let mut bytes = vec![0; 84];
+        bytes[80] = 2;
+        bytes.extend([0; 50]);
+        assert!(from_bytes(&bytes).is_err());
+
+        bytes[80] = 0;
+        assert_eq!(from_bytes(&bytes).unwrap().faces.len(), 0);
+    }
+}
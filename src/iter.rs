@@ -1,4 +1,4 @@
-use tracing::error;
+use crate::utils::error;
 
 use crate::{HalfedgeId, MeshGraph};
 
@@ -9,6 +9,7 @@
 //! - High performance using slotmap
 //! - Easy integration with Bevy game engine using the `bevy` Cargo feature
 //! - Good debugging using `rerun` Cargo feature to enable the Rerun integration
+//! - Logging through `tracing` that can be compiled out by disabling the default `tracing` Cargo feature
 //! - Best in class documentation with illustrations
 //!
 //! ## Usage
@@ -45,31 +46,40 @@
 //! <img src="https://raw.githubusercontent.com/Synphonyte/mesh-graph/refs/heads/main/docs/vertex/all.svg" alt="Connectivity" style="max-width: 50em" />
 
 mod access;
+mod disjoint_sets;
 mod elements;
+pub mod geometry;
 pub mod integrations;
 mod iter;
 mod ops;
 mod plane_slice;
+mod point_grid;
 pub mod primitives;
 #[cfg(feature = "rerun")]
 mod rerun_impl;
 mod selection;
 #[cfg(feature = "serde")]
 mod serialize;
+mod user_data;
 pub mod utils;
+mod vertex_attributes;
 
 pub use elements::*;
 pub use iter::*;
 pub use ops::*;
 pub use plane_slice::*;
 pub use selection::*;
+pub use user_data::*;
+pub use vertex_attributes::*;
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
 use parry3d::partitioning::{Bvh, BvhWorkspace};
 
-use glam::Vec3;
+use crate::point_grid::PointGrid;
+use crate::utils::error;
+use glam::{Vec2, Vec3};
 use slotmap::{SecondaryMap, SlotMap};
-use tracing::{error, instrument};
 
 use crate::utils::unwrap_or_return;
 
@@ -113,10 +123,35 @@ pub struct MeshGraph {
     pub positions: SecondaryMap<VertexId, Vec3>,
     /// Maps vertex IDs to their corresponding normals
     pub vertex_normals: Option<SecondaryMap<VertexId, Vec3>>,
+    /// Maps vertex IDs to their texture coordinates
+    pub uvs: Option<SecondaryMap<VertexId, Vec2>>,
+    /// Maps face IDs to their cached normals. See [`MeshGraph::compute_face_normals`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub face_normals: Option<SecondaryMap<FaceId, Vec3>>,
 
     /// Maps vertex IDs to their corresponding outgoing halfedges (not in any particular order)
     #[cfg_attr(feature = "serde", serde(skip))]
     pub outgoing_halfedges: SecondaryMap<VertexId, Vec<HalfedgeId>>,
+
+    /// Halfedges that are tagged as sharp creases. Always contains both halfedges of an edge.
+    /// See [`MeshGraph::set_edge_sharp`].
+    pub sharp_edges: HashSet<HalfedgeId>,
+
+    /// Maps faces to the index of the source polygon they were triangulated from.
+    /// Only populated by [`MeshGraph::from_polygons`].
+    pub polygon_ids: SecondaryMap<FaceId, usize>,
+
+    /// Arbitrary user data attached to faces. See [`MeshGraph::face_user_data`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub face_user_data_maps: FaceUserDataMaps,
+
+    /// Named per-vertex attributes. See [`MeshGraph::add_vertex_attribute`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub vertex_attributes: VertexAttributes,
+
+    /// Records changed faces if enabled. See [`MeshGraph::track_changes`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub change_tracker: ChangeTracker,
 }
 
 impl MeshGraph {
@@ -129,8 +164,20 @@ impl MeshGraph {
     /// Create a triangle mesh graph from vertex positions.
     /// Every three positions represent a triangle.
     ///
-    /// Vertices with the same position are merged into a single vertex.
+    /// Vertices with the same position are merged into a single vertex. Positions are
+    /// considered the same if they are closer than `1e-5`. Use
+    /// [`MeshGraph::triangles_with_epsilon`] to choose a different tolerance.
     pub fn triangles(vertex_positions: &[Vec3]) -> Self {
+        const EPSILON: f32 = 1e-5;
+
+        Self::triangles_with_epsilon(vertex_positions, EPSILON)
+    }
+
+    /// Create a triangle mesh graph from vertex positions.
+    /// Every three positions represent a triangle.
+    ///
+    /// Vertices that are closer than `epsilon` to a previous vertex are merged into it.
+    pub fn triangles_with_epsilon(vertex_positions: &[Vec3], epsilon: f32) -> Self {
         assert!(
             vertex_positions.len().is_multiple_of(3),
             "Number of vertex positions should be a multiple of 3"
@@ -140,17 +187,16 @@ impl MeshGraph {
         let mut unique_positions: Vec<Vec3> = Vec::with_capacity(vertex_positions.len() / 3);
         let mut face_indices = Vec::with_capacity(vertex_positions.len());
 
+        // only the 27 cells around a position have to be checked for previous vertices
+        let mut grid = PointGrid::new(epsilon);
+
         for vertex_pos in vertex_positions {
-            // Check if we've seen this position before using a fuzzy float comparison
-            let mut idx = None;
-            for (j, pos) in unique_positions.iter().enumerate() {
-                const EPSILON: f32 = 1e-5;
-
-                if pos.distance_squared(*vertex_pos) < EPSILON {
-                    idx = Some(j);
-                    break;
-                }
-            }
+            // Use the first vertex that was added within epsilon of this position
+            let idx = grid
+                .within(*vertex_pos, epsilon)
+                .filter(|(pos, _)| pos.distance_squared(*vertex_pos) < epsilon * epsilon)
+                .map(|(_, idx)| idx)
+                .min();
 
             // Use the existing index or add a new vertex
             let vertex_idx = if let Some(idx) = idx {
@@ -158,6 +204,7 @@ impl MeshGraph {
             } else {
                 let new_idx = unique_positions.len();
                 unique_positions.push(*vertex_pos);
+                grid.insert(*vertex_pos, new_idx);
 
                 #[cfg(feature = "rerun")]
                 RR.log(
@@ -177,9 +224,32 @@ impl MeshGraph {
         Self::indexed_triangles(&unique_positions, &face_indices)
     }
 
+    /// Returns the vertex positions of all faces as an unindexed triangle list (triangle soup).
+    /// Every three positions represent a triangle. No vertices are shared between triangles.
+    ///
+    /// The positions of each triangle are in the order of the face's halfedges so the winding
+    /// (and thus the face normal) is preserved. This is the inverse of [`MeshGraph::triangles`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn to_triangle_soup(&self) -> Vec<Vec3> {
+        let mut soup = Vec::with_capacity(self.faces.len() * 3);
+
+        for face in self.faces.values() {
+            let positions = face.vertex_positions(self).collect::<Vec<_>>();
+
+            if positions.len() != 3 {
+                error!("Face doesn't have exactly 3 vertex positions");
+                continue;
+            }
+
+            soup.extend(positions);
+        }
+
+        soup
+    }
+
     /// Create a triangle mesh graph from vertex positions, face indices,
     /// and a custom vertex attribute.
-    #[instrument]
+    #[cfg_attr(feature = "tracing", tracing::instrument)]
     pub fn indexed_triangles_with_custom_attribute<T>(
         vertex_positions: &[Vec3],
         face_indices: &[usize],
@@ -208,24 +278,76 @@ impl MeshGraph {
 
     /// Create a triangle mesh graph from vertex positions and face indices,
     /// returning the graph and a list of vertex IDs in the same order as `vertex_positions`.
-    #[instrument]
+    #[cfg_attr(feature = "tracing", tracing::instrument)]
     pub fn indexed_triangles_and_vertex_ids(
         vertex_positions: &[Vec3],
         face_indices: &[usize],
     ) -> (Self, Vec<VertexId>) {
+        Self::from_index_triples(
+            vertex_positions,
+            face_indices
+                .chunks_exact(3)
+                .map(|chunk| [chunk[0], chunk[1], chunk[2]]),
+        )
+    }
+
+    /// Create a triangle mesh graph from vertex positions and `u32` face indices
+    /// like they are usually found in GPU buffers and glTF files.
+    /// Every chunk of three indices represents a triangle.
+    ///
+    /// This is the same as [`MeshGraph::indexed_triangles`] without having to convert
+    /// the indices to `usize` first.
+    pub fn from_indices_u32(vertex_positions: &[Vec3], face_indices: &[u32]) -> Self {
+        Self::from_index_triples(
+            vertex_positions,
+            face_indices
+                .chunks_exact(3)
+                .map(|chunk| [chunk[0] as usize, chunk[1] as usize, chunk[2] as usize]),
+        )
+        .0
+    }
+
+    /// Create a triangle mesh graph from vertex positions and `u16` face indices.
+    /// Every chunk of three indices represents a triangle.
+    ///
+    /// This is the same as [`MeshGraph::indexed_triangles`] without having to convert
+    /// the indices to `usize` first.
+    pub fn from_indices_u16(vertex_positions: &[Vec3], face_indices: &[u16]) -> Self {
+        Self::from_index_triples(
+            vertex_positions,
+            face_indices
+                .chunks_exact(3)
+                .map(|chunk| [chunk[0] as usize, chunk[1] as usize, chunk[2] as usize]),
+        )
+        .0
+    }
+
+    fn from_index_triples(
+        vertex_positions: &[Vec3],
+        face_indices: impl ExactSizeIterator<Item = [usize; 3]>,
+    ) -> (Self, Vec<VertexId>) {
+        let face_count = face_indices.len();
+
         let mut mesh_graph = Self {
             bvh: Bvh::new(),
             bvh_workspace: BvhWorkspace::default(),
-            index_to_face_id: HashMap::with_capacity(face_indices.len() / 3),
+            index_to_face_id: HashMap::with_capacity(face_count),
             next_index: 0,
 
             vertices: SlotMap::with_capacity_and_key(vertex_positions.len()),
-            halfedges: SlotMap::with_capacity_and_key(face_indices.len()),
-            faces: SlotMap::with_capacity_and_key(face_indices.len() / 3),
+            halfedges: SlotMap::with_capacity_and_key(face_count * 3),
+            faces: SlotMap::with_capacity_and_key(face_count),
 
             positions: SecondaryMap::with_capacity(vertex_positions.len()),
             vertex_normals: None,
+            uvs: None,
+            face_normals: None,
             outgoing_halfedges: SecondaryMap::with_capacity(vertex_positions.len()),
+            sharp_edges: HashSet::new(),
+            polygon_ids: SecondaryMap::new(),
+            face_user_data_maps: FaceUserDataMaps::default(),
+            vertex_attributes: VertexAttributes::default(),
+            change_tracker: ChangeTracker::default(),
         };
 
         let mut vertex_ids = Vec::with_capacity(vertex_positions.len());
@@ -234,10 +356,10 @@ impl MeshGraph {
             vertex_ids.push(mesh_graph.add_vertex(*pos));
         }
 
-        for chunk in face_indices.chunks_exact(3) {
-            let a = vertex_ids[chunk[0]];
-            let b = vertex_ids[chunk[1]];
-            let c = vertex_ids[chunk[2]];
+        for [a, b, c] in face_indices {
+            let a = vertex_ids[a];
+            let b = vertex_ids[b];
+            let c = vertex_ids[c];
 
             if a == b || b == c || c == a {
                 #[cfg(feature = "rerun")]
@@ -284,7 +406,7 @@ impl MeshGraph {
 
         for face_id in vertex.faces(self) {
             let face = unwrap_or_return!(self.faces.get(face_id), "Face not found");
-            normal += unwrap_or_return!(face.normal(self), "Face normal not found");
+            normal += unwrap_or_return!(face.compute_normal(self), "Face normal not found");
         }
 
         self.vertex_normals
@@ -293,51 +415,33 @@ impl MeshGraph {
             .insert(vertex_id, normal.try_normalize().unwrap_or(Vec3::ZERO));
     }
 
-    /// Computes the vertex normals by averaging over the computed face normals
-    #[instrument(skip(self))]
+    /// Computes the vertex normals by averaging over the computed face normals weighted by
+    /// their area. See [`MeshGraph::compute_vertex_normals_weighted`] for other weightings.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn compute_vertex_normals(&mut self) {
-        let mut normals = SecondaryMap::with_capacity(self.vertices.len());
-
-        for face in self.faces.values() {
-            let ha_a_id = face.halfedge;
-            let he_a = self.halfedges[ha_a_id];
-
-            let he_b_id = he_a
-                .next
-                .expect("Halfedge has definitely a face and thus a next halfedge");
-            let he_b = self.halfedges[he_b_id];
-
-            let a = match he_a.start_vertex(self) {
-                Some(v) => v,
-                None => {
-                    error!("Start vertex not found");
-                    continue;
-                }
-            };
-            let b = he_a.end_vertex;
-            let c = he_b.end_vertex;
-
-            let (Some(pos_a), Some(pos_b), Some(pos_c)) = (
-                self.positions.get(a),
-                self.positions.get(b),
-                self.positions.get(c),
-            ) else {
-                continue;
-            };
-
-            let diff_a = pos_c - pos_a;
-            let diff_b = pos_c - pos_b;
-
-            // TODO : normalizing necessary here?
-            let face_normal = diff_a.cross(diff_b);
+        self.compute_vertex_normals_weighted(NormalWeighting::Area);
+    }
 
-            *normals.entry(a).unwrap().or_default() += face_normal;
-            *normals.entry(b).unwrap().or_default() += face_normal;
-            *normals.entry(c).unwrap().or_default() += face_normal;
+    /// Computes the normals of all faces and caches them in [`MeshGraph::face_normals`].
+    ///
+    /// The normals are normalized and point to the side from which the face is oriented
+    /// counter-clockwise. Afterwards [`Face::normal`] returns the cached values.
+    ///
+    /// The cache is not updated when the mesh is modified. Call this method again after moving
+    /// vertices or changing the topology or set `face_normals` to `None` to discard the cache.
+    /// The operations of this crate don't read the cache, they always use
+    /// [`Face::compute_normal`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn compute_face_normals(&mut self) {
+        let mut normals = SecondaryMap::with_capacity(self.faces.len());
+
+        for (face_id, face) in &self.faces {
+            if let Some(normal) = face.compute_normal(self) {
+                normals.insert(face_id, normal);
+            }
         }
 
-        self.vertex_normals = Some(normals);
-        self.normalize_vertex_normals();
+        self.face_normals = Some(normals);
     }
 
     /// Ensures that the vertex normals are all normalized
@@ -349,6 +453,22 @@ impl MeshGraph {
         }
     }
 
+    /// Signed volume enclosed by the mesh.
+    ///
+    /// Only meaningful for closed meshes. It is positive if the faces are oriented
+    /// counter-clockwise when looked at from the outside.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn signed_volume(&self) -> f32 {
+        self.faces
+            .values()
+            .filter_map(|face| {
+                let [a, b, c] = face.vertex_positions(self).collect_array()?;
+                Some(a.dot(b.cross(c)))
+            })
+            .sum::<f32>()
+            / 6.0
+    }
+
     /// Calls the `optimize_incremental` method of the BVH.
     #[inline]
     pub fn optimize_bvh_incremental(&mut self) {
@@ -375,7 +495,23 @@ impl MeshGraph {
             .rebuild(&mut self.bvh_workspace, Default::default());
     }
 
-    #[instrument(skip_all)]
+    /// Iterates over all faces ordered by their BVH leaf index.
+    ///
+    /// Every face stores the index under which its bounding box is registered in the [`Bvh`]
+    /// in [`Face::index`]. [`MeshGraph::index_to_face_id`] maps those indices back to face IDs.
+    /// Indices are handed out in ascending order as faces are created, so this order follows
+    /// how the mesh was built up which usually gives better cache locality for spatial
+    /// algorithms than iterating the slotmap directly.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn faces_in_bvh_order(&self) -> impl Iterator<Item = FaceId> {
+        self.index_to_face_id
+            .iter()
+            .sorted_unstable_by_key(|(index, _)| **index)
+            .map(|(_, face_id)| *face_id)
+            .filter(|face_id| self.faces.contains_key(*face_id))
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn rebuild_outgoing_halfedges(&mut self) {
         self.outgoing_halfedges.clear();
 
@@ -393,4 +529,368 @@ impl MeshGraph {
             entry.or_default().push(twin_id);
         }
     }
+
+    /// Makes all derived data consistent with the topology after the mesh has been built by
+    /// manipulating the slotmaps directly.
+    ///
+    /// This reassigns the BVH indices of all faces (and [`MeshGraph::index_to_face_id`]),
+    /// rebuilds the BVH and regenerates the outgoing halfedges cache.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn finalize(&mut self) {
+        self.repair_bvh_consistency();
+        self.rebuild_outgoing_halfedges();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_faces_in_bvh_order() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let face_ids = mesh_graph.faces_in_bvh_order().collect_vec();
+
+        assert_eq!(face_ids.len(), mesh_graph.faces.len());
+        assert_eq!(
+            face_ids.iter().collect::<HashSet<_>>().len(),
+            mesh_graph.faces.len()
+        );
+        assert!(
+            face_ids
+                .iter()
+                .map(|face_id| mesh_graph.faces[*face_id].index)
+                .is_sorted()
+        );
+    }
+
+    #[test]
+    fn test_compute_face_normals() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        mesh_graph.compute_face_normals();
+
+        let face_normals = mesh_graph.face_normals.as_ref().unwrap();
+        assert_eq!(face_normals.len(), mesh_graph.faces.len());
+
+        for (face_id, face) in &mesh_graph.faces {
+            let cached = face_normals[face_id];
+            let fresh = face.compute_normal(&mesh_graph).unwrap();
+
+            assert_eq!(face.normal(&mesh_graph), Some(cached));
+            assert!(cached.distance(fresh) < 1e-6);
+            assert!((cached.length() - 1.0).abs() < 1e-5);
+
+            // counter-clockwise faces point outwards
+            assert!(cached.dot(face.center(&mesh_graph)) > 0.0);
+        }
+
+        // the cache isn't updated automatically
+        let face_id = mesh_graph.faces.keys().next().unwrap();
+        let vertex_id = mesh_graph.faces[face_id]
+            .vertices(&mesh_graph)
+            .next()
+            .unwrap();
+        mesh_graph.positions[vertex_id] *= 0.5;
+
+        let face = mesh_graph.faces[face_id];
+        assert_ne!(face.normal(&mesh_graph), face.compute_normal(&mesh_graph));
+
+        mesh_graph.compute_face_normals();
+        assert_eq!(face.normal(&mesh_graph), face.compute_normal(&mesh_graph));
+    }
+
+    #[test]
+    fn test_triangle_soup_roundtrip() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 2,
+        });
+
+        let soup = mesh_graph.to_triangle_soup();
+
+        assert_eq!(soup.len(), mesh_graph.faces.len() * 3);
+
+        let rebuilt = MeshGraph::triangles(&soup);
+
+        assert_eq!(rebuilt.faces.len(), mesh_graph.faces.len());
+        assert_eq!(rebuilt.vertices.len(), mesh_graph.vertices.len());
+        assert_eq!(rebuilt.halfedges.len(), mesh_graph.halfedges.len());
+    }
+
+    #[test]
+    fn test_triangles_with_epsilon() {
+        let offset = Vec3::new(1e-4, 0.0, 0.0);
+        let soup = [
+            Vec3::ZERO,
+            Vec3::X,
+            Vec3::Y,
+            Vec3::X + offset,
+            Vec3::ONE,
+            Vec3::Y,
+        ];
+
+        let mesh_graph = MeshGraph::triangles_with_epsilon(&soup, 1e-6);
+        assert_eq!(mesh_graph.vertices.len(), 5);
+
+        let mesh_graph = MeshGraph::triangles(&soup);
+        assert_eq!(mesh_graph.vertices.len(), 5);
+
+        let mesh_graph = MeshGraph::triangles_with_epsilon(&soup, 1e-3);
+        assert_eq!(mesh_graph.vertices.len(), 4);
+        assert_eq!(mesh_graph.halfedges.len(), 10);
+    }
+
+    #[test]
+    fn test_triangles_far_from_origin() {
+        let offset = Vec3::splat(30000.0);
+        let soup =
+            [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Y, Vec3::X, Vec3::ONE].map(|pos| pos + offset);
+
+        let mesh_graph = MeshGraph::triangles(&soup);
+        assert_eq!(mesh_graph.vertices.len(), 4);
+        assert_eq!(mesh_graph.faces.len(), 2);
+
+        let huge = [Vec3::ZERO, Vec3::X, Vec3::Y].map(|pos| pos * 1e30 + Vec3::splat(1e30));
+        let mesh_graph = MeshGraph::triangles(&huge);
+        assert_eq!(mesh_graph.vertices.len(), 3);
+    }
+
+    #[test]
+    fn test_triangles_large_soup() {
+        // deterministic jitter well below the merge epsilon
+        let mut state = 12345_u32;
+        let mut jitter = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 1e-6
+        };
+
+        let (width, height) = (120, 140);
+        let soup = (0..height)
+            .cartesian_product(0..width)
+            .flat_map(|(y, x)| {
+                let corner =
+                    |dx: usize, dy: usize| Vec3::new((x + dx) as f32, (y + dy) as f32, 0.0);
+                [
+                    corner(0, 0),
+                    corner(1, 0),
+                    corner(1, 1),
+                    corner(0, 0),
+                    corner(1, 1),
+                    corner(0, 1),
+                ]
+            })
+            .map(|pos| pos + Vec3::new(jitter(), jitter(), jitter()))
+            .collect_vec();
+
+        assert!(soup.len() > 100_000);
+
+        let mesh_graph = MeshGraph::triangles(&soup);
+
+        assert_eq!(mesh_graph.vertices.len(), (width + 1) * (height + 1));
+        assert_eq!(mesh_graph.faces.len(), soup.len() / 3);
+        assert_eq!(mesh_graph.boundary_loops().len(), 1);
+    }
+
+    #[test]
+    fn test_triangles_merges_like_linear_scan() {
+        // positions on a fine lattice so that most of them are within epsilon of a previous one
+        let mut state = 42_u32;
+        let soup = (0..3000)
+            .map(|_| {
+                let mut coordinate = || {
+                    state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                    (state >> 28) as f32 * 0.004
+                };
+                Vec3::new(coordinate(), coordinate(), coordinate())
+            })
+            .collect_vec();
+        let epsilon = 0.01;
+
+        let mut unique_positions: Vec<Vec3> = vec![];
+        let linear_indices = soup
+            .iter()
+            .map(|vertex_pos| {
+                unique_positions
+                    .iter()
+                    .position(|pos| pos.distance_squared(*vertex_pos) < epsilon * epsilon)
+                    .unwrap_or_else(|| {
+                        unique_positions.push(*vertex_pos);
+                        unique_positions.len() - 1
+                    })
+            })
+            .collect_vec();
+
+        let (mesh_graph, vertex_ids) =
+            MeshGraph::indexed_triangles_and_vertex_ids(&unique_positions, &linear_indices);
+        let expected = mesh_graph
+            .faces
+            .values()
+            .map(|face| {
+                face.vertices(&mesh_graph)
+                    .map(|vertex_id| mesh_graph.positions[vertex_id].to_array().map(f32::to_bits))
+                    .collect_vec()
+            })
+            .collect::<HashSet<_>>();
+
+        let merged = MeshGraph::triangles_with_epsilon(&soup, epsilon);
+        let actual = merged
+            .faces
+            .values()
+            .map(|face| {
+                face.vertices(&merged)
+                    .map(|vertex_id| merged.positions[vertex_id].to_array().map(f32::to_bits))
+                    .collect_vec()
+            })
+            .collect::<HashSet<_>>();
+
+        assert_eq!(merged.vertices.len(), vertex_ids.len());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_from_indices_u32_and_u16() {
+        let positions = (0..8)
+            .map(|i| Vec3::new((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32))
+            .collect_vec();
+        let indices: [u32; 36] = [
+            0, 2, 1, 1, 2, 3, // -z
+            4, 5, 6, 5, 7, 6, // +z
+            0, 1, 4, 1, 5, 4, // -y
+            2, 6, 3, 3, 6, 7, // +y
+            0, 4, 2, 2, 4, 6, // -x
+            1, 3, 5, 3, 7, 5, // +x
+        ];
+
+        let usize_indices = indices.iter().map(|i| *i as usize).collect_vec();
+        let u16_indices = indices.iter().map(|i| *i as u16).collect_vec();
+
+        let faces = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .faces
+                .values()
+                .map(|face| face.vertices(mesh_graph).collect_vec())
+                .collect_vec()
+        };
+
+        let expected = MeshGraph::indexed_triangles(&positions, &usize_indices);
+
+        for mesh_graph in [
+            MeshGraph::from_indices_u32(&positions, &indices),
+            MeshGraph::from_indices_u16(&positions, &u16_indices),
+        ] {
+            assert_eq!(mesh_graph.faces.len(), 12);
+            assert_eq!(mesh_graph.halfedges.len(), expected.halfedges.len());
+            assert_eq!(mesh_graph.positions, expected.positions);
+            assert_eq!(faces(&mesh_graph), faces(&expected));
+            assert!(mesh_graph.halfedges.values().all(|he| !he.is_boundary()));
+        }
+    }
+
+    #[test]
+    fn test_finalize() {
+        let positions = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ];
+        let triangles = [[0, 1, 2], [0, 2, 3]];
+
+        // build the mesh graph by hand without any of the `add_*` methods
+        let mut mesh_graph = MeshGraph::new();
+        let vertex_ids = positions
+            .iter()
+            .map(|pos| {
+                let vertex_id = mesh_graph.vertices.insert(Vertex::default());
+                mesh_graph.positions.insert(vertex_id, *pos);
+                vertex_id
+            })
+            .collect_vec();
+
+        let mut he_ids = HashMap::new();
+        for triangle in triangles {
+            let face_he_ids = triangle
+                .into_iter()
+                .circular_tuple_windows()
+                .map(|(a, b)| {
+                    let he_id = mesh_graph.halfedges.insert(Halfedge {
+                        end_vertex: vertex_ids[b],
+                        face: None,
+                        twin: None,
+                        next: None,
+                    });
+                    he_ids.insert((a, b), he_id);
+                    mesh_graph.vertices[vertex_ids[a]].outgoing_halfedge = Some(he_id);
+                    he_id
+                })
+                .collect_vec();
+
+            let face_id = mesh_graph.faces.insert(Face {
+                halfedge: face_he_ids[0],
+                ..Default::default()
+            });
+
+            for (he_id, next_id) in face_he_ids.into_iter().circular_tuple_windows() {
+                mesh_graph.halfedges[he_id].face = Some(face_id);
+                mesh_graph.halfedges[he_id].next = Some(next_id);
+            }
+        }
+
+        for ((a, b), he_id) in he_ids.clone() {
+            let twin_id = he_ids.get(&(b, a)).copied().unwrap_or_else(|| {
+                let twin_id = mesh_graph.halfedges.insert(Halfedge {
+                    end_vertex: vertex_ids[a],
+                    face: None,
+                    twin: Some(he_id),
+                    next: None,
+                });
+                mesh_graph.vertices[vertex_ids[b]].outgoing_halfedge = Some(twin_id);
+                twin_id
+            });
+            mesh_graph.halfedges[he_id].twin = Some(twin_id);
+        }
+
+        mesh_graph.finalize();
+
+        assert_eq!(mesh_graph.next_index as usize, mesh_graph.faces.len());
+        for (face_id, face) in &mesh_graph.faces {
+            assert_eq!(face.id, face_id);
+            assert_eq!(mesh_graph.index_to_face_id[&face.index], face_id);
+        }
+
+        for (vertex_id, outgoing_he_ids) in &mesh_graph.outgoing_halfedges {
+            let expected = mesh_graph
+                .halfedges
+                .iter()
+                .filter(|(_, he)| he.start_vertex(&mesh_graph) == Some(vertex_id))
+                .map(|(he_id, _)| he_id)
+                .collect::<HashSet<_>>();
+            assert_eq!(
+                outgoing_he_ids.iter().copied().collect::<HashSet<_>>(),
+                expected
+            );
+        }
+        assert_eq!(mesh_graph.outgoing_halfedges.len(), vertex_ids.len());
+
+        let (face_id, z) = mesh_graph
+            .raycast_down(glam::Vec2::new(0.2, 0.7), 1.0)
+            .unwrap();
+        assert_eq!(
+            mesh_graph.faces[face_id]
+                .vertices(&mesh_graph)
+                .collect::<HashSet<_>>(),
+            HashSet::from([vertex_ids[0], vertex_ids[2], vertex_ids[3]])
+        );
+        assert!(z.abs() < 1e-6);
+    }
 }
@@ -1,4 +1,4 @@
-use tracing::{error, instrument};
+use crate::utils::error;
 
 use crate::{Halfedge, HalfedgeId, MeshGraph, VertexId, error_none};
 
@@ -8,7 +8,7 @@ impl MeshGraph {
     /// it returns the existing edge while creating any missing halfedges.
     ///
     /// See also [`add_edge`].
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_or_get_edge(
         &mut self,
         start_vertex_id: VertexId,
@@ -168,7 +168,7 @@ impl MeshGraph {
     /// It does insert into `self.outgoing_halfedges`.
     ///
     /// Use [`insert_or_get_edge`] instead of this when you can to lower the chance of creating an invalid graph.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_halfedge(
         &mut self,
         start_vertex: VertexId,
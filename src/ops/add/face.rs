@@ -1,10 +1,10 @@
+use crate::utils::error;
 use glam::Vec3;
-use tracing::{error, instrument};
 
 use crate::{Face, FaceId, HalfedgeId, MeshGraph, VertexId, error_none};
 
 impl MeshGraph {
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face_from_positions(&mut self, a: Vec3, b: Vec3, c: Vec3) -> AddFace {
         let a_id = self.add_vertex(a);
         let b_id = self.add_vertex(b);
@@ -33,7 +33,7 @@ impl MeshGraph {
     }
 
     /// Returns `None` when an edge already has two faces
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face_from_halfedge_and_position(
         &mut self,
         he_id: HalfedgeId,
@@ -44,7 +44,7 @@ impl MeshGraph {
     }
 
     /// Returns `None` when the edge described by `he_id` already has two faces
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face_from_halfedge_and_vertex(
         &mut self,
         he_id: HalfedgeId,
@@ -75,8 +75,34 @@ impl MeshGraph {
         })
     }
 
-    /// Creates a face from three vertices.
-    #[instrument(skip(self))]
+    /// Creates a face from three existing vertices given in counter-clockwise order.
+    ///
+    /// The three edges of the face are looked up or created as needed via
+    /// [`MeshGraph::add_or_get_edge`], so this is the method to use when you only have vertex IDs.
+    /// Returns `None` if one of the vertices doesn't exist.
+    ///
+    /// If you already have two boundary halfedges that should become part of the new face,
+    /// use [`MeshGraph::add_face_from_halfedges`] instead.
+    ///
+    /// ```
+    /// use glam::Vec3;
+    /// use mesh_graph::MeshGraph;
+    ///
+    /// let mut mesh_graph = MeshGraph::new();
+    ///
+    /// let a = mesh_graph.add_vertex(Vec3::new(0.0, 0.0, 0.0));
+    /// let b = mesh_graph.add_vertex(Vec3::new(1.0, 0.0, 0.0));
+    /// let c = mesh_graph.add_vertex(Vec3::new(1.0, 1.0, 0.0));
+    /// let d = mesh_graph.add_vertex(Vec3::new(0.0, 1.0, 0.0));
+    ///
+    /// mesh_graph.add_face_from_vertices(a, b, c).unwrap();
+    /// // the edge between `a` and `c` already exists and is reused
+    /// mesh_graph.add_face_from_vertices(a, c, d).unwrap();
+    ///
+    /// assert_eq!(mesh_graph.faces.len(), 2);
+    /// assert_eq!(mesh_graph.halfedges.len(), 10);
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face_from_vertices(
         &mut self,
         v_id1: VertexId,
@@ -104,8 +130,41 @@ impl MeshGraph {
         })
     }
 
-    /// Creates a face from two halfedges.
-    #[instrument(skip(self))]
+    /// Creates a face from two existing halfedges that share a vertex.
+    ///
+    /// For each of the two given halfedges the boundary one of the twin pair is used
+    /// (see [`MeshGraph::boundary_he`]). The missing third edge that closes the triangle is
+    /// created if necessary. Returns `None` if one of the edges doesn't have a boundary halfedge,
+    /// i.e. it already has two faces.
+    ///
+    /// If you only have vertex IDs, use [`MeshGraph::add_face_from_vertices`] instead.
+    ///
+    /// ```
+    /// use glam::Vec3;
+    /// use mesh_graph::MeshGraph;
+    ///
+    /// let mut mesh_graph = MeshGraph::new();
+    ///
+    /// let vertex_ids = mesh_graph
+    ///     .add_face_from_positions(
+    ///         Vec3::new(0.0, 0.0, 0.0),
+    ///         Vec3::new(1.0, 0.0, 0.0),
+    ///         Vec3::new(0.0, 1.0, 0.0),
+    ///     )
+    ///     .vertex_ids;
+    /// let (b, c) = (vertex_ids[1], vertex_ids[2]);
+    ///
+    /// let d = mesh_graph.add_vertex(Vec3::new(1.0, 1.0, 0.0));
+    /// let he_b_d = mesh_graph.add_or_get_edge(b, d).unwrap().start_to_end_he_id;
+    /// let he_c_b = mesh_graph.halfedge_from_to(c, b).unwrap();
+    ///
+    /// // creates the edge from `d` to `c` and connects everything into a face
+    /// mesh_graph.add_face_from_halfedges(he_b_d, he_c_b).unwrap();
+    ///
+    /// assert_eq!(mesh_graph.faces.len(), 2);
+    /// assert_eq!(mesh_graph.halfedges.len(), 10);
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face_from_halfedges(
         &mut self,
         he_id1: HalfedgeId,
@@ -188,7 +247,10 @@ impl MeshGraph {
 
     /// Inserts a face into the mesh graph. It connects the halfedges to the face and the face to the first halfedge.
     /// Additionally it connects the halfedges' `next` loop around the face.
-    #[instrument(skip(self))]
+    ///
+    /// This is the low level building block. Usually you want to use
+    /// [`MeshGraph::add_face_from_vertices`] or [`MeshGraph::add_face_from_halfedges`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_face(
         &mut self,
         he1_id: HalfedgeId,
@@ -217,6 +279,7 @@ impl MeshGraph {
         let face = self.faces[face_id]; // just inserted above
         self.bvh
             .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+        self.change_tracker.mark_face(face.id);
 
         face_id
     }
@@ -0,0 +1,227 @@
+use slotmap::{Key, SecondaryMap};
+
+use crate::MeshGraph;
+
+impl MeshGraph {
+    /// Inserts all vertices, halfedges and faces of `other` into this mesh graph.
+    ///
+    /// The elements of `other` get new IDs, all links between them are preserved. Vertices are
+    /// not merged by position, so the result is the disjoint union of both meshes. Positions,
+    /// sharp edges and polygon ids (offset behind the ones of this mesh) are carried over. Vertex normals, UVs and face normals are
+    /// kept only if both meshes have them (or this mesh is empty). Face user data and vertex
+    /// attributes aren't carried over.
+    ///
+    /// The BVH is rebuilt afterwards.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, other)))]
+    pub fn append(&mut self, other: &MeshGraph) {
+        let was_empty = self.vertices.is_empty();
+
+        let vertex_map = other
+            .vertices
+            .iter()
+            .map(|(vertex_id, vertex)| (vertex_id, self.vertices.insert(*vertex)))
+            .collect::<SecondaryMap<_, _>>();
+        let halfedge_map = other
+            .halfedges
+            .iter()
+            .map(|(he_id, he)| (he_id, self.halfedges.insert(*he)))
+            .collect::<SecondaryMap<_, _>>();
+        let face_map = other
+            .faces
+            .iter()
+            .map(|(face_id, face)| (face_id, self.faces.insert(*face)))
+            .collect::<SecondaryMap<_, _>>();
+
+        for new_id in vertex_map.values() {
+            let vertex = &mut self.vertices[*new_id];
+            vertex.outgoing_halfedge = vertex
+                .outgoing_halfedge
+                .and_then(|he_id| halfedge_map.get(he_id).copied());
+        }
+
+        for new_id in halfedge_map.values() {
+            let he = &mut self.halfedges[*new_id];
+            he.end_vertex = remap(&vertex_map, he.end_vertex);
+            he.face = he.face.and_then(|face_id| face_map.get(face_id).copied());
+            he.twin = he.twin.and_then(|he_id| halfedge_map.get(he_id).copied());
+            he.next = he.next.and_then(|he_id| halfedge_map.get(he_id).copied());
+        }
+
+        for new_id in face_map.values() {
+            let face = &mut self.faces[*new_id];
+            face.halfedge = remap(&halfedge_map, face.halfedge);
+            face.id = *new_id;
+            face.index = self.next_index;
+
+            self.index_to_face_id.insert(self.next_index, *new_id);
+            self.next_index += 1;
+
+            self.change_tracker.mark_face(*new_id);
+        }
+
+        for (old_id, new_id) in &vertex_map {
+            if let Some(pos) = other.positions.get(old_id) {
+                self.positions.insert(*new_id, *pos);
+            }
+
+            let outgoing = other
+                .outgoing_halfedges
+                .get(old_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|he_id| halfedge_map.get(*he_id).copied())
+                .collect();
+            self.outgoing_halfedges.insert(*new_id, outgoing);
+        }
+
+        self.vertex_normals = append_attribute(
+            self.vertex_normals.take(),
+            other.vertex_normals.as_ref(),
+            &vertex_map,
+            was_empty,
+        );
+        self.uvs = append_attribute(self.uvs.take(), other.uvs.as_ref(), &vertex_map, was_empty);
+        self.face_normals = append_attribute(
+            self.face_normals.take(),
+            other.face_normals.as_ref(),
+            &face_map,
+            was_empty,
+        );
+
+        self.sharp_edges.extend(
+            other
+                .sharp_edges
+                .iter()
+                .filter_map(|he_id| halfedge_map.get(*he_id).copied()),
+        );
+
+        // the polygons of `other` are numbered after the ones of this mesh
+        let polygon_id_offset = self.polygon_ids.values().max().map_or(0, |max| max + 1);
+        for (old_id, new_id) in &face_map {
+            if let Some(polygon_id) = other.polygon_ids.get(old_id) {
+                self.polygon_ids
+                    .insert(*new_id, polygon_id + polygon_id_offset);
+            }
+        }
+
+        self.rebuild_bvh();
+    }
+}
+
+fn remap<K: Key>(map: &SecondaryMap<K, K>, old_id: K) -> K {
+    map.get(old_id).copied().unwrap_or_default()
+}
+
+/// Adds the remapped `other` values to `values`. Returns `None` if only one of both meshes has
+/// the attribute unless the mesh was empty before.
+fn append_attribute<K: Key, T: Copy>(
+    values: Option<SecondaryMap<K, T>>,
+    other: Option<&SecondaryMap<K, T>>,
+    map: &SecondaryMap<K, K>,
+    was_empty: bool,
+) -> Option<SecondaryMap<K, T>> {
+    let other = other?;
+    let mut values = values.or_else(|| was_empty.then(SecondaryMap::new))?;
+
+    for (old_id, new_id) in map {
+        if let Some(value) = other.get(old_id) {
+            values.insert(*new_id, *value);
+        }
+    }
+
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Vec2, Vec3};
+
+    use crate::primitives::Cuboid;
+
+    use super::*;
+
+    #[test]
+    fn test_append() {
+        let cube = MeshGraph::from(Cuboid {
+            half_extents: Vec3::splat(0.5),
+        });
+
+        let mut mesh_graph = cube.clone();
+        mesh_graph.compute_vertex_normals();
+
+        let mut other = cube.clone();
+        other.transform(Mat4::from_translation(Vec3::X * 3.0));
+        other.compute_vertex_normals();
+
+        mesh_graph.append(&other);
+
+        assert_eq!(mesh_graph.vertices.len(), 2 * cube.vertices.len());
+        assert_eq!(mesh_graph.halfedges.len(), 2 * cube.halfedges.len());
+        assert_eq!(mesh_graph.faces.len(), 2 * cube.faces.len());
+        assert_eq!(mesh_graph.connected_components().len(), 2);
+
+        assert!(mesh_graph.is_closed());
+        assert!(mesh_graph.verify_twins().is_empty());
+        assert!(mesh_graph.verify_bvh_consistency().is_empty());
+        assert!((mesh_graph.volume() - 2.0).abs() < 1e-5);
+        assert_eq!(mesh_graph.centroid(), Vec3::X * 1.5);
+        assert_eq!(
+            mesh_graph.vertex_normals.as_ref().unwrap().len(),
+            mesh_graph.vertices.len()
+        );
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            assert_eq!(
+                vertex.outgoing_halfedges(&mesh_graph).count(),
+                mesh_graph.outgoing_halfedges[vertex_id].len()
+            );
+        }
+
+        // the appended faces can be found by spatial queries
+        let (_, z) = mesh_graph.raycast_down(Vec2::new(3.0, 0.0), 5.0).unwrap();
+        assert!((z - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_append_to_empty() {
+        let mut cube = MeshGraph::from(Cuboid {
+            half_extents: Vec3::ONE,
+        });
+        cube.compute_vertex_normals();
+
+        let mut mesh_graph = MeshGraph::new();
+        mesh_graph.append(&cube);
+
+        assert_eq!(mesh_graph.faces.len(), cube.faces.len());
+        assert!(mesh_graph.vertex_normals.is_some());
+        assert!(mesh_graph.is_closed());
+    }
+
+    #[test]
+    fn test_append_polygons() {
+        let positions = (0..8)
+            .map(|i| {
+                let sign = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+                Vec3::new(sign(0), sign(1), sign(2))
+            })
+            .collect::<Vec<_>>();
+        let quads = [
+            vec![0, 4, 6, 2],
+            vec![1, 3, 7, 5],
+            vec![0, 1, 5, 4],
+            vec![2, 6, 7, 3],
+            vec![0, 2, 3, 1],
+            vec![4, 5, 7, 6],
+        ];
+        let cage = MeshGraph::from_polygons(&positions, &quads);
+
+        let mut mesh_graph = cage.clone();
+        let mut other = cage;
+        other.transform(Mat4::from_translation(Vec3::X * 3.0));
+        mesh_graph.append(&other);
+
+        let polygons = mesh_graph.merge_back_to_polygons();
+        assert_eq!(polygons.len(), 12);
+        assert!(polygons.iter().all(|polygon| polygon.len() == 4));
+    }
+}
@@ -0,0 +1,849 @@
+use glam::Vec3;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+
+use crate::{
+    HalfedgeId, MeshGraph, VertexId, error_none, plane_slice::newell_normal, utils::error,
+};
+
+/// A boundary loop is considered planar if no vertex is further away from its best-fit plane
+/// than this fraction of the loop's perimeter.
+const PLANARITY_TOLERANCE: f32 = 1e-3;
+
+/// [`MeshGraph::resample_boundary_loop`] keeps the vertices at which the loop turns by more than
+/// this angle as corners.
+const CORNER_ANGLE: f32 = std::f32::consts::FRAC_PI_6;
+
+/// Return value of [`MeshGraph::hole_metrics`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HoleMetrics {
+    /// The consecutive boundary halfedges of the loop as returned by [`MeshGraph::boundary_loops`]
+    pub loop_halfedges: Vec<HalfedgeId>,
+    /// Sum of the lengths of the loop's halfedges
+    pub perimeter: f32,
+    /// Area enclosed by the loop when projected onto its best-fit plane
+    pub planar_area: f32,
+    /// Whether all vertices of the loop (nearly) lie on its best-fit plane
+    pub is_planar: bool,
+}
+
+impl MeshGraph {
+    /// Returns all boundary loops of the mesh, i.e. the holes and the outlines of open patches.
+    ///
+    /// Every loop is a list of consecutive boundary halfedges. The loops are ordered by their
+    /// first halfedge and every loop starts with its smallest halfedge id.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn boundary_loops(&self) -> Vec<Vec<HalfedgeId>> {
+        let mut visited = HashSet::new();
+        let mut loops = vec![];
+
+        for (start_he_id, start_he) in &self.halfedges {
+            if !start_he.is_boundary() || visited.contains(&start_he_id) {
+                continue;
+            }
+
+            let mut loop_he_ids = vec![];
+            let mut he_id = start_he_id;
+
+            loop {
+                if !visited.insert(he_id) {
+                    error!("Boundary loop is not closed");
+                    break;
+                }
+                loop_he_ids.push(he_id);
+
+                let Some(next_he_id) = self
+                    .halfedges
+                    .get(he_id)
+                    .or_else(error_none!("Halfedge not found"))
+                    .and_then(|he| self.vertices.get(he.end_vertex))
+                    .and_then(|vertex| vertex.outgoing_boundary_halfedge(self))
+                else {
+                    break;
+                };
+
+                if next_he_id == start_he_id {
+                    break;
+                }
+                he_id = next_he_id;
+            }
+
+            loops.push(loop_he_ids);
+        }
+
+        loops
+    }
+
+    /// Computes the perimeter and enclosed area of every boundary loop
+    /// (see [`MeshGraph::boundary_loops`]) in the same order.
+    ///
+    /// This helps to decide which holes can be filled automatically (small and planar) and
+    /// which need manual attention. Note that the outlines of open patches are boundary loops
+    /// as well.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn hole_metrics(&self) -> Vec<HoleMetrics> {
+        self.boundary_loops()
+            .into_iter()
+            .map(|loop_halfedges| {
+                let Some(points) = loop_halfedges
+                    .iter()
+                    .map(|he_id| {
+                        self.halfedges
+                            .get(*he_id)
+                            .and_then(|he| self.positions.get(he.end_vertex))
+                            .copied()
+                    })
+                    .collect::<Option<Vec<Vec3>>>()
+                else {
+                    error!("Boundary vertex position not found");
+                    return HoleMetrics {
+                        loop_halfedges,
+                        ..Default::default()
+                    };
+                };
+
+                let perimeter = points
+                    .iter()
+                    .circular_tuple_windows()
+                    .map(|(a, b)| a.distance(*b))
+                    .sum::<f32>();
+
+                let area_vector = newell_normal(&points);
+
+                let is_planar = area_vector.try_normalize().is_some_and(|normal| {
+                    let center = points.iter().sum::<Vec3>() / points.len() as f32;
+
+                    points
+                        .iter()
+                        .all(|p| (*p - center).dot(normal).abs() <= perimeter * PLANARITY_TOLERANCE)
+                });
+
+                HoleMetrics {
+                    loop_halfedges,
+                    perimeter,
+                    planar_area: area_vector.length() * 0.5,
+                    is_planar,
+                }
+            })
+            .collect()
+    }
+
+    /// Smoothes the boundary loops by moving every boundary vertex towards the center of its
+    /// two neighbours on the loop (1D Laplacian). Interior vertices are not moved.
+    ///
+    /// `lambda` in `[0, 1]` controls how far the vertices are moved in each iteration.
+    ///
+    /// A plain Laplacian pulls the loops inwards in every iteration. To counter this, every
+    /// loop is scaled about its centroid after each iteration such that the area it encloses
+    /// (see [`HoleMetrics::planar_area`]) stays the same.
+    ///
+    /// Vertex normals (if present) are recomputed and the BVH is refitted.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn smooth_boundary_loops(&mut self, iterations: usize, lambda: f32) {
+        let loop_vertices = self
+            .boundary_loops()
+            .into_iter()
+            .map(|loop_he_ids| {
+                loop_he_ids
+                    .into_iter()
+                    .filter_map(|he_id| self.halfedges.get(he_id).map(|he| he.end_vertex))
+                    .collect_vec()
+            })
+            .filter(|vertex_ids| vertex_ids.len() >= 3)
+            .collect_vec();
+
+        let loop_positions = |mesh_graph: &MeshGraph, vertex_ids: &[VertexId]| {
+            vertex_ids
+                .iter()
+                .map(|vertex_id| mesh_graph.positions.get(*vertex_id).copied())
+                .collect::<Option<Vec<Vec3>>>()
+        };
+
+        let target_areas = loop_vertices
+            .iter()
+            .map(|vertex_ids| {
+                loop_positions(self, vertex_ids)
+                    .map(|positions| newell_normal(&positions).length())
+                    .unwrap_or_default()
+            })
+            .collect_vec();
+
+        for _ in 0..iterations {
+            for (vertex_ids, target_area) in loop_vertices.iter().zip(&target_areas) {
+                let Some(positions) = loop_positions(self, vertex_ids) else {
+                    error!("Boundary vertex position not found");
+                    continue;
+                };
+
+                let smoothed = positions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pos)| {
+                        let prev = positions[(i + positions.len() - 1) % positions.len()];
+                        let next = positions[(i + 1) % positions.len()];
+
+                        pos.lerp((prev + next) * 0.5, lambda)
+                    })
+                    .collect_vec();
+
+                // the area scales quadratically with the loop
+                let area = newell_normal(&smoothed).length();
+                let scale = if area > f32::EPSILON {
+                    (target_area / area).sqrt()
+                } else {
+                    1.0
+                };
+                let centroid = smoothed.iter().sum::<Vec3>() / smoothed.len() as f32;
+
+                for (vertex_id, pos) in vertex_ids.iter().zip(smoothed) {
+                    self.positions[*vertex_id] = centroid + (pos - centroid) * scale;
+                }
+            }
+        }
+
+        if self.vertex_normals.is_some() {
+            self.compute_vertex_normals();
+        }
+
+        for face in self.faces.values() {
+            self.bvh
+                .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
+        }
+        self.refit_bvh();
+    }
+
+    /// Redistributes the vertices of a boundary loop so that its edges are roughly
+    /// `target_spacing` long.
+    ///
+    /// `loop_halfedges` are the consecutive boundary halfedges of the loop as returned by
+    /// [`MeshGraph::boundary_loops`]. Vertices at which the loop turns by more than 30° are
+    /// kept as corners. Every section between two corners gets
+    /// `length / target_spacing` (rounded) edges by splitting the longest and collapsing the
+    /// shortest boundary edges of the section. Afterwards the vertices of every section are
+    /// placed at equal arc length distances on the original polyline so the shape of the loop
+    /// is kept. A loop without corners is treated as a single section starting at its first
+    /// vertex.
+    ///
+    /// Boundary edges that can't be collapsed safely are skipped, which can leave the loop
+    /// with more vertices than intended.
+    ///
+    /// Vertex normals (if present) are recomputed and the BVH is refitted.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn resample_boundary_loop(&mut self, loop_halfedges: &[HalfedgeId], target_spacing: f32) {
+        if target_spacing <= 0.0 {
+            error!("Target spacing has to be positive");
+            return;
+        }
+
+        let Some((loop_vertices, polyline)) = self.boundary_loop_vertices(loop_halfedges) else {
+            return;
+        };
+
+        // arc length at every vertex of the polyline followed by the perimeter
+        let arc_lengths = std::iter::once(0.0)
+            .chain(
+                polyline
+                    .iter()
+                    .circular_tuple_windows()
+                    .scan(0.0, |length, (a, b)| {
+                        *length += a.distance(*b);
+                        Some(*length)
+                    }),
+            )
+            .collect_vec();
+        let perimeter = arc_lengths[polyline.len()];
+
+        let mut corner_indices = (0..polyline.len())
+            .filter(|&i| {
+                let prev = polyline[(i + polyline.len() - 1) % polyline.len()];
+                let next = polyline[(i + 1) % polyline.len()];
+                (polyline[i] - prev).angle_between(next - polyline[i]) > CORNER_ANGLE
+            })
+            .collect_vec();
+        if corner_indices.is_empty() {
+            corner_indices.push(0);
+        }
+
+        // the arc length at which each corner's section starts
+        let corner_arcs = corner_indices
+            .iter()
+            .map(|i| (loop_vertices[*i], arc_lengths[*i]))
+            .collect::<HashMap<_, _>>();
+
+        let section_length = |sections: &[(VertexId, Vec<usize>)], k: usize| {
+            let start = corner_arcs[&sections[k].0];
+            let end = corner_arcs[&sections[(k + 1) % sections.len()].0];
+            if end > start {
+                end - start
+            } else {
+                end + perimeter - start
+            }
+        };
+
+        // at least three edges in total
+        let min_section_edges = 3_usize.div_ceil(corner_indices.len());
+
+        let mut loop_he_ids = loop_halfedges.to_vec();
+
+        // every iteration splits or collapses one edge
+        for _ in 0..2 * (loop_halfedges.len() + (perimeter / target_spacing) as usize) {
+            let Some(start_vertices) = self.loop_start_vertices(&loop_he_ids) else {
+                return;
+            };
+            let sections = loop_sections(&start_vertices, &corner_arcs);
+
+            let edge_count = |k: usize| {
+                ((section_length(&sections, k) / target_spacing).round() as usize)
+                    .max(min_section_edges)
+            };
+            let length_squared = |i: &usize| self.halfedges[loop_he_ids[*i]].length_squared(self);
+
+            if let Some(i) = (0..sections.len())
+                .find(|k| sections[*k].1.len() < edge_count(*k))
+                .and_then(|k| {
+                    sections[k]
+                        .1
+                        .iter()
+                        .copied()
+                        .max_by(|a, b| length_squared(a).total_cmp(&length_squared(b)))
+                })
+            {
+                let segment_he_ids = self.subdivide_edge_n(loop_he_ids[i], 2);
+                if segment_he_ids.len() != 2 {
+                    break;
+                }
+
+                loop_he_ids.splice(i..=i, segment_he_ids);
+                continue;
+            }
+
+            // corners are never collapsed away
+            let candidates = (0..sections.len())
+                .filter(|k| sections[*k].1.len() > edge_count(*k))
+                .flat_map(|k| sections[k].1.iter().copied())
+                .filter(|i| {
+                    !corner_arcs.contains_key(&start_vertices[*i])
+                        && !corner_arcs
+                            .contains_key(&start_vertices[(i + 1) % start_vertices.len()])
+                })
+                .sorted_by(|a, b| length_squared(a).total_cmp(&length_squared(b)))
+                .collect_vec();
+
+            // collapse from the inside because the removed vertex has to be reached by rotating
+            // around it starting at its outgoing halfedge which is then the boundary halfedge
+            let mut collapse = None;
+            for i in candidates {
+                let Some(inner_he_id) = self.halfedges[loop_he_ids[i]].twin else {
+                    continue;
+                };
+
+                if let Some(checked) = self.can_collapse_edge_safely(inner_he_id) {
+                    collapse = Some((i, inner_he_id, checked));
+                    break;
+                }
+            }
+
+            let Some((i, inner_he_id, (twin_id, start_v_id, end_v_id, center_pos))) = collapse
+            else {
+                break;
+            };
+
+            self.collapse_edge_inner(inner_he_id, twin_id, start_v_id, end_v_id, center_pos);
+
+            // the following halfedge survives the collapse
+            let next_he_id = loop_he_ids[(i + 1) % loop_he_ids.len()];
+            let Some(walked) = self.walk_boundary_loop(next_he_id) else {
+                return;
+            };
+            loop_he_ids = walked;
+        }
+
+        let Some(start_vertices) = self.loop_start_vertices(&loop_he_ids) else {
+            return;
+        };
+        let sections = loop_sections(&start_vertices, &corner_arcs);
+
+        let point_at = |arc_length: f32| {
+            let arc_length = arc_length % perimeter;
+            let segment = arc_lengths
+                .partition_point(|length| *length <= arc_length)
+                .clamp(1, polyline.len())
+                - 1;
+            let segment_length = arc_lengths[segment + 1] - arc_lengths[segment];
+            let t = if segment_length > 0.0 {
+                (arc_length - arc_lengths[segment]) / segment_length
+            } else {
+                0.0
+            };
+
+            polyline[segment].lerp(polyline[(segment + 1) % polyline.len()], t)
+        };
+
+        for (k, (corner, he_indices)) in sections.iter().enumerate() {
+            let start = corner_arcs[corner];
+            let spacing = section_length(&sections, k) / he_indices.len() as f32;
+
+            for (j, i) in he_indices.iter().enumerate() {
+                let vertex_id = start_vertices[*i];
+                self.positions[vertex_id] = point_at(start + j as f32 * spacing);
+                // collapsing moves the outgoing halfedges away from the boundary
+                self.vertices[vertex_id].outgoing_halfedge = Some(loop_he_ids[*i]);
+            }
+        }
+
+        if self.vertex_normals.is_some() {
+            self.compute_vertex_normals();
+        }
+
+        let face_ids = start_vertices
+            .iter()
+            .flat_map(|vertex_id| self.vertices[*vertex_id].faces(self))
+            .collect::<HashSet<_>>();
+
+        for face_id in face_ids {
+            if let Some(face) = self.faces.get(face_id) {
+                self.bvh
+                    .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                self.change_tracker.mark_face(face.id);
+            }
+        }
+        self.refit_bvh();
+    }
+
+    /// Checks that `loop_halfedges` is a closed loop of consecutive boundary halfedges and
+    /// returns their start vertices together with the positions of these.
+    fn boundary_loop_vertices(
+        &self,
+        loop_halfedges: &[HalfedgeId],
+    ) -> Option<(Vec<VertexId>, Vec<Vec3>)> {
+        if loop_halfedges.len() < 3 {
+            error!("A boundary loop needs at least three halfedges");
+            return None;
+        }
+
+        let mut vertex_ids = Vec::with_capacity(loop_halfedges.len());
+        let mut positions = Vec::with_capacity(loop_halfedges.len());
+
+        for (he_id, next_he_id) in loop_halfedges.iter().circular_tuple_windows() {
+            let he = self
+                .halfedges
+                .get(*he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+            let next_he = self
+                .halfedges
+                .get(*next_he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+
+            if !he.is_boundary() {
+                error!("Halfedge is not a boundary halfedge");
+                return None;
+            }
+
+            if next_he.start_vertex(self) != Some(he.end_vertex) {
+                error!("The halfedges don't form a loop");
+                return None;
+            }
+
+            let start_v_id = he
+                .start_vertex(self)
+                .or_else(error_none!("Start vertex not found"))?;
+            vertex_ids.push(start_v_id);
+            positions.push(
+                *self
+                    .positions
+                    .get(start_v_id)
+                    .or_else(error_none!("Position not found"))?,
+            );
+        }
+
+        Some((vertex_ids, positions))
+    }
+
+    /// Returns the start vertices of `loop_he_ids`.
+    fn loop_start_vertices(&self, loop_he_ids: &[HalfedgeId]) -> Option<Vec<VertexId>> {
+        loop_he_ids
+            .iter()
+            .map(|he_id| self.halfedges.get(*he_id)?.start_vertex(self))
+            .collect::<Option<Vec<_>>>()
+            .or_else(error_none!("Boundary loop vertex not found"))
+    }
+
+    /// Follows the boundary halfedges from `start_he_id` until it is reached again.
+    fn walk_boundary_loop(&self, start_he_id: HalfedgeId) -> Option<Vec<HalfedgeId>> {
+        let mut loop_he_ids = vec![start_he_id];
+        let mut he_id = start_he_id;
+
+        loop {
+            let end_v_id = self
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge not found"))?
+                .end_vertex;
+
+            // the outgoing halfedge of the vertex isn't necessarily the boundary one
+            he_id = self
+                .outgoing_halfedges
+                .get(end_v_id)
+                .into_iter()
+                .flatten()
+                .copied()
+                .find(|he_id| {
+                    self.halfedges
+                        .get(*he_id)
+                        .is_some_and(|he| he.is_boundary())
+                })
+                .or_else(error_none!("Boundary loop is not closed"))?;
+
+            if he_id == start_he_id {
+                return Some(loop_he_ids);
+            }
+
+            if loop_he_ids.len() > self.halfedges.len() {
+                error!("Boundary loop is not closed");
+                return None;
+            }
+
+            loop_he_ids.push(he_id);
+        }
+    }
+}
+
+/// Groups the indices of a boundary loop into sections that start at one of the `corners`.
+/// `start_vertices` are the start vertices of the loop's halfedges.
+fn loop_sections<T>(
+    start_vertices: &[VertexId],
+    corners: &HashMap<VertexId, T>,
+) -> Vec<(VertexId, Vec<usize>)> {
+    let Some(first) = start_vertices
+        .iter()
+        .position(|vertex_id| corners.contains_key(vertex_id))
+    else {
+        error!("Boundary loop has no corner");
+        return vec![];
+    };
+
+    let mut sections: Vec<(VertexId, Vec<usize>)> = vec![];
+
+    for j in 0..start_vertices.len() {
+        let i = (first + j) % start_vertices.len();
+
+        if corners.contains_key(&start_vertices[i]) {
+            sections.push((start_vertices[i], vec![]));
+        }
+        if let Some((_, indices)) = sections.last_mut() {
+            indices.push(i);
+        }
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SecondaryMap;
+
+    use std::f32::consts::PI;
+
+    use glam::Vec3Swizzles;
+
+    use crate::{
+        primitives::Cylinder,
+        utils::{grid, grid_positions_and_indices},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_boundary_loops() {
+        let (mut mesh_graph, _) = grid(5);
+
+        assert_eq!(mesh_graph.boundary_loops().len(), 1);
+        assert_eq!(mesh_graph.boundary_loops()[0].len(), 16);
+
+        // punch a hexagonal hole around the center vertex
+        let center_face_ids = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| face.center(&mesh_graph).distance(Vec3::new(2.0, 2.0, 0.0)) < 0.8)
+            .map(|(face_id, _)| face_id)
+            .collect_vec();
+        for face_id in center_face_ids {
+            mesh_graph.remove_face(face_id);
+        }
+
+        let loop_lengths = mesh_graph
+            .boundary_loops()
+            .iter()
+            .map(|loop_he_ids| loop_he_ids.len())
+            .sorted()
+            .collect_vec();
+        assert_eq!(loop_lengths, vec![6, 16]);
+    }
+
+    #[test]
+    fn test_hole_metrics() {
+        let (mut mesh_graph, _) = grid(9);
+
+        // a hexagonal hole around (2, 2) and a 2x2 square hole around (6, 6)
+        let hole_face_ids = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| {
+                let center = face.center(&mesh_graph);
+                center.distance(Vec3::new(2.0, 2.0, 0.0)) < 0.8
+                    || (center.x > 5.0 && center.x < 7.0 && center.y > 5.0 && center.y < 7.0)
+            })
+            .map(|(face_id, _)| face_id)
+            .collect_vec();
+        for face_id in hole_face_ids {
+            mesh_graph.remove_face(face_id);
+        }
+
+        // bend the outline out of the plane
+        let corner_id = mesh_graph
+            .positions
+            .iter()
+            .find(|(_, pos)| **pos == Vec3::ZERO)
+            .map(|(vertex_id, _)| vertex_id)
+            .unwrap();
+        mesh_graph.positions[corner_id].z = 2.0;
+
+        let hole_metrics = mesh_graph.hole_metrics();
+
+        assert_eq!(
+            hole_metrics
+                .iter()
+                .map(|metrics| metrics.loop_halfedges.clone())
+                .collect_vec(),
+            mesh_graph.boundary_loops()
+        );
+
+        let metrics = hole_metrics
+            .iter()
+            .map(|metrics| (metrics.perimeter, metrics.planar_area, metrics.is_planar))
+            .sorted_by(|(a, ..), (b, ..)| a.total_cmp(b))
+            .collect_vec();
+
+        assert_eq!(metrics.len(), 3);
+
+        let (perimeter, area, is_planar) = metrics[0];
+        assert!((perimeter - (4.0 + 2.0 * 2.0_f32.sqrt())).abs() < 1e-5);
+        assert!((area - 3.0).abs() < 1e-5);
+        assert!(is_planar);
+
+        let (perimeter, area, is_planar) = metrics[1];
+        assert!((perimeter - 8.0).abs() < 1e-5);
+        assert!((area - 4.0).abs() < 1e-5);
+        assert!(is_planar);
+
+        let (perimeter, _, is_planar) = metrics[2];
+        assert!(perimeter > 32.0);
+        assert!(!is_planar);
+    }
+
+    #[test]
+    fn test_smooth_boundary_loops() {
+        let (mut mesh_graph, _) = grid(7);
+
+        let boundary_vertices = mesh_graph
+            .boundary_loops()
+            .into_iter()
+            .flatten()
+            .map(|he_id| mesh_graph.halfedges[he_id].end_vertex)
+            .collect::<HashSet<_>>();
+
+        // add noise to the boundary within the plane
+        for (i, vertex_id) in boundary_vertices.iter().sorted().enumerate() {
+            let offset = if i % 2 == 0 { 0.3 } else { -0.3 };
+            mesh_graph.positions[*vertex_id] += Vec3::new(offset, -offset, 0.0);
+        }
+
+        let roughness = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .boundary_loops()
+                .iter()
+                .flat_map(|loop_he_ids| {
+                    loop_he_ids
+                        .iter()
+                        .map(|he_id| mesh_graph.positions[mesh_graph.halfedges[*he_id].end_vertex])
+                        .circular_tuple_windows()
+                        .map(|(a, b, c)| (b - (a + c) * 0.5).length())
+                })
+                .sum::<f32>()
+        };
+
+        let interior_positions = mesh_graph
+            .positions
+            .iter()
+            .filter(|(vertex_id, _)| !boundary_vertices.contains(vertex_id))
+            .map(|(vertex_id, pos)| (vertex_id, *pos))
+            .collect::<SecondaryMap<VertexId, Vec3>>();
+
+        let roughness_before = roughness(&mesh_graph);
+        mesh_graph.smooth_boundary_loops(5, 0.5);
+
+        assert!(roughness(&mesh_graph) < roughness_before * 0.5);
+
+        for (vertex_id, pos) in interior_positions {
+            assert_eq!(mesh_graph.positions[vertex_id], pos);
+        }
+        for vertex_id in boundary_vertices {
+            assert_eq!(mesh_graph.positions[vertex_id].z, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_smooth_boundary_loops_keeps_area() {
+        let (mut mesh_graph, _) = grid(9);
+
+        // a 2x2 square hole in the middle
+        let hole_face_ids = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| {
+                let center = face.center(&mesh_graph);
+                center.x > 3.0 && center.x < 5.0 && center.y > 3.0 && center.y < 5.0
+            })
+            .map(|(face_id, _)| face_id)
+            .collect_vec();
+        for face_id in hole_face_ids {
+            mesh_graph.remove_face(face_id);
+        }
+
+        let metrics = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .hole_metrics()
+                .into_iter()
+                .map(|metrics| (metrics.perimeter, metrics.planar_area))
+                .sorted_by(|(a, _), (b, _)| a.total_cmp(b))
+                .collect_vec()
+        };
+
+        let before = metrics(&mesh_graph);
+        assert_eq!(before.len(), 2);
+
+        mesh_graph.smooth_boundary_loops(20, 0.5);
+
+        for ((perimeter_before, area_before), (perimeter, area)) in
+            before.into_iter().zip(metrics(&mesh_graph))
+        {
+            assert!((area - area_before).abs() < area_before * 1e-4);
+            // the corners are rounded but the loops don't shrink
+            assert!(perimeter < perimeter_before);
+            assert!(perimeter > perimeter_before * 0.85);
+        }
+    }
+
+    #[test]
+    fn test_resample_boundary_loop() {
+        let size = 6;
+
+        // the columns bunch up towards x = 0
+        let (mut positions, indices) = grid_positions_and_indices(size, size);
+        for pos in &mut positions {
+            pos.x = (pos.x / (size - 1) as f32).powi(2) * 5.0;
+        }
+
+        let mut mesh_graph = MeshGraph::indexed_triangles(&positions, &indices);
+        let mut coarse = mesh_graph.clone();
+
+        let loop_he_ids = mesh_graph.boundary_loops().remove(0);
+        let boundary = |mesh_graph: &MeshGraph| {
+            let loops = mesh_graph.boundary_loops();
+            assert_eq!(loops.len(), 1);
+
+            loops[0]
+                .iter()
+                .map(|he_id| {
+                    let he = mesh_graph.halfedges[*he_id];
+                    (mesh_graph.positions[he.end_vertex], he.length(mesh_graph))
+                })
+                .collect_vec()
+        };
+        let corners = [
+            Vec3::ZERO,
+            Vec3::new(5.0, 0.0, 0.0),
+            Vec3::new(5.0, 5.0, 0.0),
+            Vec3::new(0.0, 5.0, 0.0),
+        ];
+
+        let before = boundary(&mesh_graph);
+        assert!(before.iter().any(|(_, length)| *length < 0.3));
+        assert!(before.iter().any(|(_, length)| *length > 1.5));
+
+        // every side of the 5x5 square gets 6 edges
+        mesh_graph.resample_boundary_loop(&loop_he_ids, 0.8);
+
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        let after = boundary(&mesh_graph);
+        assert_eq!(after.len(), 4 * 6);
+
+        for (pos, length) in &after {
+            assert!((length - 5.0 / 6.0).abs() < 1e-4);
+
+            // the outline is still the square
+            assert!(
+                pos.x.abs() < 1e-5
+                    || (pos.x - 5.0).abs() < 1e-5
+                    || pos.y.abs() < 1e-5
+                    || (pos.y - 5.0).abs() < 1e-5
+            );
+        }
+        for corner in corners {
+            assert!(after.iter().any(|(pos, _)| pos.distance(corner) < 1e-5));
+        }
+
+        // fewer vertices than before
+        let loop_he_ids = coarse.boundary_loops().remove(0);
+        coarse.resample_boundary_loop(&loop_he_ids, 2.5);
+
+        assert!(coarse.verify_twins().is_empty());
+
+        let after = boundary(&coarse);
+        assert_eq!(after.len(), 4 * 2);
+        assert!(after.iter().all(|(_, length)| (length - 2.5).abs() < 1e-4));
+        for corner in corners {
+            assert!(after.iter().any(|(pos, _)| pos.distance(corner) < 1e-5));
+        }
+    }
+
+    #[test]
+    fn test_resample_smooth_boundary_loop() {
+        let radius = 1.0;
+        let mut mesh_graph = MeshGraph::from(Cylinder {
+            radius,
+            height: 2.0,
+            segments: 32,
+            caps: false,
+        });
+
+        let loops = mesh_graph.boundary_loops();
+        assert_eq!(loops.len(), 2);
+
+        let target_spacing = 0.5;
+        mesh_graph.resample_boundary_loop(&loops[0], target_spacing);
+
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        let loop_lengths = mesh_graph
+            .boundary_loops()
+            .iter()
+            .map(|loop_he_ids| loop_he_ids.len())
+            .sorted()
+            .collect_vec();
+        let perimeter = 64.0 * (PI / 32.0).sin() * radius;
+        assert_eq!(
+            loop_lengths,
+            vec![(perimeter / target_spacing).round() as usize, 32]
+        );
+
+        // the vertices stay on the original polygon
+        for he in mesh_graph.halfedges.values().filter(|he| he.is_boundary()) {
+            let distance = mesh_graph.positions[he.end_vertex].xz().length();
+            assert!(distance <= radius + 1e-5);
+            assert!(distance >= radius * (PI / 32.0).cos() - 1e-5);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use slotmap::SecondaryMap;
+
+use crate::{MeshGraph, VertexId, utils::unwrap_or_return};
+
+/// How the weight of a brush decreases from its center (`1.0`) to its radius (`0.0`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Falloff {
+    /// Linear decrease
+    Linear,
+    /// Smoothstep curve with zero slope at the center and at the radius
+    #[default]
+    Smooth,
+    /// Spherical profile that stays high for most of the radius and drops steeply at the end
+    Sphere,
+    /// Quadratic curve that drops quickly away from the center
+    Sharp,
+}
+
+impl Falloff {
+    /// Maps the distance `t` from the center, relative to the radius, to a weight. `t` is clamped
+    /// to the range `0..=1`.
+    pub fn weight(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Falloff::Linear => 1.0 - t,
+            Falloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+            Falloff::Sphere => (1.0 - t * t).sqrt(),
+            Falloff::Sharp => (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+impl MeshGraph {
+    /// Computes the weight of every vertex within `radius` of `center_vertex` for a brush with
+    /// the given `falloff`.
+    ///
+    /// Distances are measured along the edges of the mesh (Dijkstra), which approximates the
+    /// geodesic distance. Thus the brush doesn't bleed through thin parts of the mesh.
+    /// Vertices farther away than `radius` are not part of the result.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn brush_weights(
+        &self,
+        center_vertex: VertexId,
+        radius: f32,
+        falloff: Falloff,
+    ) -> SecondaryMap<VertexId, f32> {
+        let mut weights = SecondaryMap::new();
+
+        if !self.vertices.contains_key(center_vertex) {
+            return weights;
+        }
+
+        let mut distances = SecondaryMap::new();
+        let mut queue = BinaryHeap::new();
+
+        distances.insert(center_vertex, 0.0);
+        queue.push(DistanceCandidate {
+            distance: 0.0,
+            vertex_id: center_vertex,
+        });
+
+        while let Some(DistanceCandidate {
+            distance,
+            vertex_id,
+        }) = queue.pop()
+        {
+            if distances
+                .get(vertex_id)
+                .is_some_and(|best: &f32| distance > *best)
+            {
+                // outdated entry
+                continue;
+            }
+
+            let pos =
+                *unwrap_or_return!(self.positions.get(vertex_id), "Position missing", weights);
+
+            for neighbour_id in self.vertices[vertex_id].neighbours(self) {
+                let Some(neighbour_pos) = self.positions.get(neighbour_id) else {
+                    continue;
+                };
+
+                let neighbour_distance = distance + pos.distance(*neighbour_pos);
+
+                if neighbour_distance > radius
+                    || distances
+                        .get(neighbour_id)
+                        .is_some_and(|best| neighbour_distance >= *best)
+                {
+                    continue;
+                }
+
+                distances.insert(neighbour_id, neighbour_distance);
+                queue.push(DistanceCandidate {
+                    distance: neighbour_distance,
+                    vertex_id: neighbour_id,
+                });
+            }
+        }
+
+        for (vertex_id, distance) in distances {
+            let t = if radius > 0.0 { distance / radius } else { 0.0 };
+            weights.insert(vertex_id, falloff.weight(t));
+        }
+
+        weights
+    }
+}
+
+/// Entry of the Dijkstra queue in [`MeshGraph::brush_weights`]. Ordered such that the
+/// smallest distance is popped first.
+struct DistanceCandidate {
+    distance: f32,
+    vertex_id: VertexId,
+}
+
+impl PartialEq for DistanceCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DistanceCandidate {}
+
+impl PartialOrd for DistanceCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DistanceCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| other.vertex_id.cmp(&self.vertex_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid;
+
+    use super::*;
+
+    #[test]
+    fn test_brush_weights() {
+        let size = 9;
+
+        let (mesh_graph, vertex_ids) = grid(size);
+
+        let center = vertex_ids[4 * size + 4];
+
+        for falloff in [
+            Falloff::Linear,
+            Falloff::Smooth,
+            Falloff::Sphere,
+            Falloff::Sharp,
+        ] {
+            let weights = mesh_graph.brush_weights(center, 2.0, falloff);
+
+            assert_eq!(weights[center], 1.0);
+
+            // exactly two edges away along the grid lines
+            for index in [4 * size + 6, 4 * size + 2, 6 * size + 4, 2 * size + 4] {
+                assert_eq!(weights[vertex_ids[index]], 0.0);
+            }
+
+            // one edge away
+            assert!(weights[vertex_ids[4 * size + 5]] > 0.0);
+            assert!(weights[vertex_ids[4 * size + 5]] < 1.0);
+
+            // outside of the radius
+            assert!(!weights.contains_key(vertex_ids[4 * size + 7]));
+            assert!(!weights.contains_key(vertex_ids[0]));
+
+            assert!(weights.values().all(|weight| (0.0..=1.0).contains(weight)));
+        }
+    }
+}
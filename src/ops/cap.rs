@@ -0,0 +1,241 @@
+use crate::utils::error;
+use itertools::Itertools;
+
+use crate::{
+    FaceId, MeshGraph, Polygon3, VertexId, plane_slice::project_onto_best_fit_plane,
+    point_grid::PointGrid, triangulate_polygon,
+};
+
+/// Distance up to which a contour point is considered to coincide with a boundary vertex.
+const SNAP_DISTANCE: f32 = 1e-4;
+
+impl MeshGraph {
+    /// Closes the hole described by the closed `contour` (e.g. from [`crate::plane_slice`])
+    /// with a triangulated cap.
+    ///
+    /// Contour points that coincide with existing boundary vertices reuse these vertices so the
+    /// cap is stitched to the boundary halfedges of the mesh. All other points are inserted as
+    /// new vertices. The contour is projected onto its best-fit plane for the triangulation
+    /// so it doesn't have to be perfectly planar.
+    ///
+    /// The cap is oriented such that it continues the existing boundary. If the contour doesn't
+    /// touch any boundary edge, the faces follow the winding of the contour.
+    ///
+    /// Returns the ids of the created faces.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, contour)))]
+    pub fn cap_contour(&mut self, contour: &Polygon3) -> Vec<FaceId> {
+        let mut points = contour.vertices.iter().copied().collect_vec();
+
+        if let (Some(first), Some(last)) = (points.first(), points.last())
+            && points.len() > 1
+            && first.distance_squared(*last) < 1e-6
+        {
+            points.pop();
+        }
+
+        if points.len() < 3 {
+            error!("Contour needs at least three distinct points");
+            return vec![];
+        }
+
+        let Some(polygon) = project_onto_best_fit_plane(&points) else {
+            error!("Contour is degenerate");
+            return vec![];
+        };
+
+        let boundary_vertices = self.boundary_vertex_grid();
+
+        let vertex_ids = points
+            .iter()
+            .map(|p| {
+                boundary_vertices
+                    .closest_within(*p, SNAP_DISTANCE)
+                    .map(|(_, vertex_id)| vertex_id)
+                    .unwrap_or_else(|| self.add_vertex(*p))
+            })
+            .collect_vec();
+
+        // The cap has to use the free halfedges of the boundary edges it touches
+        let mut follow_contour = true;
+        for (&a, &b) in vertex_ids.iter().circular_tuple_windows() {
+            if self.is_boundary_halfedge_from_to(a, b) {
+                break;
+            }
+            if self.is_boundary_halfedge_from_to(b, a) {
+                follow_contour = false;
+                break;
+            }
+        }
+
+        triangulate_polygon(&polygon)
+            .into_iter()
+            .filter_map(|[a, b, c]| {
+                let (a, b, c) = (vertex_ids[a], vertex_ids[b], vertex_ids[c]);
+
+                if follow_contour {
+                    self.add_face_from_vertices(a, b, c)
+                } else {
+                    self.add_face_from_vertices(a, c, b)
+                }
+                .map(|added| added.face_id)
+            })
+            .collect()
+    }
+
+    fn boundary_vertex_grid(&self) -> PointGrid<VertexId> {
+        let mut grid = PointGrid::new(SNAP_DISTANCE);
+
+        for (vertex_id, pos) in &self.positions {
+            let is_boundary = self
+                .outgoing_halfedges
+                .get(vertex_id)
+                .is_some_and(|he_ids| {
+                    he_ids.iter().any(|he_id| {
+                        self.halfedges
+                            .get(*he_id)
+                            .is_some_and(|he| he.is_boundary())
+                    })
+                });
+
+            if is_boundary {
+                grid.insert(*pos, vertex_id);
+            }
+        }
+
+        grid
+    }
+
+    fn is_boundary_halfedge_from_to(
+        &self,
+        start_vertex_id: VertexId,
+        end_vertex_id: VertexId,
+    ) -> bool {
+        self.halfedges_from_to(start_vertex_id, end_vertex_id)
+            .into_iter()
+            .any(|he_id| self.halfedges[he_id].is_boundary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use glam::Vec3;
+
+    use crate::{plane_slice, primitives::IcoSphere};
+
+    use super::*;
+
+    #[test]
+    fn test_cap_contour() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 2,
+        });
+
+        let top_faces = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| face.center(&mesh_graph).z > 1.2)
+            .map(|(face_id, _)| face_id)
+            .collect_vec();
+
+        for face_id in top_faces {
+            mesh_graph.remove_face(face_id);
+        }
+
+        // walk the boundary loop that was left behind by removing the top
+        let start_he_id = mesh_graph
+            .halfedges
+            .iter()
+            .find(|(_, he)| he.is_boundary())
+            .map(|(he_id, _)| he_id)
+            .unwrap();
+
+        let mut contour = Polygon3 {
+            vertices: Default::default(),
+        };
+        let mut he_id = start_he_id;
+        loop {
+            let end_vertex = mesh_graph.halfedges[he_id].end_vertex;
+            contour.vertices.push_back(mesh_graph.positions[end_vertex]);
+
+            he_id = mesh_graph.outgoing_halfedges[end_vertex]
+                .iter()
+                .copied()
+                .find(|he_id| mesh_graph.halfedges[*he_id].is_boundary())
+                .unwrap();
+
+            if he_id == start_he_id {
+                break;
+            }
+        }
+
+        let vertex_count = mesh_graph.vertices.len();
+
+        let face_ids = mesh_graph.cap_contour(&contour);
+
+        assert_eq!(face_ids.len(), contour.vertices.len() - 2);
+        assert_eq!(mesh_graph.vertices.len(), vertex_count);
+        assert!(mesh_graph.halfedges.values().all(|he| !he.is_boundary()));
+
+        for face_id in face_ids {
+            assert!(mesh_graph.faces[face_id].normal(&mesh_graph).unwrap().z > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_cap_plane_slice() {
+        let sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let contours = plane_slice(&sphere, Vec3::Z, 0.3).collect_vec();
+        assert_eq!(contours.len(), 1);
+        let contour = &contours[0];
+
+        // capping the cross-section on its own gives a flat disk
+        let mut cross_section = MeshGraph::new();
+        let face_ids = cross_section.cap_contour(contour);
+
+        assert!(!face_ids.is_empty());
+        for pos in cross_section.positions.values() {
+            assert!((pos.z - 0.3).abs() < 1e-5);
+        }
+        let area = face_ids
+            .iter()
+            .map(|face_id| cross_section.faces[*face_id].area(&cross_section))
+            .sum::<f32>();
+        let circle_area = PI * (1.0 - 0.3 * 0.3);
+        assert!(area < circle_area && area > circle_area * 0.9);
+
+        // a wall extruded upwards from the contour is closed at the bottom by the cap
+        let mut points = contour.vertices.iter().copied().collect_vec();
+        if points.first() == points.last() {
+            points.pop();
+        }
+        let n = points.len();
+        let wall_positions = points
+            .iter()
+            .copied()
+            .chain(points.iter().map(|pos| pos + Vec3::Z))
+            .collect_vec();
+        let wall_indices = (0..n)
+            .flat_map(|i| {
+                let j = (i + 1) % n;
+                [i, j, n + j, i, n + j, n + i]
+            })
+            .collect_vec();
+        let mut wall = MeshGraph::indexed_triangles(&wall_positions, &wall_indices);
+        assert_eq!(wall.boundary_loops().len(), 2);
+
+        let vertex_count = wall.vertices.len();
+        let face_ids = wall.cap_contour(contour);
+
+        assert_eq!(face_ids.len(), n - 2);
+        assert_eq!(wall.vertices.len(), vertex_count);
+        assert_eq!(wall.boundary_loops().len(), 1);
+        assert!(wall.verify_twins().is_empty());
+    }
+}
@@ -0,0 +1,391 @@
+use glam::Vec3;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+
+use crate::{MeshGraph, VertexId, utils::error};
+
+impl MeshGraph {
+    /// Applies one step of Catmull-Clark subdivision.
+    ///
+    /// This operates on the polygons returned by [`MeshGraph::merge_back_to_polygons`], so a
+    /// quad cage created with [`MeshGraph::from_polygons`] is subdivided as quads and not as
+    /// the triangles it is stored as. Every polygon with `n` vertices is replaced by `n` quads
+    /// that connect the face point, the edge points and the moved original vertices. The
+    /// result is triangulated again and remembers its quads, so the method can be applied
+    /// repeatedly.
+    ///
+    /// Boundary edges and sharp edges are treated as creases: their edge points are the edge
+    /// midpoints, vertices on exactly two creases follow the cubic B-spline rule of the crease
+    /// and vertices on more than two creases stay in place. The halves of sharp edges are
+    /// sharp again.
+    ///
+    /// UVs and vertex attributes are interpolated linearly: the original vertices keep their
+    /// values and the edge and face points get the averages of the edge and polygon vertices.
+    ///
+    /// The mesh is rebuilt, so all vertex, halfedge and face ids change and face user data is
+    /// dropped. Vertex normals are recomputed if present.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn catmull_clark(&mut self) {
+        let polygons = self.merge_back_to_polygons();
+
+        if polygons.is_empty() {
+            return;
+        }
+
+        let sorted = |a: VertexId, b: VertexId| if a < b { (a, b) } else { (b, a) };
+
+        let sharp_edges = self
+            .sharp_edges
+            .iter()
+            .filter_map(|he_id| {
+                let he = self.halfedges.get(*he_id)?;
+                Some(sorted(he.start_vertex(self)?, he.end_vertex))
+            })
+            .collect::<HashSet<_>>();
+
+        let face_points = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .iter()
+                    .map(|v_id| self.positions[*v_id])
+                    .sum::<Vec3>()
+                    / polygon.len() as f32
+            })
+            .collect_vec();
+
+        // the polygons on both sides of every edge
+        let mut edge_polygons = HashMap::<(VertexId, VertexId), Vec<usize>>::new();
+        for (polygon_idx, polygon) in polygons.iter().enumerate() {
+            for (a, b) in polygon.iter().circular_tuple_windows() {
+                edge_polygons
+                    .entry(sorted(*a, *b))
+                    .or_default()
+                    .push(polygon_idx);
+            }
+        }
+
+        let is_crease = |edge: &(VertexId, VertexId), polygon_ids: &[usize]| {
+            polygon_ids.len() != 2 || sharp_edges.contains(edge)
+        };
+
+        let mut positions = vec![];
+        let mut vertex_indices = HashMap::<VertexId, usize>::new();
+        let mut edge_indices = HashMap::<(VertexId, VertexId), usize>::new();
+
+        let mut vertex_edges = HashMap::<VertexId, Vec<(VertexId, VertexId)>>::new();
+        let mut vertex_polygons = HashMap::<VertexId, Vec<usize>>::new();
+        for edge in edge_polygons.keys() {
+            vertex_edges.entry(edge.0).or_default().push(*edge);
+            vertex_edges.entry(edge.1).or_default().push(*edge);
+        }
+        for (polygon_idx, polygon) in polygons.iter().enumerate() {
+            for vertex_id in polygon {
+                vertex_polygons
+                    .entry(*vertex_id)
+                    .or_default()
+                    .push(polygon_idx);
+            }
+        }
+
+        for (vertex_id, edges) in vertex_edges.iter().sorted_by_key(|(id, _)| **id) {
+            let pos = self.positions[*vertex_id];
+            let other = |edge: &(VertexId, VertexId)| {
+                if edge.0 == *vertex_id { edge.1 } else { edge.0 }
+            };
+
+            let creases = edges
+                .iter()
+                .filter(|edge| is_crease(edge, &edge_polygons[*edge]))
+                .collect_vec();
+
+            let new_pos = match creases.len() {
+                0 | 1 => {
+                    let n = edges.len() as f32;
+                    let adjacent_polygons = &vertex_polygons[vertex_id];
+
+                    let face_avg = adjacent_polygons
+                        .iter()
+                        .map(|idx| face_points[*idx])
+                        .sum::<Vec3>()
+                        / adjacent_polygons.len() as f32;
+                    let edge_avg = edges
+                        .iter()
+                        .map(|edge| (pos + self.positions[other(edge)]) * 0.5)
+                        .sum::<Vec3>()
+                        / n;
+
+                    (face_avg + 2.0 * edge_avg + (n - 3.0) * pos) / n
+                }
+                2 => {
+                    (self.positions[other(creases[0])]
+                        + 6.0 * pos
+                        + self.positions[other(creases[1])])
+                        / 8.0
+                }
+                _ => pos,
+            };
+
+            vertex_indices.insert(*vertex_id, positions.len());
+            positions.push(new_pos);
+        }
+
+        for (edge, polygon_ids) in edge_polygons.iter().sorted_by_key(|(edge, _)| **edge) {
+            let midpoint = (self.positions[edge.0] + self.positions[edge.1]) * 0.5;
+
+            let edge_point = if is_crease(edge, polygon_ids) {
+                midpoint
+            } else {
+                (midpoint + (face_points[polygon_ids[0]] + face_points[polygon_ids[1]]) * 0.5) * 0.5
+            };
+
+            edge_indices.insert(*edge, positions.len());
+            positions.push(edge_point);
+        }
+
+        let mut face_indices = Vec::with_capacity(polygons.len());
+        let mut quads = Vec::with_capacity(polygons.len() * 4);
+        for (polygon, face_point) in polygons.iter().zip(face_points) {
+            let face_idx = positions.len();
+            positions.push(face_point);
+            face_indices.push(face_idx);
+
+            for (prev, vertex_id, next) in polygon.iter().circular_tuple_windows() {
+                quads.push(vec![
+                    edge_indices[&sorted(*prev, *vertex_id)],
+                    vertex_indices[vertex_id],
+                    edge_indices[&sorted(*vertex_id, *next)],
+                    face_idx,
+                ]);
+            }
+        }
+
+        let (mut subdivided, new_vertex_ids) =
+            Self::from_polygons_and_vertex_ids(&positions, &quads);
+
+        if subdivided.faces.len() != quads.len() * 2 {
+            error!("Some of the subdivided quads couldn't be added");
+        }
+
+        for (a, b) in sharp_edges {
+            let (Some(&a_idx), Some(&b_idx), Some(&edge_idx)) = (
+                vertex_indices.get(&a),
+                vertex_indices.get(&b),
+                edge_indices.get(&sorted(a, b)),
+            ) else {
+                continue;
+            };
+
+            for (start, end) in [(a_idx, edge_idx), (edge_idx, b_idx)] {
+                if let Some(he_id) =
+                    subdivided.halfedge_from_to(new_vertex_ids[start], new_vertex_ids[end])
+                {
+                    subdivided.set_edge_sharp(he_id, true);
+                }
+            }
+        }
+
+        let sources = vertex_indices
+            .iter()
+            .map(|(vertex_id, idx)| (new_vertex_ids[*idx], vec![*vertex_id]))
+            .chain(
+                edge_indices
+                    .iter()
+                    .map(|((a, b), idx)| (new_vertex_ids[*idx], vec![*a, *b])),
+            )
+            .chain(
+                polygons
+                    .iter()
+                    .zip(face_indices)
+                    .map(|(polygon, idx)| (new_vertex_ids[idx], polygon.clone())),
+            )
+            .collect_vec();
+        self.transfer_vertex_data(&mut subdivided, &sources);
+
+        if self.vertex_normals.is_some() {
+            subdivided.compute_vertex_normals();
+        }
+
+        let mut change_tracker = std::mem::take(&mut self.change_tracker);
+        for face_id in self.faces.keys().chain(subdivided.faces.keys()) {
+            change_tracker.mark_face(face_id);
+        }
+        subdivided.change_tracker = change_tracker;
+
+        *self = subdivided;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube_cage() -> MeshGraph {
+        let positions = (0..8)
+            .map(|i| {
+                Vec3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                )
+            })
+            .collect_vec();
+        let quads = [
+            vec![0, 2, 3, 1],
+            vec![4, 5, 7, 6],
+            vec![0, 1, 5, 4],
+            vec![2, 6, 7, 3],
+            vec![1, 3, 7, 5],
+            vec![0, 4, 6, 2],
+        ];
+
+        MeshGraph::from_polygons(&positions, &quads)
+    }
+
+    fn min_max_radius(mesh_graph: &MeshGraph) -> (f32, f32) {
+        mesh_graph
+            .positions
+            .values()
+            .map(|pos| pos.length())
+            .minmax()
+            .into_option()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_catmull_clark_interpolates() {
+        let mut mesh_graph = cube_cage();
+
+        mesh_graph.uvs = Some(
+            mesh_graph
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, pos.truncate()))
+                .collect(),
+        );
+
+        let weight = mesh_graph.add_vertex_attribute::<f32>("weight");
+        let positions = mesh_graph.positions.clone();
+        let weights = mesh_graph.vertex_attribute_mut(weight).unwrap();
+        for (vertex_id, pos) in positions {
+            weights.insert(vertex_id, pos.z);
+        }
+
+        mesh_graph.catmull_clark();
+
+        // 8 corners, 12 edge points and 6 face points
+        assert_eq!(mesh_graph.vertices.len(), 26);
+        let uvs = mesh_graph.uvs.as_ref().unwrap();
+        let weights = mesh_graph.vertex_attribute(weight).unwrap();
+
+        let mut weight_counts = HashMap::<i32, usize>::new();
+        for vertex_id in mesh_graph.vertices.keys() {
+            let value = weights[vertex_id];
+            assert!((value - value.round()).abs() < 1e-6);
+            *weight_counts.entry(value.round() as i32).or_default() += 1;
+        }
+        // the top and bottom face each have 4 corners, 4 edge points and 1 face point
+        assert_eq!(weight_counts, HashMap::from([(-1, 9), (0, 8), (1, 9)]));
+
+        // only the face points of the top and bottom face are in the center of the UVs
+        assert_eq!(
+            mesh_graph
+                .vertices
+                .keys()
+                .filter(|vertex_id| uvs[*vertex_id].length() < 1e-6)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_catmull_clark_cube() {
+        let mut mesh_graph = cube_cage();
+        let volume = mesh_graph.signed_volume();
+
+        assert_eq!(mesh_graph.faces.len(), 12);
+        let (_, mut max_radius) = min_max_radius(&mesh_graph);
+
+        for step in 1..=3 {
+            let face_count = mesh_graph.faces.len();
+            mesh_graph.catmull_clark();
+
+            assert_eq!(mesh_graph.faces.len(), face_count * 4);
+            assert_eq!(
+                mesh_graph.merge_back_to_polygons().len(),
+                6 * 4usize.pow(step)
+            );
+            assert!(mesh_graph.verify_twins().is_empty());
+            assert!(mesh_graph.boundary_loops().is_empty());
+
+            // the corners are rounded off
+            let (_, new_max_radius) = min_max_radius(&mesh_graph);
+            assert!(new_max_radius < max_radius);
+            max_radius = new_max_radius;
+
+            assert!(mesh_graph.signed_volume() < volume);
+            assert!(mesh_graph.signed_volume() > 0.0);
+        }
+
+        // close to a sphere
+        let (min_radius, max_radius) = min_max_radius(&mesh_graph);
+        assert!(max_radius / min_radius < 1.1);
+    }
+
+    #[test]
+    fn test_catmull_clark_creases() {
+        // with all edges of the cube sharp the shape doesn't change
+        let mut mesh_graph = cube_cage();
+
+        let cube_edges = mesh_graph
+            .halfedges
+            .iter()
+            .filter(|(_, he)| {
+                let start = mesh_graph.positions[he.start_vertex(&mesh_graph).unwrap()];
+                let end = mesh_graph.positions[he.end_vertex];
+                // the diagonals of the quads change two coordinates
+                (start - end).abs().cmpgt(Vec3::ZERO).bitmask().count_ones() == 1
+            })
+            .map(|(he_id, _)| he_id)
+            .collect_vec();
+        assert_eq!(cube_edges.len(), 24);
+        for he_id in cube_edges {
+            mesh_graph.set_edge_sharp(he_id, true);
+        }
+
+        mesh_graph.catmull_clark();
+
+        assert_eq!(mesh_graph.faces.len(), 48);
+        assert_eq!(mesh_graph.sharp_edges.len(), 48);
+        for pos in mesh_graph.positions.values() {
+            assert!((pos.abs().max_element() - 1.0).abs() < 1e-6);
+        }
+
+        // boundaries of an open patch are creases as well
+        let mut mesh_graph = MeshGraph::from_polygons(
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(2.0, 1.0, 0.0),
+            ],
+            &[vec![0, 1, 4, 3], vec![1, 2, 5, 4]],
+        );
+
+        mesh_graph.catmull_clark();
+
+        assert_eq!(mesh_graph.faces.len(), 16);
+        assert_eq!(mesh_graph.boundary_loops().len(), 1);
+        assert_eq!(mesh_graph.boundary_loops()[0].len(), 12);
+
+        for pos in mesh_graph.positions.values() {
+            assert_eq!(pos.z, 0.0);
+            assert!(pos.x >= 0.0 && pos.x <= 2.0 && pos.y >= 0.0 && pos.y <= 1.0);
+        }
+
+        // the boundary vertex in the middle of the bottom edge stays in place
+        assert!(mesh_graph.positions.values().any(|pos| *pos == Vec3::X));
+    }
+}
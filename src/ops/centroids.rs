@@ -0,0 +1,116 @@
+use glam::Vec3;
+use slotmap::SecondaryMap;
+
+use crate::{FaceId, MeshGraph};
+
+impl MeshGraph {
+    /// Computes the center of every face in one pass (see [`crate::Face::center`]).
+    ///
+    /// Useful for algorithms like segmentation or clustering that access the face centers
+    /// repeatedly.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn face_centroids(&self) -> SecondaryMap<FaceId, Vec3> {
+        let mut centroids = SecondaryMap::with_capacity(self.faces.len());
+
+        for (face_id, face) in &self.faces {
+            centroids.insert(face_id, face.center(self));
+        }
+
+        centroids
+    }
+
+    /// Average of all vertex positions. Returns zero for an empty mesh.
+    ///
+    /// This depends on how densely the surface is sampled. See [`MeshGraph::center_of_mass`]
+    /// for a measure that only depends on the shape.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn centroid(&self) -> Vec3 {
+        if self.positions.is_empty() {
+            return Vec3::ZERO;
+        }
+
+        self.positions.values().sum::<Vec3>() / self.positions.len() as f32
+    }
+
+    /// Center of mass of the surface, i.e. the face centers weighted by the face areas.
+    ///
+    /// Falls back to [`MeshGraph::centroid`] if the mesh has no area.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn center_of_mass(&self) -> Vec3 {
+        let (weighted_sum, total_area) =
+            self.faces
+                .values()
+                .fold((Vec3::ZERO, 0.0), |(weighted_sum, total_area), face| {
+                    let area = face.area(self);
+                    (weighted_sum + face.center(self) * area, total_area + area)
+                });
+
+        if total_area <= 0.0 {
+            return self.centroid();
+        }
+
+        weighted_sum / total_area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec2, Vec2};
+
+    use crate::primitives::{Grid, IcoSphere};
+
+    use super::*;
+
+    #[test]
+    fn test_face_centroids() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let centroids = mesh_graph.face_centroids();
+
+        assert_eq!(centroids.len(), mesh_graph.faces.len());
+
+        for (face_id, face) in &mesh_graph.faces {
+            assert_eq!(centroids[face_id], face.center(&mesh_graph));
+        }
+
+        assert!(MeshGraph::new().face_centroids().is_empty());
+    }
+
+    #[test]
+    fn test_centroid_and_center_of_mass() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        assert!(mesh_graph.centroid().length() < 1e-5);
+        assert!(mesh_graph.center_of_mass().length() < 1e-5);
+
+        let offset = Vec3::new(3.0, -1.0, 2.0);
+        let mut grid = MeshGraph::from(Grid {
+            size: Vec2::ONE,
+            subdivisions: UVec2::new(8, 1),
+        });
+        for pos in grid.positions.values_mut() {
+            *pos += offset;
+        }
+
+        assert!(grid.centroid().distance(offset) < 1e-5);
+        assert!(grid.center_of_mass().distance(offset) < 1e-5);
+
+        // move the vertices towards the left side without changing the covered rectangle
+        for pos in grid.positions.values_mut() {
+            let x = pos.x - offset.x + 0.5;
+            pos.x = x * x - 0.5 + offset.x;
+        }
+
+        assert!(grid.centroid().x < offset.x - 0.05);
+        assert!(grid.center_of_mass().distance(offset) < 1e-5);
+
+        assert_eq!(MeshGraph::new().centroid(), Vec3::ZERO);
+        assert_eq!(MeshGraph::new().center_of_mass(), Vec3::ZERO);
+    }
+}
@@ -0,0 +1,128 @@
+use hashbrown::HashSet;
+use slotmap::SecondaryMap;
+
+use crate::{FaceId, MeshGraph};
+
+/// Records the faces that have been changed by mesh operations.
+/// See [`MeshGraph::track_changes`].
+#[derive(Debug, Clone, Default)]
+pub struct ChangeTracker {
+    /// `None` if tracking is disabled
+    dirty_faces: Option<HashSet<FaceId>>,
+}
+
+impl ChangeTracker {
+    /// Marks a face as changed. Does nothing if tracking is disabled.
+    #[inline]
+    pub fn mark_face(&mut self, face_id: FaceId) {
+        if let Some(dirty_faces) = &mut self.dirty_faces {
+            dirty_faces.insert(face_id);
+        }
+    }
+
+    /// Returns `true` if changes are tracked.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.dirty_faces.is_some()
+    }
+
+    /// Moves the recorded faces to the new face ids given by `face_map`.
+    /// Faces that aren't in `face_map` are dropped.
+    pub(crate) fn remap(&mut self, face_map: &SecondaryMap<FaceId, FaceId>) {
+        if let Some(dirty_faces) = &mut self.dirty_faces {
+            *dirty_faces = dirty_faces
+                .iter()
+                .filter_map(|face_id| face_map.get(*face_id).copied())
+                .collect();
+        }
+    }
+}
+
+impl MeshGraph {
+    /// Enables or disables the tracking of changed faces.
+    ///
+    /// While enabled, every face that is inserted, removed or whose vertices are moved by one
+    /// of the operations of this crate is recorded. Use [`MeshGraph::take_dirty_faces`] to
+    /// retrieve them, e.g. to re-upload only the changed geometry to the GPU.
+    ///
+    /// Changes made by manipulating the fields (like `positions`) directly are not tracked.
+    /// Disabling the tracking discards the recorded faces.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn track_changes(&mut self, enabled: bool) {
+        if enabled {
+            self.change_tracker.dirty_faces.get_or_insert_default();
+        } else {
+            self.change_tracker.dirty_faces = None;
+        }
+    }
+
+    /// Returns the faces that have changed since tracking was enabled or this was called the
+    /// last time, and resets the record.
+    ///
+    /// Removed faces are included as well, so check if a face still exists in `faces` to decide
+    /// whether it has to be updated or dropped.
+    /// If tracking is disabled, this is always empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn take_dirty_faces(&mut self) -> HashSet<FaceId> {
+        self.change_tracker
+            .dirty_faces
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::utils::grid;
+
+    use super::*;
+
+    #[test]
+    fn test_track_changes_subdivide_edge() {
+        let size = 4;
+
+        let (mut mesh_graph, vertex_ids) = grid(size);
+
+        // disabled by default
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[0], vertex_ids[size + 1])
+            .unwrap();
+        mesh_graph.subdivide_edge(he_id);
+        assert!(mesh_graph.take_dirty_faces().is_empty());
+
+        mesh_graph.track_changes(true);
+        assert!(mesh_graph.take_dirty_faces().is_empty());
+
+        // an interior diagonal
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[size + 1], vertex_ids[2 * size + 2])
+            .unwrap();
+        mesh_graph.subdivide_edge(he_id).unwrap();
+
+        let (center_v, _) = mesh_graph
+            .positions
+            .iter()
+            .find(|(_, pos)| **pos == Vec3::new(1.5, 1.5, 0.0))
+            .unwrap();
+
+        let affected_faces = mesh_graph.vertices[center_v]
+            .faces(&mesh_graph)
+            .collect::<HashSet<_>>();
+        assert_eq!(affected_faces.len(), 4);
+
+        assert_eq!(mesh_graph.take_dirty_faces(), affected_faces);
+        assert!(mesh_graph.take_dirty_faces().is_empty());
+
+        // removed faces are reported as well
+        let face_id = *affected_faces.iter().next().unwrap();
+        mesh_graph.remove_face(face_id);
+        assert_eq!(mesh_graph.take_dirty_faces(), HashSet::from([face_id]));
+
+        mesh_graph.track_changes(false);
+        mesh_graph.remove_face(*affected_faces.iter().nth(1).unwrap());
+        assert!(mesh_graph.take_dirty_faces().is_empty());
+    }
+}
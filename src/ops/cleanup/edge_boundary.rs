@@ -1,4 +1,4 @@
-use tracing::{error, instrument};
+use crate::utils::error;
 
 use crate::{MeshGraph, VertexId, error_none};
 
@@ -7,7 +7,7 @@ impl MeshGraph {
     /// then it will be the one in [`Vertex::outgoing_halfedge`].
     ///
     /// The method [`Vertex::is_boundary`] relies on this.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn make_outgoing_halfedge_boundary_if_possible(&mut self, vertex_id: VertexId) {
         if !self.vertices.contains_key(vertex_id) {
             error!("Vertex not found");
@@ -33,7 +33,7 @@ impl MeshGraph {
     /// then it will be the one in [`Vertex::outgoing_halfedge`].
     ///
     /// The method [`Vertex::is_boundary`] relies on this.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn make_all_outgoing_halfedges_boundary_if_possible(&mut self) {
         let boundary_pairs = self
             .halfedges
@@ -1,14 +1,12 @@
 mod edge_boundary;
 mod vertex_neighborhood;
 
-use tracing::instrument;
-
 use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, error_none};
 
 impl MeshGraph {
     /// Test if two faces have at least one halfedge in common.
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn faces_share_edge(&self, face_id1: FaceId, face_id2: FaceId) -> bool {
         self.faces_share_edge_inner(face_id1, face_id2).is_some()
     }
@@ -64,7 +62,7 @@ impl MeshGraph {
 
     /// Test if two faces share all vertices.
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn faces_share_all_vertices(&self, face_id1: FaceId, face_id2: FaceId) -> bool {
         self.faces_share_all_vertices_inner(face_id1, face_id2)
             .is_some()
@@ -105,7 +103,7 @@ impl MeshGraph {
 
     /// Test if two halfedges share all vertices.
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn halfedges_share_all_vertices(
         &self,
         halfedge_id1: HalfedgeId,
@@ -138,7 +136,7 @@ impl MeshGraph {
 
     /// Test if two vertices have the exact same position.
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn vertices_share_position(&self, vertex_id1: VertexId, vertex_id2: VertexId) -> bool {
         self.vertices_share_position_inner(vertex_id1, vertex_id2)
             .is_some()
@@ -1,6 +1,6 @@
+use crate::utils::error;
 use hashbrown::HashSet;
 use itertools::Itertools;
-use tracing::{error, instrument};
 
 use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, error_none};
 
@@ -31,7 +31,7 @@ impl MeshGraph {
     ///
     /// See [Freestyle: Sculpting meshes with self-adaptive topology DOI 10.1016/j.cag.2011.03.033](https://inria.hal.science/inria-00606516v1/document)
     /// Chapters 3.2 and 5.1
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn make_vertex_neighborhood_manifold(
         &mut self,
         vertex_id: VertexId,
@@ -177,7 +177,17 @@ impl MeshGraph {
                 .copied(),
         );
 
-        while let Some(&start_he_id) = outgoing_halfedges.iter().next() {
+        // Rotating starts at a boundary halfedge if possible. Otherwise the fan of a boundary
+        // vertex would be mistaken for two disconnected neighborhoods.
+        while let Some(&start_he_id) = outgoing_halfedges
+            .iter()
+            .find(|he_id| {
+                self.halfedges
+                    .get(**he_id)
+                    .is_some_and(|he| he.is_boundary())
+            })
+            .or_else(|| outgoing_halfedges.iter().next())
+        {
             let mut current_he_id = start_he_id;
 
             let len = outgoing_halfedges.len();
@@ -243,7 +253,7 @@ impl MeshGraph {
                 + glam::Vec3::new(0.1, 0.0, 0.0),
         );
 
-        tracing::info!("Duplicated {vertex_id:?}: {new_vert_id:?}");
+        crate::utils::info!("Duplicated {vertex_id:?}: {new_vert_id:?}");
 
         for &he_id in &outgoing_halfedges {
             let he = self
@@ -337,7 +347,7 @@ impl MeshGraph {
         None
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn split_regions_at_edge(
         &mut self,
         vertex_id: VertexId,
@@ -403,7 +413,7 @@ impl MeshGraph {
         Some(new_vertex_id)
     }
 
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn remove_degenerate_faces(
         &mut self,
         vertex_id: VertexId,
@@ -459,8 +469,7 @@ impl MeshGraph {
 
                 side_two.pop();
 
-                let new_vertex_id =
-                    self.split_regions_at_vertex(vertex_id, side_one, side_two, removed_halfedges);
+                let new_vertex_id = self.split_regions_at_vertex(vertex_id, side_one, side_two);
 
                 let (del_v_ids, del_he_ids) = self.remove_face(face_id1);
                 removed_vertices.extend(del_v_ids);
@@ -479,13 +488,12 @@ impl MeshGraph {
         None
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn split_regions_at_vertex(
         &mut self,
         vertex_id: VertexId,
         side_one: Vec<FaceId>,
         side_two: Vec<FaceId>,
-        removed_halfedges: &mut Vec<HalfedgeId>,
     ) -> Option<VertexId> {
         if side_one.len() < 2 || side_two.len() < 2 {
             error!("Not enough halfedges to split");
@@ -577,7 +585,7 @@ impl MeshGraph {
         self.weld_faces_at(start_vertex_id, other_common_vertex_id, face_id1, face_id2)
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn weld_faces_at(
         &mut self,
         start_vertex_id: VertexId,
@@ -778,7 +786,7 @@ impl MeshGraph {
                             .outgoing_halfedge = Some(twin_id1);
                     }
                 } else {
-                    tracing::warn!("There's only one halfedge left");
+                    crate::utils::warn!("There's only one halfedge left");
                 }
 
                 return Some(true);
@@ -1029,7 +1037,13 @@ mod tests {
         #[cfg(feature = "rerun")]
         meshgraph.log_rerun();
 
-        meshgraph.remove_degenerate_edges(center_v_id);
+        let new_v_id = meshgraph.remove_degenerate_edges(center_v_id).unwrap();
+
+        // the fan is split at the degenerate edge into two fans of three faces each
+        assert_eq!(meshgraph.vertices.len(), 7);
+        assert_eq!(meshgraph.vertices[center_v_id].faces(&meshgraph).count(), 3);
+        assert_eq!(meshgraph.vertices[new_v_id].faces(&meshgraph).count(), 3);
+        assert!(meshgraph.verify_twins().is_empty());
 
         #[cfg(feature = "rerun")]
         {
@@ -1154,4 +1168,27 @@ mod tests {
         assert_eq!(removed_halfedges.len(), 6);
         assert_eq!(removed_faces.len(), 2);
     }
+
+    #[test]
+    fn test_split_disconnected_neighborhoods_keeps_boundary_fans() {
+        let (mut meshgraph, _) = crate::utils::grid(4);
+        let vertex_count = meshgraph.vertices.len();
+
+        let boundary_vertex_ids = meshgraph
+            .vertices
+            .iter()
+            .filter(|(_, vertex)| vertex.is_boundary(&meshgraph))
+            .map(|(vertex_id, _)| vertex_id)
+            .collect::<Vec<_>>();
+        assert_eq!(boundary_vertex_ids.len(), 12);
+
+        // the fan of every boundary vertex is a single connected neighborhood
+        for vertex_id in boundary_vertex_ids {
+            let new_vertices = meshgraph.split_disconnected_neighborhoods(vertex_id);
+            assert!(new_vertices.unwrap_or_default().is_empty());
+        }
+
+        assert_eq!(meshgraph.vertices.len(), vertex_count);
+        assert_eq!(meshgraph.boundary_loops().len(), 1);
+    }
 }
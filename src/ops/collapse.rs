@@ -1,20 +1,39 @@
+use crate::utils::error;
 use glam::Vec3;
 use hashbrown::HashSet;
 use itertools::Itertools;
-use tracing::{error, instrument};
 
-use crate::{Face, FaceId, HalfedgeId, MeshGraph, VertexId, error_none, utils::unwrap_or_return};
+use crate::{
+    Face, FaceId, HalfedgeId, MeshGraph, VertexId, error_none,
+    geometry::closest_parameter_on_segment, utils::unwrap_or_return,
+};
 
 impl MeshGraph {
     /// Collapses edges until all edges have a length above the minimum length.
     ///
     /// This will schedule necessary updates to the BVH but you have to call
     /// `refit_bvh()` after the operation.
-    #[instrument(skip(self))]
+    #[inline]
     pub fn collapse_until_edges_above_min_length(
         &mut self,
         min_length_squared: f32,
         marked_vertices: &mut HashSet<VertexId>,
+    ) {
+        self.collapse_until_edges_above_min_length_opts(min_length_squared, marked_vertices, false);
+    }
+
+    /// Same as [`MeshGraph::collapse_until_edges_above_min_length`] but if `preserve_boundary`
+    /// is `true`, edges that touch a boundary vertex are never collapsed. This keeps the outline
+    /// of open patches in place.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn collapse_until_edges_above_min_length_opts(
+        &mut self,
+        min_length_squared: f32,
+        marked_vertices: &mut HashSet<VertexId>,
+        preserve_boundary: bool,
     ) {
         let mut halfedges_to_collapse = self.halfedges_map(|len_sqr| len_sqr < min_length_squared);
 
@@ -27,6 +46,10 @@ impl MeshGraph {
             let mut min_end_v_id = VertexId::default();
 
             for (&he_id, &len) in &halfedges_to_collapse {
+                if preserve_boundary && self.is_edge_touching_boundary(he_id) {
+                    continue;
+                }
+
                 if len < min_len
                     && let Some((twin_id, start_v_id, end_v_id, center)) =
                         self.can_collapse_edge_inner(he_id)
@@ -101,6 +124,7 @@ impl MeshGraph {
                         if let Some(face) = self.faces.get(face_id) {
                             self.bvh
                                 .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                            self.change_tracker.mark_face(face.id);
                         } else {
                             error!("Face not found. BVH will not be updated.");
                         }
@@ -125,15 +149,47 @@ impl MeshGraph {
         self.log_rerun();
     }
 
+    /// Checks if the start or end vertex of the edge is a boundary vertex.
+    ///
+    /// `Vertex::is_boundary` can't be used here because `can_collapse_edge_inner` moves the
+    /// outgoing halfedges of the vertices away from the boundary.
+    fn is_edge_touching_boundary(&self, halfedge_id: HalfedgeId) -> bool {
+        let Some(he) = self.halfedges.get(halfedge_id) else {
+            error!("Halfedge not found");
+            return true;
+        };
+
+        [he.start_vertex(self), Some(he.end_vertex)]
+            .into_iter()
+            .any(|vertex_id| {
+                vertex_id
+                    .and_then(|vertex_id| self.vertices.get(vertex_id))
+                    .is_none_or(|vertex| vertex.outgoing_boundary_halfedge(self).is_some())
+            })
+    }
+
     #[inline]
     pub fn can_collapse_edge(&mut self, halfedge_id: HalfedgeId) -> bool {
         self.can_collapse_edge_inner(halfedge_id).is_some()
     }
 
-    #[instrument(skip(self))]
+    #[inline]
     pub fn can_collapse_edge_inner(
         &mut self,
         halfedge_id: HalfedgeId,
+    ) -> Option<(HalfedgeId, VertexId, VertexId, Vec3)> {
+        self.can_collapse_edge_to(halfedge_id, |start_pos, end_pos| {
+            (start_pos + end_pos) * 0.5
+        })
+    }
+
+    /// Checks if the edge can be collapsed into the position computed by `target_pos` from the
+    /// start and end position without flipping any faces.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, target_pos)))]
+    pub(crate) fn can_collapse_edge_to(
+        &mut self,
+        halfedge_id: HalfedgeId,
+        target_pos: impl FnOnce(Vec3, Vec3) -> Vec3,
     ) -> Option<(HalfedgeId, VertexId, VertexId, Vec3)> {
         // TODO : consider boundary edge
         //
@@ -191,7 +247,7 @@ impl MeshGraph {
             .get(end_vertex_id)
             .or_else(error_none!("End position not found"))?;
 
-        let center = (start_pos + end_pos) * 0.5;
+        let center = target_pos(*start_pos, *end_pos);
 
         self.check_inverted_faces(start_vertex_id, center)?;
         self.check_inverted_faces(end_vertex_id, center)?;
@@ -237,7 +293,7 @@ impl MeshGraph {
         Some(())
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn collapse_edge_inner(
         &mut self,
         halfedge_id: HalfedgeId,
@@ -313,16 +369,35 @@ impl MeshGraph {
             }
         }
 
+        // interpolate at the projection of the new position onto the edge
+        let t = closest_parameter_on_segment(
+            center_pos,
+            self.positions[start_v_id],
+            self.positions[end_v_id],
+        );
+        self.vertex_attributes
+            .interpolate(start_v_id, start_v_id, end_v_id, t);
+
         self.remove_only_vertex(end_v_id);
         result.removed_vertices.push(end_v_id);
 
         self.halfedges.remove(halfedge_id);
         self.halfedges.remove(twin_id);
+        self.sharp_edges.remove(&halfedge_id);
+        self.sharp_edges.remove(&twin_id);
 
         self.positions[start_v_id] = center_pos;
 
+        // if the end vertex was the tip of an ear, none of its halfedges are left
         let new_outgoing_he_id = end_outgoing_halfedges
             .into_iter()
+            .chain(
+                self.outgoing_halfedges
+                    .get(start_v_id)
+                    .into_iter()
+                    .flatten()
+                    .copied(),
+            )
             .find(|he_id| self.halfedges.contains_key(*he_id));
 
         if let Some(new_outgoing_he_id) = new_outgoing_he_id {
@@ -359,7 +434,7 @@ impl MeshGraph {
     /// It also performs a cleanup afterwards to remove flaps (faces that share the same vertices).
     ///
     /// Returns the vertices, halfedges and faces that were removed.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn collapse_edge(&mut self, halfedge_id: HalfedgeId) -> CollapseEdge {
         let he = *unwrap_or_return!(
             self.halfedges.get(halfedge_id),
@@ -392,9 +467,145 @@ impl MeshGraph {
         self.collapse_edge_inner(halfedge_id, twin_id, start_v_id, end_v_id, center_pos)
     }
 
+    /// Collapses the edge like [`MeshGraph::collapse_edge`] but only if this is safe.
+    ///
+    /// The edge is refused (and `None` returned) if it violates the link condition, i.e. if
+    /// the one-rings of the two end points share any vertex besides the vertices opposite
+    /// to the edge, if it is an interior edge between two boundary vertices, or if the collapse
+    /// would flip any face.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn collapse_edge_if_valid(&mut self, halfedge_id: HalfedgeId) -> Option<CollapseEdge> {
+        let (twin_id, start_v_id, end_v_id, center_pos) =
+            self.can_collapse_edge_safely(halfedge_id)?;
+
+        Some(self.collapse_edge_inner(halfedge_id, twin_id, start_v_id, end_v_id, center_pos))
+    }
+
+    /// Checks the conditions of [`MeshGraph::collapse_edge_if_valid`] without collapsing the
+    /// edge. The result can be passed on to [`MeshGraph::collapse_edge_inner`].
+    pub(crate) fn can_collapse_edge_safely(
+        &mut self,
+        halfedge_id: HalfedgeId,
+    ) -> Option<(HalfedgeId, VertexId, VertexId, Vec3)> {
+        if !self.satisfies_link_condition(halfedge_id)? {
+            return None;
+        }
+
+        self.can_collapse_edge_inner(halfedge_id)
+    }
+
+    /// Collapses every edge that is shorter than `min_length` in a single pass over the mesh.
+    ///
+    /// Each edge is collapsed with [`MeshGraph::collapse_edge_if_valid`], so edges that would
+    /// violate the link condition or flip faces are skipped. Edges that become short through
+    /// a previous collapse in the same pass are not revisited.
+    /// Use [`MeshGraph::collapse_until_edges_above_min_length`] for that.
+    ///
+    /// Returns the number of collapsed edges. The BVH is rebuilt at the end if anything changed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn collapse_short_edges(&mut self, min_length: f32) -> usize {
+        let min_length_squared = min_length * min_length;
+
+        let edges = self.edges().collect_vec();
+
+        let mut collapsed_count = 0;
+
+        for he_id in edges {
+            let Some(he) = self.halfedges.get(he_id) else {
+                // removed by a previous collapse
+                continue;
+            };
+
+            if he.length_squared(self) >= min_length_squared {
+                continue;
+            }
+
+            let Some(start_v_id) = he.start_vertex(self) else {
+                error!("Start vertex not found");
+                continue;
+            };
+
+            if self.collapse_edge_if_valid(he_id).is_none() {
+                continue;
+            }
+
+            collapsed_count += 1;
+
+            if let Some(vertex) = self.vertices.get(start_v_id) {
+                for face_id in vertex.faces(self).collect_vec() {
+                    self.change_tracker.mark_face(face_id);
+                }
+            }
+        }
+
+        if collapsed_count > 0 {
+            self.rebuild_bvh();
+        }
+
+        collapsed_count
+    }
+
+    /// Checks that the only vertices shared by the one-rings of the start and end vertex of the
+    /// edge are the vertices opposite to the edge in its adjacent faces, and that an interior
+    /// edge doesn't connect two boundary vertices which would pinch the boundary.
+    fn satisfies_link_condition(&self, halfedge_id: HalfedgeId) -> Option<bool> {
+        let he = self
+            .halfedges
+            .get(halfedge_id)
+            .or_else(error_none!("Halfedge not found"))?;
+        let twin = self
+            .halfedges
+            .get(he.twin.or_else(error_none!("Twin missing"))?)
+            .or_else(error_none!("Twin halfedge not found"))?;
+
+        let is_boundary_vertex = |vertex_id| {
+            self.outgoing_halfedges
+                .get(vertex_id)
+                .is_some_and(|he_ids: &Vec<HalfedgeId>| {
+                    he_ids.iter().any(|he_id| {
+                        self.halfedges
+                            .get(*he_id)
+                            .is_some_and(|he| he.is_boundary())
+                    })
+                })
+        };
+
+        if !he.is_boundary()
+            && !twin.is_boundary()
+            && is_boundary_vertex(he.end_vertex)
+            && is_boundary_vertex(twin.end_vertex)
+        {
+            return Some(false);
+        }
+
+        let start_neighbours = self
+            .vertices
+            .get(twin.end_vertex)
+            .or_else(error_none!("Start vertex not found"))?
+            .neighbours(self)
+            .collect::<HashSet<_>>();
+        let end_neighbours = self
+            .vertices
+            .get(he.end_vertex)
+            .or_else(error_none!("End vertex not found"))?
+            .neighbours(self)
+            .collect::<HashSet<_>>();
+
+        let opposite_vertices = [he.opposite_vertex(self), twin.opposite_vertex(self)]
+            .into_iter()
+            .flatten()
+            .collect::<HashSet<_>>();
+
+        Some(
+            start_neighbours
+                .intersection(&end_neighbours)
+                .all(|vertex_id| opposite_vertices.contains(vertex_id)),
+        )
+    }
+
     /// Remove a halfedge face and re-connecting the adjacent halfedges.
     /// Only works on manifold triangle meshes.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn remove_halfedge_face(
         &mut self,
         halfedge_id: HalfedgeId,
@@ -463,6 +674,12 @@ impl MeshGraph {
                 .index,
         );
 
+        // the two remaining halfedges form one edge now which is sharp if either of the
+        // removed edges was
+        if self.sharp_edges.remove(&next_he_id) | self.sharp_edges.remove(&prev_he_id) {
+            self.sharp_edges.extend([next_twin_id, prev_twin_id]);
+        }
+
         self.halfedges.remove(next_he_id);
         self.halfedges.remove(prev_he_id);
         self.remove_outgoing_halfedge(next_end_v_id, prev_he_id);
@@ -470,6 +687,8 @@ impl MeshGraph {
         if let Some(face) = self.faces.remove(face_id) {
             self.bvh.remove(face.index);
         }
+        self.face_user_data_maps.remove(face_id);
+        self.change_tracker.mark_face(face_id);
 
         self.halfedges
             .get_mut(next_twin_id)
@@ -496,6 +715,8 @@ pub struct CollapseEdge {
 
 #[cfg(test)]
 mod test {
+    use crate::utils::{grid, grid_positions_and_indices};
+
     use super::*;
 
     #[test]
@@ -627,6 +848,197 @@ mod test {
         assert_eq!(mesh_graph.outgoing_halfedges[start_v_id].len(), 6);
     }
 
+    #[test]
+    fn test_collapse_edge_if_valid() {
+        // tetrahedron where the face (0, 1, 2) is split at its center 4
+        let mut mesh_graph = MeshGraph::indexed_triangles(
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(1.0, 2.0, 0.0),
+                Vec3::new(1.0, 0.7, 2.0),
+                Vec3::new(1.0, 0.7, 0.0),
+            ],
+            &[0, 4, 1, 1, 4, 2, 2, 4, 0, 0, 1, 3, 1, 2, 3, 2, 0, 3],
+        );
+
+        let vertex_ids = mesh_graph.vertices.keys().collect_vec();
+        let halfedge_from_to = |mesh_graph: &MeshGraph, from: usize, to: usize| {
+            mesh_graph
+                .halfedge_from_to(vertex_ids[from], vertex_ids[to])
+                .unwrap()
+        };
+
+        // vertex 2 is a neighbour of both 0 and 1 but not opposite to the edge
+        let he_id = halfedge_from_to(&mesh_graph, 0, 1);
+        assert!(mesh_graph.collapse_edge_if_valid(he_id).is_none());
+        assert_eq!(mesh_graph.vertices.len(), 5);
+        assert_eq!(mesh_graph.faces.len(), 6);
+
+        let he_id = halfedge_from_to(&mesh_graph, 4, 0);
+        let result = mesh_graph.collapse_edge_if_valid(he_id).unwrap();
+        assert_eq!(result.removed_vertices.len(), 1);
+        assert_eq!(mesh_graph.faces.len(), 4);
+    }
+
+    #[test]
+    fn test_collapse_edge_if_valid_keeps_boundary() {
+        // strip of two quads where the middle rung connects two boundary vertices
+        let (positions, indices) = grid_positions_and_indices(3, 2);
+        let (mut mesh_graph, vertex_ids) =
+            MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[1], vertex_ids[4])
+            .unwrap();
+        assert!(mesh_graph.collapse_edge_if_valid(he_id).is_none());
+        assert_eq!(mesh_graph.vertices.len(), 6);
+        assert_eq!(mesh_graph.faces.len(), 4);
+
+        // collapsing along the boundary is still fine
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[1], vertex_ids[0])
+            .unwrap();
+        assert!(mesh_graph.collapse_edge_if_valid(he_id).is_some());
+        assert_eq!(mesh_graph.faces.len(), 3);
+    }
+
+    #[test]
+    fn test_collapse_preserve_boundary() {
+        let size = 7;
+        let (mut mesh_graph, _) = grid(size);
+
+        let boundary_length = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .halfedges
+                .values()
+                .filter(|he| he.is_boundary())
+                .map(|he| he.length(mesh_graph))
+                .sum::<f32>()
+        };
+        let face_count = mesh_graph.faces.len();
+
+        mesh_graph.collapse_until_edges_above_min_length_opts(1.5 * 1.5, &mut HashSet::new(), true);
+
+        assert!(mesh_graph.faces.len() < face_count);
+        assert!((boundary_length(&mesh_graph) - 4.0 * (size - 1) as f32).abs() < 1e-4);
+        assert_eq!(
+            mesh_graph
+                .halfedges
+                .values()
+                .filter(|he| he.is_boundary())
+                .count(),
+            4 * (size - 1)
+        );
+    }
+
+    /// Hot loop that is used to compare the runtime with and without the `tracing` feature.
+    /// The outcome must not depend on the feature.
+    #[test]
+    fn test_collapse_many_edges() {
+        let mut mesh_graph = MeshGraph::from(crate::primitives::IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        });
+
+        let he_ids = mesh_graph.halfedges.keys().collect_vec();
+        let collapsed = he_ids
+            .into_iter()
+            .filter(|&he_id| {
+                mesh_graph.halfedges.contains_key(he_id)
+                    && mesh_graph.collapse_edge_if_valid(he_id).is_some()
+            })
+            .count();
+
+        assert!(collapsed > 100);
+        // still a closed manifold of genus 0
+        assert_eq!(mesh_graph.faces.len(), 2 * mesh_graph.vertices.len() - 4);
+        assert!(
+            mesh_graph
+                .halfedges
+                .values()
+                .all(|he| he.twin.is_some() && !he.is_boundary())
+        );
+    }
+
+    #[test]
+    fn test_collapse_short_edges() {
+        let mut mesh_graph = MeshGraph::from(crate::primitives::IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        // move a few vertices that are far apart very close to one of their neighbours
+        let mut moved = HashSet::new();
+        let vertex_ids = mesh_graph.vertices.keys().collect_vec();
+        for vertex_id in vertex_ids {
+            let neighbours = mesh_graph.vertices[vertex_id]
+                .neighbours(&mesh_graph)
+                .collect_vec();
+
+            if moved.len() == 5 || neighbours.iter().any(|id| moved.contains(id)) {
+                continue;
+            }
+
+            let target = mesh_graph.positions[neighbours[0]];
+            let pos = &mut mesh_graph.positions[vertex_id];
+            *pos = pos.lerp(target, 0.95);
+
+            moved.insert(vertex_id);
+        }
+
+        let min_length = 0.1;
+        let is_short = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .halfedges
+                .values()
+                .filter(|he| he.length(mesh_graph) < min_length)
+                .count()
+        };
+        assert_eq!(is_short(&mesh_graph), 10);
+
+        let face_count = mesh_graph.faces.len();
+
+        assert_eq!(mesh_graph.collapse_short_edges(min_length), 5);
+
+        assert_eq!(is_short(&mesh_graph), 0);
+        assert_eq!(mesh_graph.faces.len(), face_count - 10);
+        assert!(mesh_graph.verify_twins().is_empty());
+        // still a closed manifold of genus 0
+        assert_eq!(mesh_graph.faces.len(), 2 * mesh_graph.vertices.len() - 4);
+        assert!(mesh_graph.halfedges.values().all(|he| !he.is_boundary()));
+
+        // nothing left to do
+        assert_eq!(mesh_graph.collapse_short_edges(min_length), 0);
+    }
+
+    #[test]
+    fn test_collapse_edge_into_ear_tip() {
+        let (mut mesh_graph, vertex_ids) = grid(3);
+
+        // the corner vertex 6 only belongs to a single triangle
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[7], vertex_ids[6])
+            .unwrap();
+        let result = mesh_graph.collapse_edge(he_id);
+
+        assert_eq!(result.removed_vertices, vec![vertex_ids[6]]);
+
+        // the surviving vertex gets one of its own halfedges as none of the tip's are left
+        let outgoing_he_id = mesh_graph.vertices[vertex_ids[7]]
+            .outgoing_halfedge
+            .unwrap();
+        assert_eq!(
+            mesh_graph.halfedges[outgoing_he_id].start_vertex(&mesh_graph),
+            Some(vertex_ids[7])
+        );
+
+        assert_eq!(mesh_graph.vertices.len(), 8);
+        assert_eq!(mesh_graph.faces.len(), 7);
+        assert!(mesh_graph.verify_twins().is_empty());
+        assert_eq!(mesh_graph.boundary_loops().len(), 1);
+    }
+
     #[cfg(feature = "gltf")]
     #[test]
     fn test_can_collapse_edge() {
@@ -0,0 +1,269 @@
+use slotmap::{Key, SecondaryMap, SlotMap};
+
+use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, utils::error};
+
+/// Return value of [`MeshGraph::len`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MeshLengths {
+    /// Number of live vertices
+    pub vertices: usize,
+    /// Number of live halfedges
+    pub halfedges: usize,
+    /// Number of live faces
+    pub faces: usize,
+}
+
+impl MeshGraph {
+    /// Returns the number of live vertices, halfedges and faces. Slots that have been vacated
+    /// by removing elements are not counted.
+    #[inline]
+    pub fn len(&self) -> MeshLengths {
+        MeshLengths {
+            vertices: self.vertices.len(),
+            halfedges: self.halfedges.len(),
+            faces: self.faces.len(),
+        }
+    }
+
+    /// Returns `true` if the mesh has no vertices, halfedges or faces.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.halfedges.is_empty() && self.faces.is_empty()
+    }
+
+    /// Compacts the storage of the mesh to reclaim the memory of removed elements.
+    ///
+    /// Slotmaps never release the slots of removed elements, so after heavy deletion (e.g.
+    /// decimation) most of the memory can be vacant. This copies all live elements into new
+    /// tightly packed maps and remaps all references between them as well as `positions`,
+    /// `vertex_normals`, `uvs`, `face_normals`, `sharp_edges`, `polygon_ids`, the face user
+    /// data and the vertex attributes.
+    /// If changes are tracked (see [`MeshGraph::track_changes`]), the recorded faces that still
+    /// exist are remapped as well while removed ones are dropped since their IDs are invalid.
+    /// Afterwards the mesh is finalized (see [`MeshGraph::finalize`]) which reassigns the BVH
+    /// indices and rebuilds the BVH and the outgoing halfedges cache.
+    ///
+    /// All previously obtained vertex, halfedge and face IDs are invalid afterwards.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn shrink_to_fit(&mut self) {
+        let mut vertices = SlotMap::with_capacity_and_key(self.vertices.len());
+        let mut halfedges = SlotMap::with_capacity_and_key(self.halfedges.len());
+        let mut faces = SlotMap::with_capacity_and_key(self.faces.len());
+
+        let vertex_map = self
+            .vertices
+            .iter()
+            .map(|(vertex_id, vertex)| (vertex_id, vertices.insert(*vertex)))
+            .collect::<SecondaryMap<VertexId, VertexId>>();
+        let halfedge_map = self
+            .halfedges
+            .iter()
+            .map(|(he_id, he)| (he_id, halfedges.insert(*he)))
+            .collect::<SecondaryMap<HalfedgeId, HalfedgeId>>();
+        let face_map = self
+            .faces
+            .iter()
+            .map(|(face_id, face)| (face_id, faces.insert(*face)))
+            .collect::<SecondaryMap<FaceId, FaceId>>();
+
+        for vertex in vertices.values_mut() {
+            vertex.outgoing_halfedge = vertex
+                .outgoing_halfedge
+                .and_then(|he_id| remap(&halfedge_map, he_id));
+        }
+
+        for he in halfedges.values_mut() {
+            he.end_vertex = remap(&vertex_map, he.end_vertex).unwrap_or_default();
+            he.face = he.face.and_then(|face_id| remap(&face_map, face_id));
+            he.twin = he.twin.and_then(|he_id| remap(&halfedge_map, he_id));
+            he.next = he.next.and_then(|he_id| remap(&halfedge_map, he_id));
+        }
+
+        for (face_id, face) in &mut faces {
+            face.halfedge = remap(&halfedge_map, face.halfedge).unwrap_or_default();
+            face.id = face_id;
+        }
+
+        self.positions = self
+            .positions
+            .iter()
+            .filter_map(|(vertex_id, pos)| Some((vertex_map.get(vertex_id).copied()?, *pos)))
+            .collect();
+
+        if let Some(normals) = &mut self.vertex_normals {
+            *normals = normals
+                .iter()
+                .filter_map(|(vertex_id, normal)| {
+                    Some((vertex_map.get(vertex_id).copied()?, *normal))
+                })
+                .collect();
+        }
+
+        if let Some(uvs) = &mut self.uvs {
+            *uvs = uvs
+                .iter()
+                .filter_map(|(vertex_id, uv)| Some((vertex_map.get(vertex_id).copied()?, *uv)))
+                .collect();
+        }
+
+        if let Some(normals) = &mut self.face_normals {
+            *normals = normals
+                .iter()
+                .filter_map(|(face_id, normal)| Some((face_map.get(face_id).copied()?, *normal)))
+                .collect();
+        }
+
+        self.sharp_edges = self
+            .sharp_edges
+            .iter()
+            .filter_map(|he_id| halfedge_map.get(*he_id).copied())
+            .collect();
+
+        self.polygon_ids = self
+            .polygon_ids
+            .iter()
+            .filter_map(|(face_id, polygon_id)| {
+                Some((face_map.get(face_id).copied()?, *polygon_id))
+            })
+            .collect();
+
+        self.face_user_data_maps.remap(&face_map);
+        self.change_tracker.remap(&face_map);
+        self.vertex_attributes.remap(&vertex_map);
+
+        self.vertices = vertices;
+        self.halfedges = halfedges;
+        self.faces = faces;
+
+        self.finalize();
+        self.index_to_face_id.shrink_to_fit();
+    }
+}
+
+/// Looks up the new ID of `old_id`. Logs an error and returns `None` if `old_id` refers to an
+/// element that doesn't exist.
+fn remap<K: Key>(map: &SecondaryMap<K, K>, old_id: K) -> Option<K> {
+    let new_id = map.get(old_id).copied();
+
+    if new_id.is_none() {
+        error!("Reference to missing element {old_id:?}");
+    }
+
+    new_id
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+    use parry3d::query::{Ray, RayCast};
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        });
+        mesh_graph.compute_vertex_normals();
+        mesh_graph.uvs = Some(
+            mesh_graph
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, pos.truncate()))
+                .collect(),
+        );
+
+        mesh_graph.simplify_to_face_count(100);
+        mesh_graph.refit_bvh();
+
+        let lengths = mesh_graph.len();
+        let volume = mesh_graph.signed_volume();
+        let capacity = mesh_graph.vertices.capacity()
+            + mesh_graph.halfedges.capacity()
+            + mesh_graph.faces.capacity();
+
+        assert!(!mesh_graph.is_empty());
+        assert!(lengths.faces <= 100);
+
+        mesh_graph.shrink_to_fit();
+
+        assert_eq!(mesh_graph.len(), lengths);
+        assert!(
+            mesh_graph.vertices.capacity()
+                + mesh_graph.halfedges.capacity()
+                + mesh_graph.faces.capacity()
+                < capacity / 2
+        );
+        assert_eq!(mesh_graph.positions.len(), lengths.vertices);
+        assert_eq!(
+            mesh_graph.vertex_normals.as_ref().unwrap().len(),
+            lengths.vertices
+        );
+        assert_eq!(mesh_graph.uvs.as_ref().unwrap().len(), lengths.vertices);
+        assert_eq!(mesh_graph.index_to_face_id.len(), lengths.faces);
+
+        // connectivity and queries still work
+        assert!(mesh_graph.verify_twins().is_empty());
+        assert!(mesh_graph.boundary_loops().is_empty());
+        assert!((mesh_graph.signed_volume() - volume).abs() < 1e-5);
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            assert!(vertex.neighbours(&mesh_graph).count() >= 3);
+            assert_eq!(
+                mesh_graph.outgoing_halfedges[vertex_id].len(),
+                vertex.outgoing_halfedges(&mesh_graph).count()
+            );
+        }
+
+        for (face_id, face) in &mesh_graph.faces {
+            assert_eq!(face.id, face_id);
+            assert_eq!(mesh_graph.index_to_face_id[&face.index], face_id);
+        }
+
+        let hit =
+            mesh_graph.cast_local_ray(&Ray::new(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z), 10.0, true);
+        assert!(hit.is_some_and(|toi| toi > 3.5 && toi < 4.5));
+    }
+
+    #[test]
+    fn test_shrink_to_fit_remaps_dirty_faces() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        mesh_graph.track_changes(true);
+        mesh_graph.simplify_to_face_count(100);
+
+        let dirty_faces = mesh_graph.take_dirty_faces();
+        let surviving_dirty = dirty_faces
+            .iter()
+            .filter(|face_id| mesh_graph.faces.contains_key(**face_id))
+            .count();
+        assert!(surviving_dirty > 0);
+        assert!(surviving_dirty < dirty_faces.len());
+
+        for face_id in dirty_faces {
+            mesh_graph.change_tracker.mark_face(face_id);
+        }
+
+        mesh_graph.shrink_to_fit();
+
+        let dirty_faces = mesh_graph.take_dirty_faces();
+        assert_eq!(dirty_faces.len(), surviving_dirty);
+        for face_id in dirty_faces {
+            assert!(mesh_graph.faces.contains_key(face_id));
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let mesh_graph = MeshGraph::new();
+
+        assert!(mesh_graph.is_empty());
+        assert_eq!(mesh_graph.len(), MeshLengths::default());
+    }
+}
@@ -0,0 +1,139 @@
+use hashbrown::{HashMap, HashSet};
+use slotmap::SecondaryMap;
+
+use crate::{FaceId, MeshGraph, disjoint_sets::DisjointSets};
+
+impl MeshGraph {
+    /// Partitions all faces into components that are connected through edges, i.e. two faces
+    /// are in the same component if they can be reached from each other by walking across
+    /// twin halfedges.
+    ///
+    /// Uses union-find so this is almost linear in the number of faces. The components are
+    /// ordered by their first face in iteration order of `faces`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn connected_components(&self) -> Vec<HashSet<FaceId>> {
+        let face_indices = self
+            .faces
+            .keys()
+            .enumerate()
+            .map(|(index, face_id)| (face_id, index))
+            .collect::<SecondaryMap<_, _>>();
+
+        let mut face_sets = DisjointSets::new(face_indices.len());
+
+        for he in self.halfedges.values() {
+            let (Some(face_id), Some(twin_id)) = (he.face, he.twin) else {
+                continue;
+            };
+            let Some(twin_face_id) = self.halfedges.get(twin_id).and_then(|twin| twin.face) else {
+                continue;
+            };
+
+            if let (Some(&a), Some(&b)) =
+                (face_indices.get(face_id), face_indices.get(twin_face_id))
+            {
+                face_sets.union(a, b);
+            }
+        }
+
+        let mut components = Vec::<HashSet<FaceId>>::new();
+        let mut component_index = HashMap::new();
+
+        for (face_id, &index) in &face_indices {
+            let component = *component_index
+                .entry(face_sets.find(index))
+                .or_insert_with(|| {
+                    components.push(HashSet::new());
+                    components.len() - 1
+                });
+
+            components[component].insert(face_id);
+        }
+
+        components
+    }
+
+    /// Splits the mesh into one mesh graph per component of [`MeshGraph::connected_components`].
+    ///
+    /// Every component gets its own IDs and BVH. Positions, vertex normals, UVs, sharp edges,
+    /// polygon ids, face user data and vertex attributes are carried over. Vertices without
+    /// faces are dropped.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn split_components(self) -> Vec<MeshGraph> {
+        self.connected_components()
+            .iter()
+            .map(|faces| self.extract_faces(faces))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{primitives::IcoSphere, utils::two_spheres};
+
+    use super::*;
+
+    #[test]
+    fn test_connected_components() {
+        let mesh_graph = two_spheres(2);
+
+        let components = mesh_graph.connected_components();
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), components[1].len());
+        assert_eq!(
+            components[0].len() + components[1].len(),
+            mesh_graph.faces.len()
+        );
+        assert!(components[0].is_disjoint(&components[1]));
+
+        for component in &components {
+            let centers_on_right = component
+                .iter()
+                .filter(|face_id| mesh_graph.faces[**face_id].center(&mesh_graph).x > 1.5)
+                .count();
+            assert!(centers_on_right == 0 || centers_on_right == component.len());
+        }
+
+        assert!(MeshGraph::new().connected_components().is_empty());
+    }
+
+    #[test]
+    fn test_split_components() {
+        let sphere = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let mut mesh_graph = two_spheres(2);
+        mesh_graph.compute_vertex_normals();
+
+        let parts = mesh_graph.split_components();
+
+        assert_eq!(parts.len(), 2);
+
+        for part in &parts {
+            assert_eq!(part.vertices.len(), sphere.vertices.len());
+            assert_eq!(part.halfedges.len(), sphere.halfedges.len());
+            assert_eq!(part.faces.len(), sphere.faces.len());
+            assert!(part.is_closed());
+            assert!(part.verify_twins().is_empty());
+            assert!(part.verify_bvh_consistency().is_empty());
+            assert!((part.volume() - sphere.volume()).abs() < 1e-4);
+
+            let center = part.centroid();
+            for (vertex_id, normal) in part.vertex_normals.as_ref().unwrap() {
+                assert!(normal.dot(part.positions[vertex_id] - center) > 0.0);
+            }
+
+            for (vertex_id, vertex) in &part.vertices {
+                assert_eq!(
+                    vertex.outgoing_halfedges(part).count(),
+                    part.outgoing_halfedges[vertex_id].len()
+                );
+            }
+        }
+
+        assert!(parts[0].centroid().distance(parts[1].centroid()) > 2.9);
+    }
+}
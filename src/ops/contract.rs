@@ -0,0 +1,236 @@
+use crate::{MeshGraph, VertexId};
+use slotmap::SecondaryMap;
+
+/// Factor by which the contraction weight grows every iteration (`s_L` in the paper).
+const CONTRACTION_WEIGHT_GROWTH: f32 = 3.0;
+/// Maximum number of conjugate gradient iterations per linear solve.
+const MAX_SOLVER_ITERATIONS: usize = 500;
+/// Relative residual at which the conjugate gradient solver stops.
+const SOLVER_TOLERANCE: f32 = 1e-5;
+
+impl MeshGraph {
+    /// Contracts the mesh towards its curve skeleton by Laplacian based mesh contraction as
+    /// described in "Skeleton Extraction by Mesh Contraction" (Au et al. 2008).
+    ///
+    /// Every iteration solves the weighted least squares problem
+    /// `min |W_L * L * x|² + |W_H * (x - p)|²` where `L` is the [cotangent Laplacian](MeshGraph::cotangent_laplacian)
+    /// and `p` are the current positions. `W_L` starts at `contraction_weight` and grows by a
+    /// factor of 3 every iteration. `W_H` starts at `attraction_weight` and is increased for
+    /// vertices whose one-ring area has shrunk so already contracted regions stay in place.
+    ///
+    /// This is the preprocessing step for skeleton extraction. Vertex normals (if present) are
+    /// recomputed and the BVH is updated.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn contract(&mut self, iterations: usize, contraction_weight: f32, attraction_weight: f32) {
+        let initial_areas = self.one_ring_areas();
+
+        let mut contraction_weight = contraction_weight;
+
+        for _ in 0..iterations {
+            let laplacian = self.cotangent_laplacian();
+
+            if laplacian.is_empty() {
+                return;
+            }
+
+            let areas = self.one_ring_areas();
+            let attraction_weights_sqr = laplacian
+                .vertex_ids
+                .iter()
+                .map(|vertex_id| {
+                    let initial_area = initial_areas.get(*vertex_id).copied().unwrap_or(0.0);
+                    let area = areas.get(*vertex_id).copied().unwrap_or(0.0);
+
+                    if area > f32::EPSILON {
+                        attraction_weight * attraction_weight * initial_area / area
+                    } else {
+                        attraction_weight * attraction_weight
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let contraction_weight_sqr = contraction_weight * contraction_weight;
+
+            // normal equations: (W_L² * LᵀL + W_H²) * x = W_H² * p. L is symmetric.
+            let apply = |x: &[f32]| {
+                let lx = laplacian.apply(x);
+                laplacian
+                    .apply(&lx)
+                    .into_iter()
+                    .zip(x)
+                    .zip(&attraction_weights_sqr)
+                    .map(|((llx, x), w)| contraction_weight_sqr * llx + w * x)
+                    .collect::<Vec<_>>()
+            };
+
+            let positions = laplacian
+                .vertex_ids
+                .iter()
+                .map(|vertex_id| self.positions.get(*vertex_id).copied().unwrap_or_default())
+                .collect::<Vec<_>>();
+
+            let mut new_positions = positions.clone();
+
+            for axis in 0..3 {
+                let x0 = positions.iter().map(|p| p[axis]).collect::<Vec<_>>();
+                let b = x0
+                    .iter()
+                    .zip(&attraction_weights_sqr)
+                    .map(|(x, w)| x * w)
+                    .collect::<Vec<_>>();
+
+                let x = conjugate_gradient(apply, &b, x0);
+
+                for (pos, x) in new_positions.iter_mut().zip(x) {
+                    pos[axis] = x;
+                }
+            }
+
+            for (vertex_id, pos) in laplacian.vertex_ids.iter().zip(new_positions) {
+                self.positions.insert(*vertex_id, pos);
+            }
+
+            contraction_weight *= CONTRACTION_WEIGHT_GROWTH;
+        }
+
+        if self.vertex_normals.is_some() {
+            self.compute_vertex_normals();
+        }
+
+        for face in self.faces.values() {
+            self.bvh
+                .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
+        }
+        self.refit_bvh();
+    }
+
+    fn one_ring_areas(&self) -> SecondaryMap<VertexId, f32> {
+        let mut areas = SecondaryMap::with_capacity(self.vertices.len());
+
+        for face in self.faces.values() {
+            let vertices = face.vertices(self).collect::<Vec<_>>();
+            let positions = face.vertex_positions(self).collect::<Vec<_>>();
+
+            if positions.len() != 3 {
+                continue;
+            }
+
+            let area = (positions[1] - positions[0])
+                .cross(positions[2] - positions[0])
+                .length()
+                * 0.5;
+
+            for vertex_id in vertices {
+                if let Some(entry) = areas.entry(vertex_id) {
+                    *entry.or_insert(0.0) += area;
+                }
+            }
+        }
+
+        areas
+    }
+}
+
+/// Solves `A * x = b` for a symmetric positive definite `A` given as the function `apply`.
+fn conjugate_gradient(apply: impl Fn(&[f32]) -> Vec<f32>, b: &[f32], mut x: Vec<f32>) -> Vec<f32> {
+    let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>();
+
+    let ax = apply(&x);
+    let mut r = b.iter().zip(ax).map(|(b, ax)| b - ax).collect::<Vec<_>>();
+    let mut p = r.clone();
+    let mut r_sqr = dot(&r, &r);
+
+    let tolerance_sqr = dot(b, b) * SOLVER_TOLERANCE * SOLVER_TOLERANCE;
+
+    for _ in 0..MAX_SOLVER_ITERATIONS {
+        if r_sqr <= tolerance_sqr {
+            break;
+        }
+
+        let ap = apply(&p);
+        let p_ap = dot(&p, &ap);
+
+        if p_ap.abs() < f32::MIN_POSITIVE {
+            break;
+        }
+
+        let alpha = r_sqr / p_ap;
+
+        for ((x, r), (p, ap)) in x.iter_mut().zip(r.iter_mut()).zip(p.iter().zip(&ap)) {
+            *x += alpha * p;
+            *r -= alpha * ap;
+        }
+
+        let new_r_sqr = dot(&r, &r);
+        let beta = new_r_sqr / r_sqr;
+        r_sqr = new_r_sqr;
+
+        for (p, r) in p.iter_mut().zip(&r) {
+            *p = r + beta * *p;
+        }
+    }
+
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use glam::Vec3;
+    use itertools::Itertools;
+
+    use super::*;
+
+    fn cylinder(rings: usize, segments: usize) -> MeshGraph {
+        let positions = (0..rings)
+            .flat_map(|ring| {
+                (0..segments).map(move |segment| {
+                    let angle = segment as f32 / segments as f32 * TAU;
+                    Vec3::new(angle.cos(), angle.sin(), ring as f32 * 0.5)
+                })
+            })
+            .collect_vec();
+
+        let mut indices = vec![];
+        for ring in 0..rings - 1 {
+            for segment in 0..segments {
+                let a = ring * segments + segment;
+                let b = ring * segments + (segment + 1) % segments;
+                let c = a + segments;
+                let d = b + segments;
+
+                indices.extend([a, b, d, a, d, c]);
+            }
+        }
+
+        MeshGraph::indexed_triangles(&positions, &indices)
+    }
+
+    fn mean_radial_distance(mesh_graph: &MeshGraph) -> f32 {
+        mesh_graph
+            .positions
+            .values()
+            .map(|pos| pos.truncate().length())
+            .sum::<f32>()
+            / mesh_graph.positions.len() as f32
+    }
+
+    #[test]
+    fn test_cylinder_contracts_towards_axis() {
+        let mut mesh_graph = cylinder(9, 16);
+
+        let mut radial_distance = mean_radial_distance(&mesh_graph);
+
+        for _ in 0..3 {
+            mesh_graph.contract(1, 1.0, 1.0);
+
+            let new_radial_distance = mean_radial_distance(&mesh_graph);
+            assert!(new_radial_distance < radial_distance);
+            radial_distance = new_radial_distance;
+        }
+
+        assert!(radial_distance < 0.9);
+    }
+}
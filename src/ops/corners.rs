@@ -0,0 +1,106 @@
+use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, error_none};
+
+/// The corner of a triangle at one of its vertices. Return value of [`MeshGraph::corners`].
+#[derive(Debug, Clone, Copy)]
+pub struct Corner {
+    /// The face this corner belongs to
+    pub face: FaceId,
+    /// The vertex at the tip of the corner
+    pub vertex: VertexId,
+    /// Interior angle of the face at `vertex` in radians
+    pub angle: f32,
+    /// The halfedge of `face` that ends in `vertex`
+    pub incoming: HalfedgeId,
+    /// The halfedge of `face` that starts at `vertex`
+    pub outgoing: HalfedgeId,
+}
+
+impl MeshGraph {
+    /// Iterates over all triangle corners of the mesh, i.e. three per face.
+    ///
+    /// This is the natural unit for angle weighted normals, curvature and quality metrics.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn corners(&self) -> impl Iterator<Item = Corner> {
+        self.faces.iter().flat_map(move |(face_id, face)| {
+            face.halfedges(self)
+                .filter_map(move |incoming| self.corner(face_id, incoming))
+        })
+    }
+
+    pub(crate) fn corner(&self, face: FaceId, incoming: HalfedgeId) -> Option<Corner> {
+        let incoming_he = self
+            .halfedges
+            .get(incoming)
+            .or_else(error_none!("Halfedge not found"))?;
+        let outgoing = incoming_he.next.or_else(error_none!("Next missing"))?;
+        let outgoing_he = self
+            .halfedges
+            .get(outgoing)
+            .or_else(error_none!("Next halfedge not found"))?;
+
+        let vertex = incoming_he.end_vertex;
+        let pos = self
+            .positions
+            .get(vertex)
+            .or_else(error_none!("Position not found"))?;
+        let prev_pos = self
+            .positions
+            .get(incoming_he.start_vertex(self)?)
+            .or_else(error_none!("Position not found"))?;
+        let next_pos = self
+            .positions
+            .get(outgoing_he.end_vertex)
+            .or_else(error_none!("Position not found"))?;
+
+        Some(Corner {
+            face,
+            vertex,
+            angle: (prev_pos - pos).angle_between(next_pos - pos),
+            incoming,
+            outgoing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use hashbrown::HashMap;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_corner_angles_sum_to_pi() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let mut angle_sums = HashMap::<FaceId, (f32, usize)>::new();
+
+        for corner in mesh_graph.corners() {
+            assert_eq!(
+                mesh_graph.halfedges[corner.incoming].end_vertex,
+                corner.vertex
+            );
+            assert_eq!(
+                mesh_graph.halfedges[corner.outgoing].start_vertex(&mesh_graph),
+                Some(corner.vertex)
+            );
+
+            let (sum, count) = angle_sums.entry(corner.face).or_default();
+            *sum += corner.angle;
+            *count += 1;
+        }
+
+        assert_eq!(angle_sums.len(), mesh_graph.faces.len());
+
+        for (sum, count) in angle_sums.values() {
+            assert_eq!(*count, 3);
+            assert!((sum - PI).abs() < 1e-4);
+        }
+    }
+}
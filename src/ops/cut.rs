@@ -0,0 +1,308 @@
+use hashbrown::HashSet;
+use itertools::Itertools;
+
+use crate::{HalfedgeId, MeshGraph, VertexId, error_none, utils::error};
+
+impl MeshGraph {
+    /// Splits the mesh open along a closed loop of edges.
+    ///
+    /// `loop_halfedges` have to be consecutive interior halfedges, i.e. every halfedge starts at
+    /// the end vertex of the previous one and the last one ends at the start vertex of the first
+    /// one. The loop must not visit a vertex twice.
+    ///
+    /// Every vertex of the loop is duplicated. The faces on the left of the loop halfedges keep
+    /// the original vertices while the faces on the right are connected to the duplicates. This
+    /// leaves two boundary loops behind. Cutting along a loop around a handle reduces the genus
+    /// by one and the resulting holes can be filled afterwards.
+    ///
+    /// Returns the created duplicate vertices in the order of the loop. If the loop is invalid
+    /// nothing is changed and an empty list is returned.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn cut_along_loop(&mut self, loop_halfedges: &[HalfedgeId]) -> Vec<VertexId> {
+        let Some((loop_vertices, right_side_outgoing)) = self.cut_loop_fans(loop_halfedges) else {
+            return vec![];
+        };
+
+        let count = loop_halfedges.len();
+        let prev = |i: usize| (i + count - 1) % count;
+
+        let duplicates = loop_vertices
+            .iter()
+            .map(|vertex_id| {
+                // checked in `cut_loop_fans`
+                let duplicate_id = self.add_vertex(self.positions[*vertex_id]);
+
+                if let Some(normals) = &mut self.vertex_normals
+                    && let Some(normal) = normals.get(*vertex_id).copied()
+                {
+                    normals.insert(duplicate_id, normal);
+                }
+
+                if let Some(uvs) = &mut self.uvs
+                    && let Some(uv) = uvs.get(*vertex_id).copied()
+                {
+                    uvs.insert(duplicate_id, uv);
+                }
+
+                self.vertex_attributes
+                    .interpolate(duplicate_id, *vertex_id, *vertex_id, 0.0);
+
+                duplicate_id
+            })
+            .collect_vec();
+
+        // connect the faces on the right side to the duplicates
+        for (i, outgoing) in right_side_outgoing.iter().enumerate() {
+            for he_id in outgoing {
+                // checked in `cut_loop_fans`
+                let twin_id = self.halfedges[*he_id].twin.unwrap();
+                self.halfedges[twin_id].end_vertex = duplicates[i];
+
+                if let Some(face_id) = self.halfedges[twin_id].face {
+                    self.change_tracker.mark_face(face_id);
+                }
+            }
+
+            let twin_of_prev = self.halfedges[loop_halfedges[prev(i)]].twin.unwrap();
+            let moved = outgoing
+                .iter()
+                .skip(1)
+                .copied()
+                .chain([twin_of_prev])
+                .collect::<HashSet<_>>();
+
+            if let Some(original_outgoing) = self.outgoing_halfedges.get_mut(loop_vertices[i]) {
+                original_outgoing.retain(|he_id| !moved.contains(he_id));
+            }
+            self.outgoing_halfedges[duplicates[i]].extend(moved);
+        }
+
+        // new boundary halfedges on both sides of the cut
+        for (i, he_id) in loop_halfedges.iter().enumerate() {
+            let next = (i + 1) % count;
+            // checked in `cut_loop_fans`
+            let twin_id = self.halfedges[*he_id].twin.unwrap();
+
+            let (Some(left_boundary), Some(right_boundary)) = (
+                self.add_halfedge(loop_vertices[next], loop_vertices[i]),
+                self.add_halfedge(duplicates[i], duplicates[next]),
+            ) else {
+                error!("Couldn't add boundary halfedges");
+                continue;
+            };
+
+            self.halfedges[*he_id].twin = Some(left_boundary);
+            self.halfedges[left_boundary].twin = Some(*he_id);
+            self.halfedges[twin_id].twin = Some(right_boundary);
+            self.halfedges[right_boundary].twin = Some(twin_id);
+
+            // both halfedges of a sharp edge are sharp
+            if self.sharp_edges.contains(he_id) {
+                self.sharp_edges.extend([left_boundary, right_boundary]);
+            }
+
+            self.vertices[loop_vertices[next]].outgoing_halfedge = Some(left_boundary);
+            self.vertices[duplicates[i]].outgoing_halfedge = Some(right_boundary);
+        }
+
+        duplicates
+    }
+
+    /// Validates the loop for [`MeshGraph::cut_along_loop`] and returns its vertices together
+    /// with the outgoing halfedges of each vertex on the right side of the loop. These are
+    /// ordered clockwise and start with the loop halfedge itself.
+    fn cut_loop_fans(
+        &self,
+        loop_halfedges: &[HalfedgeId],
+    ) -> Option<(Vec<VertexId>, Vec<Vec<HalfedgeId>>)> {
+        if loop_halfedges.len() < 3 {
+            error!("A loop needs at least three halfedges");
+            return None;
+        }
+
+        let mut loop_vertices = Vec::with_capacity(loop_halfedges.len());
+
+        for (he_id, next_he_id) in loop_halfedges.iter().circular_tuple_windows() {
+            let he = self
+                .halfedges
+                .get(*he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+            let twin = self
+                .halfedges
+                .get(he.twin.or_else(error_none!("Twin missing"))?)
+                .or_else(error_none!("Twin not found"))?;
+
+            if he.is_boundary() || twin.is_boundary() {
+                error!("The loop has to consist of interior edges");
+                return None;
+            }
+
+            let next_he = self
+                .halfedges
+                .get(*next_he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+            if next_he.start_vertex(self) != Some(he.end_vertex) {
+                error!("The halfedges don't form a loop");
+                return None;
+            }
+
+            loop_vertices.push(twin.end_vertex);
+        }
+
+        if !loop_vertices.iter().all_unique() {
+            error!("The loop visits a vertex more than once");
+            return None;
+        }
+
+        let mut right_side_outgoing = Vec::with_capacity(loop_halfedges.len());
+
+        for (i, he_id) in loop_halfedges.iter().enumerate() {
+            let prev_he_id = loop_halfedges[(i + loop_halfedges.len() - 1) % loop_halfedges.len()];
+            let stop_he_id = self.halfedges[prev_he_id].twin?;
+
+            // rotate clockwise from the loop halfedge to the previous one
+            let mut outgoing = vec![*he_id];
+            let mut current_he_id = *he_id;
+
+            loop {
+                let twin_id = self.halfedges.get(current_he_id)?.twin?;
+                current_he_id = self
+                    .halfedges
+                    .get(twin_id)?
+                    .next
+                    .or_else(error_none!("Next halfedge missing"))?;
+
+                if current_he_id == stop_he_id {
+                    break;
+                }
+
+                if current_he_id == *he_id || outgoing.len() > self.halfedges.len() {
+                    error!("Couldn't rotate around the loop vertex");
+                    return None;
+                }
+
+                outgoing.push(current_he_id);
+            }
+
+            right_side_outgoing.push(outgoing);
+        }
+
+        Some((loop_vertices, right_side_outgoing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use glam::Vec3;
+
+    use super::*;
+
+    fn genus(mesh_graph: &MeshGraph) -> i32 {
+        let euler_characteristic = mesh_graph.vertices.len() as i32
+            - mesh_graph.halfedges.len() as i32 / 2
+            + mesh_graph.faces.len() as i32;
+        let boundary_count = mesh_graph.boundary_loops().len() as i32;
+
+        (2 - boundary_count - euler_characteristic) / 2
+    }
+
+    #[test]
+    fn test_cut_torus_along_meridian() {
+        let (major_segments, minor_segments) = (12, 8);
+
+        let positions = (0..major_segments * minor_segments)
+            .map(|i| {
+                let u = (i / minor_segments) as f32 / major_segments as f32 * TAU;
+                let v = (i % minor_segments) as f32 / minor_segments as f32 * TAU;
+                let radius = 2.0 + 0.5 * v.cos();
+
+                Vec3::new(radius * u.cos(), radius * u.sin(), 0.5 * v.sin())
+            })
+            .collect_vec();
+        let index = |u: usize, v: usize| (u % major_segments) * minor_segments + v % minor_segments;
+        let indices = (0..major_segments)
+            .cartesian_product(0..minor_segments)
+            .flat_map(|(u, v)| {
+                [
+                    index(u, v),
+                    index(u + 1, v),
+                    index(u + 1, v + 1),
+                    index(u, v),
+                    index(u + 1, v + 1),
+                    index(u, v + 1),
+                ]
+            })
+            .collect_vec();
+
+        let (mut mesh_graph, vertex_ids) =
+            MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+        assert!(mesh_graph.boundary_loops().is_empty());
+        assert_eq!(genus(&mesh_graph), 1);
+
+        let meridian = (0..minor_segments)
+            .map(|v| {
+                mesh_graph
+                    .halfedge_from_to(vertex_ids[index(0, v)], vertex_ids[index(0, v + 1)])
+                    .unwrap()
+            })
+            .collect_vec();
+
+        let face_count = mesh_graph.faces.len();
+
+        let duplicates = mesh_graph.cut_along_loop(&meridian);
+
+        assert_eq!(duplicates.len(), minor_segments);
+        assert_eq!(mesh_graph.faces.len(), face_count);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        let boundary_loops = mesh_graph.boundary_loops();
+        assert_eq!(boundary_loops.len(), 2);
+        assert!(
+            boundary_loops
+                .iter()
+                .all(|boundary_loop| boundary_loop.len() == minor_segments)
+        );
+        assert_eq!(genus(&mesh_graph), 0);
+
+        for (duplicate_id, v) in duplicates.iter().zip(0..) {
+            let original_id = vertex_ids[index(0, v)];
+
+            assert_eq!(
+                mesh_graph.positions[*duplicate_id],
+                mesh_graph.positions[original_id]
+            );
+            assert!(mesh_graph.vertices[*duplicate_id].is_boundary(&mesh_graph));
+            assert!(mesh_graph.vertices[original_id].is_boundary(&mesh_graph));
+            assert_eq!(
+                mesh_graph.vertices[*duplicate_id]
+                    .faces(&mesh_graph)
+                    .count()
+                    + mesh_graph.vertices[original_id].faces(&mesh_graph).count(),
+                6
+            );
+            assert_eq!(
+                mesh_graph.outgoing_halfedges[*duplicate_id].len()
+                    + mesh_graph.outgoing_halfedges[original_id].len(),
+                8
+            );
+        }
+    }
+
+    #[test]
+    fn test_cut_along_invalid_loop() {
+        let mut mesh_graph = MeshGraph::from(crate::primitives::IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let he_ids = mesh_graph.halfedges.keys().take(3).collect_vec();
+        let vertex_count = mesh_graph.vertices.len();
+
+        assert!(mesh_graph.cut_along_loop(&he_ids).is_empty());
+        assert!(mesh_graph.cut_along_loop(&[]).is_empty());
+        assert_eq!(mesh_graph.vertices.len(), vertex_count);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{FaceId, MeshGraph};
+
+/// Concave edges are this many times more expensive per radian than convex ones.
+const CONCAVE_WEIGHT_FACTOR: f32 = 2.0;
+
+impl MeshGraph {
+    /// Computes the edges of the dual graph, i.e. one entry per interior edge consisting of the
+    /// two adjacent faces and a weight.
+    ///
+    /// The weight is the length of the shared edge scaled by how much the surface bends at that
+    /// edge (see [`crate::Halfedge::dihedral_angle`]). Concave edges are weighted higher than convex
+    /// ones. This is the typical input for graph based mesh segmentation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn dual_adjacency(&self) -> Vec<(FaceId, FaceId, f32)> {
+        let mut adjacency = Vec::with_capacity(self.halfedges.len() / 2);
+
+        for (he_id, he) in &self.halfedges {
+            let (Some(face_id), Some(twin_id)) = (he.face, he.twin) else {
+                continue;
+            };
+
+            // visit every edge only once
+            if twin_id < he_id {
+                continue;
+            }
+
+            let Some(twin_face_id) = self.halfedges.get(twin_id).and_then(|twin| twin.face) else {
+                continue;
+            };
+
+            let Some(angle) = he.dihedral_angle(self) else {
+                continue;
+            };
+
+            let bend = if angle < 0.0 {
+                -angle * CONCAVE_WEIGHT_FACTOR
+            } else {
+                angle
+            };
+
+            adjacency.push((face_id, twin_face_id, he.length(self) * (1.0 + bend)));
+        }
+
+        adjacency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_cube_dual_adjacency() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let v = (0..8)
+            .map(|i| {
+                mesh_graph.add_vertex(Vec3::new(
+                    (i & 1) as f32,
+                    ((i >> 1) & 1) as f32,
+                    ((i >> 2) & 1) as f32,
+                ))
+            })
+            .collect_vec();
+
+        for [a, b, c] in [
+            [0, 2, 3],
+            [0, 3, 1],
+            [4, 5, 7],
+            [4, 7, 6],
+            [0, 1, 5],
+            [0, 5, 4],
+            [2, 6, 7],
+            [2, 7, 3],
+            [0, 4, 6],
+            [0, 6, 2],
+            [1, 3, 7],
+            [1, 7, 5],
+        ] {
+            mesh_graph.add_face_from_vertices(v[a], v[b], v[c]).unwrap();
+        }
+
+        let adjacency = mesh_graph.dual_adjacency();
+
+        // 12 cube edges plus 6 diagonals of the triangulated sides
+        assert_eq!(adjacency.len(), 18);
+
+        let sharp_edges = adjacency.iter().filter(|(_, _, w)| *w > 2.0).count();
+        let flat_edges = adjacency.iter().filter(|(_, _, w)| *w < 1.5).count();
+
+        assert_eq!(sharp_edges, 12);
+        assert_eq!(flat_edges, 6);
+
+        for (face_id1, face_id2, _) in adjacency {
+            assert!(
+                mesh_graph.faces[face_id1]
+                    .adjacent_faces(&mesh_graph)
+                    .contains(&face_id2)
+            );
+        }
+    }
+}
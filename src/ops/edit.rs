@@ -1,10 +1,14 @@
+use crate::utils::error;
 use glam::Vec3;
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 use slotmap::SparseSecondaryMap;
-use tracing::{error, instrument};
 
-use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, error_none, utils::unwrap_or_return};
+use crate::{
+    FaceId, HalfedgeId, MeshGraph, Selection, VertexId, error_none, utils::unwrap_or_return,
+};
+
+const VOLUME_NEWTON_ITERATIONS: usize = 8;
 
 pub struct MergeVertices {
     pub removed_vertices: Vec<VertexId>,
@@ -14,7 +18,7 @@ pub struct MergeVertices {
 
 impl MeshGraph {
     /// Merges the given vertices into a single vertex, reconnecting halfedges and faces as needed.
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn merge_vertices(
         &mut self,
         vertices: impl IntoIterator<Item = VertexId>,
@@ -107,6 +111,8 @@ impl MeshGraph {
                 self.bvh.remove(face.index);
             }
             self.faces.remove(face_id);
+            self.face_user_data_maps.remove(face_id);
+            self.change_tracker.mark_face(face_id);
         }
 
         // Collect outgoing halfedge cleanup info before removing halfedges
@@ -129,6 +135,7 @@ impl MeshGraph {
         // Remove halfedges
         for &he_id in &halfedges_to_remove {
             self.halfedges.remove(he_id);
+            self.sharp_edges.remove(&he_id);
         }
 
         let mut removed_vertices = Vec::with_capacity(vertex_ids.len() - 1);
@@ -257,6 +264,7 @@ impl MeshGraph {
                 for &he_id in &group {
                     if he_id != best_fwd {
                         self.halfedges.remove(he_id);
+                        self.sharp_edges.remove(&he_id);
                         removed_halfedges.push(he_id);
                     }
                 }
@@ -265,6 +273,7 @@ impl MeshGraph {
                 for &twin_id in &twins {
                     if Some(twin_id) != best_rev {
                         self.halfedges.remove(twin_id);
+                        self.sharp_edges.remove(&twin_id);
                         removed_halfedges.push(twin_id);
                     }
                 }
@@ -325,7 +334,7 @@ impl MeshGraph {
     ///    \ /                  \ /
     ///     *                    *
     /// ```
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn flip_edge(&mut self, halfedge_id: HalfedgeId) {
         #[cfg(feature = "rerun")]
         self.log_he_rerun("flip", halfedge_id);
@@ -390,12 +399,14 @@ impl MeshGraph {
         let face1 = unwrap_or_return!(self.faces.get(face_id1), "Face not found");
         self.bvh
             .insert_or_update_partially(face1.aabb(self), face1.index, 0.0);
+        self.change_tracker.mark_face(face1.id);
 
         // checked if halfedge exists above
         let face_id2 = unwrap_or_return!(self.halfedges[twin_he_id].face, "Face not found");
         let face2 = unwrap_or_return!(self.faces.get(face_id2), "Face not found");
         self.bvh
             .insert_or_update_partially(face2.aabb(self), face2.index, 0.0);
+        self.change_tracker.mark_face(face2.id);
     }
 
     /// Makes two halfedges twins of each other. Doesn't change anything else
@@ -408,7 +419,7 @@ impl MeshGraph {
     }
 
     /// Removes the outgoing halfedge from a vertex. Doesn't change anything else.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn remove_outgoing_halfedge(&mut self, vertex_id: VertexId, halfedge_id: HalfedgeId) {
         let outgoing_halfedges = unwrap_or_return!(
             self.outgoing_halfedges.get_mut(vertex_id),
@@ -419,7 +430,7 @@ impl MeshGraph {
     }
 
     /// Adds the outgoing halfedge to a vertex and overrides the vertex.outgoing_halfedge
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn add_outgoing_halfedge(&mut self, vertex_id: VertexId, outgoing_halfedge: HalfedgeId) {
         let vertex = unwrap_or_return!(self.vertices.get_mut(vertex_id), "Vertex not found");
         vertex.outgoing_halfedge = Some(outgoing_halfedge);
@@ -433,7 +444,7 @@ impl MeshGraph {
 
     /// Smooths the position of the vertex by computing the average of its own and its neighbors' positions and
     /// moving it there. Also called Laplacian Smoothing.
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn smooth_vertices(&mut self, vertices: impl IntoIterator<Item = VertexId>) {
         let mut new_positions = SparseSecondaryMap::new();
 
@@ -466,6 +477,7 @@ impl MeshGraph {
 
             self.bvh
                 .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
         }
     }
 
@@ -474,7 +486,7 @@ impl MeshGraph {
     ///
     /// > Note: If you want to smooth multiple vertices, use the `smooth_vertices` method instead of
     /// > calling this method multiple times.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn smooth_vertex(&mut self, vertex_id: VertexId) {
         let pos = unwrap_or_return!(
             self.compute_smoothed_vertex_pos(vertex_id),
@@ -493,10 +505,152 @@ impl MeshGraph {
 
             self.bvh
                 .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
         }
     }
 
-    #[instrument(skip(self))]
+    /// Laplacian smoothing of the selected vertices that keeps the enclosed volume constant.
+    ///
+    /// Every iteration moves each selected vertex by `lambda` towards the average of its
+    /// neighbours. Afterwards the selected region is scaled uniformly about its centroid such that
+    /// [`MeshGraph::signed_volume`] is the same as before smoothing. This is a cheap alternative
+    /// to Taubin smoothing when exact volume conservation is required.
+    ///
+    /// The mesh has to be closed for the volume to be meaningful.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, selection)))]
+    pub fn volume_preserving_smooth(
+        &mut self,
+        selection: &Selection,
+        iterations: usize,
+        lambda: f32,
+    ) {
+        let vertex_ids = selection
+            .resolve_to_vertices(self)
+            .into_iter()
+            .collect_vec();
+
+        if vertex_ids.is_empty() {
+            return;
+        }
+
+        let target_volume = self.signed_volume();
+
+        for _ in 0..iterations {
+            let smoothed_positions = vertex_ids
+                .iter()
+                .filter_map(|&vertex_id| {
+                    let pos = *self.positions.get(vertex_id)?;
+
+                    let mut neighbours_sum = Vec3::ZERO;
+                    let mut count = 0.0;
+                    for neighbour_id in self.vertices.get(vertex_id)?.neighbours(self) {
+                        neighbours_sum += *self.positions.get(neighbour_id)?;
+                        count += 1.0;
+                    }
+
+                    if count == 0.0 {
+                        return None;
+                    }
+
+                    Some((vertex_id, pos.lerp(neighbours_sum / count, lambda)))
+                })
+                .collect_vec();
+
+            let centroid = smoothed_positions.iter().map(|(_, pos)| *pos).sum::<Vec3>()
+                / smoothed_positions.len().max(1) as f32;
+
+            // The volume is a cubic polynomial of the scale so Newton converges very quickly
+            let mut scale = 1.0;
+            for _ in 0..VOLUME_NEWTON_ITERATIONS {
+                self.set_scaled_positions(&smoothed_positions, centroid, scale);
+                let error = self.signed_volume() - target_volume;
+
+                if error.abs() <= target_volume.abs() * 1e-6 {
+                    break;
+                }
+
+                self.set_scaled_positions(&smoothed_positions, centroid, scale + 1e-3);
+                let derivative = (self.signed_volume() - target_volume - error) / 1e-3;
+
+                if derivative.abs() < f32::EPSILON {
+                    break;
+                }
+
+                scale -= error / derivative;
+            }
+            self.set_scaled_positions(&smoothed_positions, centroid, scale);
+        }
+
+        let mut affected_face_ids = HashSet::new();
+
+        for &vertex_id in &vertex_ids {
+            self.compute_vertex_normal(vertex_id);
+            affected_face_ids.extend(self.vertices[vertex_id].faces(self));
+        }
+
+        for face_id in affected_face_ids {
+            let Some(face) = self.faces.get(face_id) else {
+                error!("Face {:?} does not exist", face_id);
+                continue;
+            };
+
+            self.bvh
+                .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
+        }
+    }
+
+    /// Smoothes the vertex normals by relaxing every normal towards the average of its
+    /// neighbours' normals and re-normalizing it. Positions are not changed.
+    ///
+    /// `lambda` in `[0, 1]` controls how far the normals are moved in each iteration.
+    /// This is useful to fix noisy imported normals. Does nothing if there are no vertex normals.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn smooth_normals(&mut self, iterations: usize, lambda: f32) {
+        let Some(mut normals) = self.vertex_normals.take() else {
+            error!("Vertex normals are missing");
+            return;
+        };
+
+        for _ in 0..iterations {
+            let smoothed_normals = normals
+                .iter()
+                .filter_map(|(vertex_id, normal)| {
+                    let neighbours_sum = self
+                        .vertices
+                        .get(vertex_id)?
+                        .neighbours(self)
+                        .filter_map(|neighbour_id| normals.get(neighbour_id))
+                        .sum::<Vec3>();
+
+                    let average = neighbours_sum.try_normalize()?;
+
+                    Some((
+                        vertex_id,
+                        normal
+                            .lerp(average, lambda)
+                            .try_normalize()
+                            .unwrap_or(*normal),
+                    ))
+                })
+                .collect_vec();
+
+            for (vertex_id, normal) in smoothed_normals {
+                normals[vertex_id] = normal;
+            }
+        }
+
+        self.vertex_normals = Some(normals);
+    }
+
+    fn set_scaled_positions(&mut self, positions: &[(VertexId, Vec3)], center: Vec3, scale: f32) {
+        for &(vertex_id, pos) in positions {
+            self.positions
+                .insert(vertex_id, center + (pos - center) * scale);
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn compute_smoothed_vertex_pos(&mut self, vertex_id: VertexId) -> Option<Vec3> {
         let vertex = self.vertices.get(vertex_id)?;
 
@@ -525,8 +679,98 @@ impl MeshGraph {
 mod tests {
     use glam::Vec3;
 
+    use crate::primitives::IcoSphere;
+
     use super::*;
 
+    fn roughness(mesh_graph: &MeshGraph) -> f32 {
+        mesh_graph
+            .vertices
+            .iter()
+            .map(|(vertex_id, vertex)| {
+                let neighbours = vertex.neighbours(mesh_graph).collect_vec();
+                let avg = neighbours
+                    .iter()
+                    .map(|n| mesh_graph.positions[*n])
+                    .sum::<Vec3>()
+                    / neighbours.len() as f32;
+
+                mesh_graph.positions[vertex_id].distance(avg)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_volume_preserving_smooth() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        });
+
+        // add high frequency noise
+        for (i, pos) in mesh_graph.positions.values_mut().enumerate() {
+            *pos *= if i % 2 == 0 { 1.05 } else { 0.95 };
+        }
+
+        let volume = mesh_graph.signed_volume();
+        let noisy_roughness = roughness(&mesh_graph);
+
+        mesh_graph.volume_preserving_smooth(&Selection::select_all(&mesh_graph), 5, 0.5);
+
+        assert!((mesh_graph.signed_volume() - volume).abs() < volume * 1e-4);
+        assert!(roughness(&mesh_graph) < noisy_roughness * 0.5);
+    }
+
+    #[test]
+    fn test_smooth_normals() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 3,
+        });
+        mesh_graph.compute_vertex_normals();
+
+        let positions = mesh_graph.positions.clone();
+
+        let max_radial_deviation = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .vertex_normals
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|(vertex_id, normal)| {
+                    normal.angle_between(mesh_graph.positions[vertex_id].normalize())
+                })
+                .fold(0.0_f32, f32::max)
+        };
+
+        // tilt every normal in a pseudo random tangential direction
+        for (i, (vertex_id, normal)) in mesh_graph
+            .vertex_normals
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .enumerate()
+        {
+            let (tangent, bitangent) = normal.any_orthonormal_pair();
+            let angle = i as f32 * 2.4;
+            let noise = tangent * angle.cos() + bitangent * angle.sin();
+
+            *normal = (positions[vertex_id].normalize() + noise * 0.4).normalize();
+        }
+
+        let noisy_deviation = max_radial_deviation(&mesh_graph);
+        assert!(noisy_deviation > 0.3);
+
+        mesh_graph.smooth_normals(10, 0.5);
+
+        assert!(max_radial_deviation(&mesh_graph) < 0.1);
+
+        for (vertex_id, normal) in mesh_graph.vertex_normals.as_ref().unwrap() {
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert_eq!(mesh_graph.positions[vertex_id], positions[vertex_id]);
+        }
+    }
+
     #[cfg(feature = "gltf")]
     #[test]
     fn test_merge_vertices_cube() {
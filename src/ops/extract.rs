@@ -0,0 +1,223 @@
+use hashbrown::HashSet;
+use slotmap::{SecondaryMap, SlotMap};
+
+use crate::{FaceId, HalfedgeId, MeshGraph, Selection, VertexId};
+
+impl MeshGraph {
+    /// Copies the faces of `selection` (see [`Selection::resolve_to_faces`]) into a new mesh graph.
+    ///
+    /// The new mesh has its own IDs. Positions, vertex normals, UVs, sharp edges, polygon ids,
+    /// face user data and vertex attributes of the involved elements are carried over. Where the
+    /// selected region borders faces that aren't selected, boundary halfedges are created. This
+    /// is useful to export a region of the mesh.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, selection)))]
+    pub fn extract_selection(&self, selection: &Selection) -> MeshGraph {
+        self.extract_faces(&selection.resolve_to_faces(self))
+    }
+
+    /// Copies the given faces together with their halfedges, the twins of these and their
+    /// vertices into a new mesh graph. Twins whose face isn't copied become boundary halfedges.
+    pub(crate) fn extract_faces(&self, face_ids: &HashSet<FaceId>) -> MeshGraph {
+        let mut mesh_graph = MeshGraph::new();
+
+        let halfedge_ids = face_ids
+            .iter()
+            .filter_map(|face_id| self.faces.get(*face_id))
+            .flat_map(|face| face.halfedges(self))
+            .flat_map(|he_id| [Some(he_id), self.halfedges[he_id].twin])
+            .flatten()
+            .filter(|he_id| self.halfedges.contains_key(*he_id))
+            .collect::<HashSet<_>>();
+
+        let mut vertices = SlotMap::<VertexId, _>::with_key();
+        let mut halfedges = SlotMap::<HalfedgeId, _>::with_key();
+        let mut faces = SlotMap::<FaceId, _>::with_key();
+
+        let vertex_map = halfedge_ids
+            .iter()
+            .map(|he_id| self.halfedges[*he_id].end_vertex)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|vertex_id| {
+                let vertex = self.vertices.get(vertex_id)?;
+                Some((vertex_id, vertices.insert(*vertex)))
+            })
+            .collect::<SecondaryMap<_, _>>();
+        let halfedge_map = halfedge_ids
+            .iter()
+            .map(|he_id| (*he_id, halfedges.insert(self.halfedges[*he_id])))
+            .collect::<SecondaryMap<_, _>>();
+        let face_map = face_ids
+            .iter()
+            .filter_map(|face_id| Some((*face_id, faces.insert(*self.faces.get(*face_id)?))))
+            .collect::<SecondaryMap<_, _>>();
+
+        for vertex in vertices.values_mut() {
+            // the outgoing halfedge can belong to another component at a non-manifold vertex
+            vertex.outgoing_halfedge = vertex
+                .outgoing_halfedge
+                .and_then(|he_id| halfedge_map.get(he_id).copied());
+        }
+
+        for he in halfedges.values_mut() {
+            he.end_vertex = vertex_map.get(he.end_vertex).copied().unwrap_or_default();
+            he.face = he.face.and_then(|face_id| face_map.get(face_id).copied());
+            he.twin = he.twin.and_then(|he_id| halfedge_map.get(he_id).copied());
+            // halfedges of faces that aren't extracted become boundary halfedges
+            he.next = he
+                .face
+                .and(he.next)
+                .and_then(|he_id| halfedge_map.get(he_id).copied());
+        }
+
+        for (face_id, face) in &mut faces {
+            face.halfedge = halfedge_map.get(face.halfedge).copied().unwrap_or_default();
+            face.id = face_id;
+        }
+
+        for (old_id, new_id) in &vertex_map {
+            if let Some(pos) = self.positions.get(old_id) {
+                mesh_graph.positions.insert(*new_id, *pos);
+            }
+        }
+
+        if let Some(normals) = &self.vertex_normals {
+            mesh_graph.vertex_normals = Some(
+                vertex_map
+                    .iter()
+                    .filter_map(|(old_id, new_id)| Some((*new_id, *normals.get(old_id)?)))
+                    .collect(),
+            );
+        }
+
+        if let Some(uvs) = &self.uvs {
+            mesh_graph.uvs = Some(
+                vertex_map
+                    .iter()
+                    .filter_map(|(old_id, new_id)| Some((*new_id, *uvs.get(old_id)?)))
+                    .collect(),
+            );
+        }
+
+        mesh_graph.sharp_edges = self
+            .sharp_edges
+            .iter()
+            .filter_map(|he_id| halfedge_map.get(*he_id).copied())
+            .collect();
+
+        mesh_graph.polygon_ids = face_map
+            .iter()
+            .filter_map(|(old_id, new_id)| Some((*new_id, *self.polygon_ids.get(old_id)?)))
+            .collect();
+
+        mesh_graph.face_user_data_maps = self.face_user_data_maps.clone();
+        mesh_graph.face_user_data_maps.remap(&face_map);
+
+        mesh_graph.vertex_attributes = self.vertex_attributes.clone();
+        mesh_graph.vertex_attributes.remap(&vertex_map);
+
+        mesh_graph.vertices = vertices;
+        mesh_graph.halfedges = halfedges;
+        mesh_graph.faces = faces;
+
+        mesh_graph.finalize();
+
+        let vertex_ids = mesh_graph.vertices.keys().collect::<Vec<_>>();
+        for vertex_id in vertex_ids {
+            if mesh_graph.vertices[vertex_id].outgoing_halfedge.is_none() {
+                mesh_graph.vertices[vertex_id].outgoing_halfedge = mesh_graph
+                    .outgoing_halfedges
+                    .get(vertex_id)
+                    .and_then(|he_ids| he_ids.first().copied());
+            }
+        }
+        mesh_graph.make_all_outgoing_halfedges_boundary_if_possible();
+
+        mesh_graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+    use itertools::Itertools;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_single_face() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+        mesh_graph.compute_vertex_normals();
+
+        let face_id = mesh_graph.faces.keys().next().unwrap();
+        let selection = Selection::from_iter([face_id]);
+
+        let extracted = mesh_graph.extract_selection(&selection);
+
+        assert_eq!(extracted.vertices.len(), 3);
+        assert_eq!(extracted.halfedges.len(), 6);
+        assert_eq!(extracted.faces.len(), 1);
+        assert!(extracted.verify_twins().is_empty());
+        assert!(extracted.verify_bvh_consistency().is_empty());
+
+        let boundary_loops = extracted.boundary_loops();
+        assert_eq!(boundary_loops.len(), 1);
+        assert_eq!(boundary_loops[0].len(), 3);
+
+        let original_positions = mesh_graph.faces[face_id]
+            .vertices(&mesh_graph)
+            .map(|vertex_id| mesh_graph.positions[vertex_id])
+            .collect_vec();
+        let extracted_face = extracted.faces.values().next().unwrap();
+        let extracted_positions = extracted_face
+            .vertices(&extracted)
+            .map(|vertex_id| extracted.positions[vertex_id])
+            .collect_vec();
+
+        // same winding, maybe starting at another vertex
+        assert!((0..3).any(|offset| {
+            (0..3).all(|i| original_positions[i] == extracted_positions[(i + offset) % 3])
+        }));
+        assert_eq!(extracted.vertex_normals.as_ref().unwrap().len(), 3);
+        assert!(
+            extracted
+                .vertices
+                .values()
+                .all(|vertex| vertex.is_boundary(&extracted))
+        );
+    }
+
+    #[test]
+    fn test_extract_region() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let selection = mesh_graph
+            .faces
+            .values()
+            .filter(|face| face.center(&mesh_graph).y > 0.5)
+            .map(|face| face.id)
+            .collect::<Selection>();
+        let face_count = selection.faces.len();
+
+        let extracted = mesh_graph.extract_selection(&selection);
+
+        assert_eq!(extracted.faces.len(), face_count);
+        assert_eq!(extracted.connected_components().len(), 1);
+        assert_eq!(extracted.boundary_loops().len(), 1);
+        assert!(extracted.verify_twins().is_empty());
+        assert!(extracted.positions.values().all(|pos| pos.y > 0.0));
+        assert!((extracted.centroid() - Vec3::Y).length() < 0.5);
+
+        let extracted = mesh_graph.extract_selection(&Selection::default());
+        assert!(extracted.vertices.is_empty());
+        assert!(extracted.faces.is_empty());
+    }
+}
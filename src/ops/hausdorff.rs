@@ -0,0 +1,165 @@
+use crate::MeshGraph;
+use glam::Vec3;
+use itertools::Itertools;
+use parry3d::query::PointQuery;
+
+/// Return value of [`MeshGraph::hausdorff_distance`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HausdorffResult {
+    /// Distances from the samples of `self` to the surface of `other`
+    pub forward: DistanceStats,
+    /// Distances from the samples of `other` to the surface of `self`
+    pub backward: DistanceStats,
+    /// The symmetric Hausdorff distance, i.e. the maximum of both directions
+    pub max: f32,
+}
+
+/// Statistics of sampled point-to-surface distances
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DistanceStats {
+    /// Largest distance (the one-sided Hausdorff distance)
+    pub max: f32,
+    /// Average distance
+    pub mean: f32,
+    /// Root mean square of the distances
+    pub rms: f32,
+}
+
+impl MeshGraph {
+    /// Approximates the symmetric Hausdorff distance between `self` and `other`.
+    ///
+    /// All vertices plus `samples` points distributed over the faces (proportional to their area)
+    /// of each mesh are projected onto the surface of the other mesh using the BVH. This is the
+    /// standard metric for the error of decimation or remeshing.
+    ///
+    /// If one of the meshes has no faces, all distances are infinite.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, other)))]
+    pub fn hausdorff_distance(&self, other: &MeshGraph, samples: usize) -> HausdorffResult {
+        let forward = self.one_sided_distance(other, samples);
+        let backward = other.one_sided_distance(self, samples);
+
+        HausdorffResult {
+            forward,
+            backward,
+            max: forward.max.max(backward.max),
+        }
+    }
+
+    fn one_sided_distance(&self, other: &MeshGraph, samples: usize) -> DistanceStats {
+        if self.faces.is_empty() || other.faces.is_empty() {
+            return DistanceStats {
+                max: f32::INFINITY,
+                mean: f32::INFINITY,
+                rms: f32::INFINITY,
+            };
+        }
+
+        let points = self.surface_samples(samples);
+
+        let mut max = 0.0_f32;
+        let mut sum = 0.0;
+        let mut sum_sqr = 0.0;
+
+        for point in &points {
+            let distance = point.distance(other.project_local_point(*point, false).point);
+
+            max = max.max(distance);
+            sum += distance;
+            sum_sqr += distance * distance;
+        }
+
+        let count = points.len() as f32;
+
+        DistanceStats {
+            max,
+            mean: sum / count,
+            rms: (sum_sqr / count).sqrt(),
+        }
+    }
+
+    /// Deterministic samples on the surface. Contains all vertex positions and `samples`
+    /// area weighted points inside the faces.
+    fn surface_samples(&self, samples: usize) -> Vec<Vec3> {
+        let triangles = self
+            .faces
+            .values()
+            .filter_map(|face| face.vertex_positions(self).collect_array::<3>())
+            .collect_vec();
+
+        let cumulative_areas = triangles
+            .iter()
+            .scan(0.0, |total, [a, b, c]| {
+                *total += (*b - *a).cross(*c - *a).length() * 0.5;
+                Some(*total)
+            })
+            .collect_vec();
+
+        let total_area = cumulative_areas.last().copied().unwrap_or(0.0);
+
+        let mut points = self.positions.values().copied().collect_vec();
+        points.reserve(samples);
+
+        if triangles.is_empty() || total_area <= 0.0 {
+            return points;
+        }
+
+        // R2 low discrepancy sequence for the barycentric coordinates
+        const G: f32 = 1.324_718;
+        let alpha = Vec3::new(1.0 / G, 1.0 / (G * G), 0.0);
+
+        for i in 0..samples {
+            let target_area = (i as f32 + 0.5) / samples as f32 * total_area;
+            let index = cumulative_areas
+                .partition_point(|area| *area < target_area)
+                .min(triangles.len() - 1);
+            let [a, b, c] = triangles[index];
+
+            let mut u = (0.5 + alpha.x * i as f32).fract();
+            let mut v = (0.5 + alpha.y * i as f32).fract();
+            if u + v > 1.0 {
+                u = 1.0 - u;
+                v = 1.0 - v;
+            }
+
+            points.push(a + (b - a) * u + (c - a) * v);
+        }
+
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_hausdorff_distance() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        });
+
+        let self_distance = mesh_graph.hausdorff_distance(&mesh_graph, 1000);
+        assert!(self_distance.max < 1e-5);
+
+        let mut decimated = mesh_graph.clone();
+        decimated.collapse_until_edges_above_min_length(0.2 * 0.2, &mut HashSet::new());
+        decimated.rebuild_bvh();
+
+        assert!(decimated.faces.len() < mesh_graph.faces.len());
+
+        let distance = mesh_graph.hausdorff_distance(&decimated, 1000);
+        assert!(distance.max > 1e-4);
+        assert!(distance.max < 0.2);
+        assert!(distance.forward.mean <= distance.forward.rms);
+        assert!(distance.forward.rms <= distance.forward.max);
+        assert_eq!(
+            distance.max,
+            distance.forward.max.max(distance.backward.max)
+        );
+    }
+}
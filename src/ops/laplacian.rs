@@ -0,0 +1,102 @@
+use crate::{MeshGraph, VertexId};
+use glam::Vec3;
+use hashbrown::HashMap;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+/// Cotangents are clamped to this magnitude to keep nearly degenerate triangles from dominating.
+const MAX_COTANGENT: f32 = 1e3;
+
+/// Sparse cotangent Laplacian of a mesh graph. Return value of [`MeshGraph::cotangent_laplacian`].
+///
+/// The weight between two neighbouring vertices is `cot(α) + cot(β)` where `α` and `β` are the
+/// angles opposite to their shared edge. The diagonal is the negative sum of a row's weights.
+#[derive(Debug, Clone, Default)]
+pub struct CotangentLaplacian {
+    /// The vertex of each row and column
+    pub vertex_ids: Vec<VertexId>,
+    /// Maps each vertex to its row and column
+    pub index_map: SecondaryMap<VertexId, usize>,
+    /// The off-diagonal entries `(column, weight)` of every row
+    pub rows: Vec<Vec<(usize, f32)>>,
+}
+
+impl CotangentLaplacian {
+    /// Number of rows (and columns)
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if the matrix has no rows
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Computes the matrix-vector product `L * x`.
+    pub fn apply(&self, x: &[f32]) -> Vec<f32> {
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row.iter().map(|&(j, w)| w * (x[j] - x[i])).sum())
+            .collect()
+    }
+}
+
+/// Cotangent of `angle` clamped to [`MAX_COTANGENT`].
+#[inline]
+pub(crate) fn clamped_cotangent(angle: f32) -> f32 {
+    (angle.cos() / angle.sin().max(f32::EPSILON)).clamp(-MAX_COTANGENT, MAX_COTANGENT)
+}
+
+/// Clamped cotangent of the angle at `apex` in the triangle `(apex, a, b)`.
+#[inline]
+pub(crate) fn cotangent_at(apex: Vec3, a: Vec3, b: Vec3) -> f32 {
+    clamped_cotangent((a - apex).angle_between(b - apex))
+}
+
+impl MeshGraph {
+    /// Computes the cotangent Laplacian of the whole mesh.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn cotangent_laplacian(&self) -> CotangentLaplacian {
+        let vertex_ids = self.vertices.keys().collect_vec();
+
+        let mut index_map = SecondaryMap::with_capacity(vertex_ids.len());
+        for (index, vertex_id) in vertex_ids.iter().enumerate() {
+            index_map.insert(*vertex_id, index);
+        }
+
+        let mut weights = vec![HashMap::<usize, f32>::new(); vertex_ids.len()];
+
+        for corner in self.corners() {
+            // the edge opposite to the corner goes from the end of `outgoing` to the start of `incoming`
+            let (Some(a), Some(b)) = (
+                self.halfedges[corner.outgoing]
+                    .next
+                    .and_then(|he_id| self.halfedges.get(he_id))
+                    .and_then(|he| he.start_vertex(self)),
+                self.halfedges[corner.incoming].start_vertex(self),
+            ) else {
+                continue;
+            };
+            let (Some(&a), Some(&b)) = (index_map.get(a), index_map.get(b)) else {
+                continue;
+            };
+
+            let cot = clamped_cotangent(corner.angle);
+
+            *weights[a].entry(b).or_default() += cot;
+            *weights[b].entry(a).or_default() += cot;
+        }
+
+        CotangentLaplacian {
+            vertex_ids,
+            index_map,
+            rows: weights
+                .into_iter()
+                .map(|row| row.into_iter().sorted_by_key(|(j, _)| *j).collect())
+                .collect(),
+        }
+    }
+}
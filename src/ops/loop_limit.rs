@@ -0,0 +1,134 @@
+use std::f32::consts::TAU;
+
+use crate::{MeshGraph, VertexId, error_none};
+use glam::Vec3;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+impl MeshGraph {
+    /// Returns for every vertex its position on the limit surface of Loop subdivision
+    /// without changing the mesh.
+    ///
+    /// Interior vertices use the valence dependent limit stencil of Loop (1987). Boundary
+    /// vertices use the cubic B-spline limit stencil `(1, 4, 1) / 6` of their two boundary
+    /// neighbours. Non-manifold boundary vertices keep their position.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn loop_limit_positions(&self) -> SecondaryMap<VertexId, Vec3> {
+        let mut limit_positions = SecondaryMap::with_capacity(self.vertices.len());
+
+        for vertex_id in self.vertices.keys() {
+            let Some(limit_pos) = self.loop_limit_position(vertex_id) else {
+                continue;
+            };
+
+            limit_positions.insert(vertex_id, limit_pos);
+        }
+
+        limit_positions
+    }
+
+    fn loop_limit_position(&self, vertex_id: VertexId) -> Option<Vec3> {
+        let pos = *self
+            .positions
+            .get(vertex_id)
+            .or_else(error_none!("Position not found"))?;
+
+        let mut neighbours = vec![];
+        let mut boundary_neighbours = vec![];
+
+        for &he_id in self
+            .outgoing_halfedges
+            .get(vertex_id)
+            .or_else(error_none!("Outgoing halfedges not found"))?
+        {
+            let he = self
+                .halfedges
+                .get(he_id)
+                .or_else(error_none!("Halfedge not found"))?;
+            let twin = self
+                .halfedges
+                .get(he.twin.or_else(error_none!("Twin missing"))?)
+                .or_else(error_none!("Twin not found"))?;
+
+            let neighbour_pos = *self
+                .positions
+                .get(he.end_vertex)
+                .or_else(error_none!("Neighbour position not found"))?;
+
+            if he.is_boundary() || twin.is_boundary() {
+                boundary_neighbours.push(neighbour_pos);
+            }
+            neighbours.push(neighbour_pos);
+        }
+
+        if !boundary_neighbours.is_empty() {
+            let Some([prev, next]) = boundary_neighbours.into_iter().collect_array() else {
+                return Some(pos);
+            };
+
+            return Some((prev + 4.0 * pos + next) / 6.0);
+        }
+
+        if neighbours.is_empty() {
+            return Some(pos);
+        }
+
+        let valence = neighbours.len() as f32;
+        let beta = (0.625 - (0.375 + 0.25 * (TAU / valence).cos()).powi(2)) / valence;
+        let weight = 1.0 / (0.375 / beta + valence);
+
+        Some(pos * (1.0 - valence * weight) + neighbours.into_iter().sum::<Vec3>() * weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    fn max_limit_offset(subdivisions: u8) -> f32 {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions,
+        });
+
+        let limit_positions = mesh_graph.loop_limit_positions();
+        assert_eq!(limit_positions.len(), mesh_graph.vertices.len());
+
+        limit_positions
+            .iter()
+            .map(|(vertex_id, limit_pos)| limit_pos.distance(mesh_graph.positions[vertex_id]))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_loop_limit_positions() {
+        let coarse_offset = max_limit_offset(1);
+        let fine_offset = max_limit_offset(4);
+
+        assert!(fine_offset < 0.01);
+        assert!(fine_offset < coarse_offset);
+    }
+
+    #[test]
+    fn test_loop_limit_positions_boundary() {
+        let mesh_graph = MeshGraph::indexed_triangles(
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            &[0, 1, 2, 0, 2, 3],
+        );
+
+        let limit_positions = mesh_graph.loop_limit_positions();
+        let vertex_ids = mesh_graph.vertices.keys().collect_vec();
+
+        // corner 0 has the boundary neighbours 1 and 3
+        assert!(
+            limit_positions[vertex_ids[0]].distance(Vec3::new(1.0 / 6.0, 1.0 / 6.0, 0.0)) < 1e-6
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::f32::consts::TAU;
+
+use glam::Vec3;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+
+use crate::{MeshGraph, VertexId, utils::error};
+
+impl MeshGraph {
+    /// Applies one step of Loop subdivision.
+    ///
+    /// Every triangle is split into four by inserting a vertex on each edge. Interior vertices
+    /// are moved with the valence dependent stencil of Loop (1987) and edge vertices are placed
+    /// at `3/8` of the edge endpoints plus `1/8` of the two opposite vertices.
+    ///
+    /// Boundary edges and sharp edges (see [`MeshGraph::set_edge_sharp`]) are treated as
+    /// creases: their edge vertices are the edge midpoints, vertices on exactly two creases
+    /// follow the cubic B-spline rule of the crease and vertices on more than two creases stay
+    /// in place. The halves of sharp edges are sharp again, so creases stay crisp when this is
+    /// applied repeatedly.
+    ///
+    /// UVs and vertex attributes are interpolated linearly: the original vertices keep their
+    /// values and the new edge vertices get the averages of the edge end points.
+    ///
+    /// The mesh is rebuilt, so all vertex, halfedge and face ids change and face user data and
+    /// polygon ids are dropped. Vertex normals are recomputed if present.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn loop_subdivide(&mut self) {
+        let triangles = self
+            .faces
+            .values()
+            .filter_map(|face| face.vertices(self).collect_array::<3>())
+            .collect_vec();
+
+        if triangles.is_empty() {
+            return;
+        }
+
+        let sorted = |a: VertexId, b: VertexId| if a < b { (a, b) } else { (b, a) };
+
+        let sharp_edges = self
+            .sharp_edges
+            .iter()
+            .filter_map(|he_id| {
+                let he = self.halfedges.get(*he_id)?;
+                Some(sorted(he.start_vertex(self)?, he.end_vertex))
+            })
+            .collect::<HashSet<_>>();
+
+        // the vertices opposite of every edge in the adjacent triangles
+        let mut edge_opposites = HashMap::<(VertexId, VertexId), Vec<VertexId>>::new();
+        for [a, b, c] in &triangles {
+            for (start, end, opposite) in [(a, b, c), (b, c, a), (c, a, b)] {
+                edge_opposites
+                    .entry(sorted(*start, *end))
+                    .or_default()
+                    .push(*opposite);
+            }
+        }
+
+        let is_crease = |edge: &(VertexId, VertexId), opposites: &[VertexId]| {
+            opposites.len() != 2 || sharp_edges.contains(edge)
+        };
+
+        let mut vertex_edges = HashMap::<VertexId, Vec<(VertexId, VertexId)>>::new();
+        for edge in edge_opposites.keys() {
+            vertex_edges.entry(edge.0).or_default().push(*edge);
+            vertex_edges.entry(edge.1).or_default().push(*edge);
+        }
+
+        let mut positions = vec![];
+        let mut vertex_indices = HashMap::<VertexId, usize>::new();
+        let mut edge_indices = HashMap::<(VertexId, VertexId), usize>::new();
+
+        for (vertex_id, edges) in vertex_edges.iter().sorted_by_key(|(id, _)| **id) {
+            let pos = self.positions[*vertex_id];
+            let other = |edge: &(VertexId, VertexId)| {
+                if edge.0 == *vertex_id { edge.1 } else { edge.0 }
+            };
+
+            let creases = edges
+                .iter()
+                .filter(|edge| is_crease(edge, &edge_opposites[*edge]))
+                .collect_vec();
+
+            let new_pos = match creases.len() {
+                0 | 1 => {
+                    let valence = edges.len() as f32;
+                    let beta = (0.625 - (0.375 + 0.25 * (TAU / valence).cos()).powi(2)) / valence;
+
+                    pos * (1.0 - valence * beta)
+                        + edges
+                            .iter()
+                            .map(|edge| self.positions[other(edge)])
+                            .sum::<Vec3>()
+                            * beta
+                }
+                2 => {
+                    (self.positions[other(creases[0])]
+                        + 6.0 * pos
+                        + self.positions[other(creases[1])])
+                        / 8.0
+                }
+                _ => pos,
+            };
+
+            vertex_indices.insert(*vertex_id, positions.len());
+            positions.push(new_pos);
+        }
+
+        for (edge, opposites) in edge_opposites.iter().sorted_by_key(|(edge, _)| **edge) {
+            let sum = self.positions[edge.0] + self.positions[edge.1];
+
+            let edge_point = if is_crease(edge, opposites) {
+                sum * 0.5
+            } else {
+                sum * 0.375 + (self.positions[opposites[0]] + self.positions[opposites[1]]) * 0.125
+            };
+
+            edge_indices.insert(*edge, positions.len());
+            positions.push(edge_point);
+        }
+
+        let mut face_indices = Vec::with_capacity(triangles.len() * 12);
+        for [a, b, c] in &triangles {
+            let ab = edge_indices[&sorted(*a, *b)];
+            let bc = edge_indices[&sorted(*b, *c)];
+            let ca = edge_indices[&sorted(*c, *a)];
+
+            face_indices.extend([vertex_indices[a], ab, ca]);
+            face_indices.extend([ab, vertex_indices[b], bc]);
+            face_indices.extend([ca, bc, vertex_indices[c]]);
+            face_indices.extend([ab, bc, ca]);
+        }
+
+        let (mut subdivided, new_vertex_ids) =
+            Self::indexed_triangles_and_vertex_ids(&positions, &face_indices);
+
+        if subdivided.faces.len() != triangles.len() * 4 {
+            error!("Some of the subdivided triangles couldn't be added");
+        }
+
+        for (a, b) in sharp_edges {
+            let (Some(&a_idx), Some(&b_idx), Some(&edge_idx)) = (
+                vertex_indices.get(&a),
+                vertex_indices.get(&b),
+                edge_indices.get(&sorted(a, b)),
+            ) else {
+                continue;
+            };
+
+            for (start, end) in [(a_idx, edge_idx), (edge_idx, b_idx)] {
+                if let Some(he_id) =
+                    subdivided.halfedge_from_to(new_vertex_ids[start], new_vertex_ids[end])
+                {
+                    subdivided.set_edge_sharp(he_id, true);
+                }
+            }
+        }
+
+        let sources = vertex_indices
+            .iter()
+            .map(|(vertex_id, idx)| (new_vertex_ids[*idx], vec![*vertex_id]))
+            .chain(
+                edge_indices
+                    .iter()
+                    .map(|((a, b), idx)| (new_vertex_ids[*idx], vec![*a, *b])),
+            )
+            .collect_vec();
+        self.transfer_vertex_data(&mut subdivided, &sources);
+
+        if self.vertex_normals.is_some() {
+            subdivided.compute_vertex_normals();
+        }
+
+        let mut change_tracker = std::mem::take(&mut self.change_tracker);
+        for face_id in self.faces.keys().chain(subdivided.faces.keys()) {
+            change_tracker.mark_face(face_id);
+        }
+        subdivided.change_tracker = change_tracker;
+
+        *self = subdivided;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use crate::primitives::{Cuboid, IcoSphere};
+
+    use super::*;
+
+    fn max_radius(mesh_graph: &MeshGraph) -> f32 {
+        mesh_graph
+            .positions
+            .values()
+            .map(|pos| pos.length())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_loop_subdivide() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+        let volume = mesh_graph.signed_volume();
+
+        for _ in 0..2 {
+            let face_count = mesh_graph.faces.len();
+            mesh_graph.loop_subdivide();
+
+            assert_eq!(mesh_graph.faces.len(), face_count * 4);
+            assert!(mesh_graph.verify_twins().is_empty());
+            assert!(mesh_graph.boundary_loops().is_empty());
+        }
+
+        // smoothing shrinks the sphere a bit
+        assert!(mesh_graph.signed_volume() < volume);
+        assert!(mesh_graph.signed_volume() > volume * 0.8);
+    }
+
+    #[test]
+    fn test_loop_subdivide_interpolates() {
+        let mut mesh_graph = MeshGraph::from(Cuboid {
+            half_extents: Vec3::ONE,
+        });
+
+        mesh_graph.uvs = Some(
+            mesh_graph
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, pos.truncate()))
+                .collect(),
+        );
+
+        let weight = mesh_graph.add_vertex_attribute::<f32>("weight");
+        let positions = mesh_graph.positions.clone();
+        let weights = mesh_graph.vertex_attribute_mut(weight).unwrap();
+        for (vertex_id, pos) in positions {
+            weights.insert(vertex_id, pos.z);
+        }
+
+        mesh_graph.loop_subdivide();
+
+        // 8 corners, 12 cube edges and 6 face diagonals
+        assert_eq!(mesh_graph.vertices.len(), 26);
+        let uvs = mesh_graph.uvs.as_ref().unwrap();
+        let weights = mesh_graph.vertex_attribute(weight).unwrap();
+
+        let mut weight_counts = HashMap::<i32, usize>::new();
+        for vertex_id in mesh_graph.vertices.keys() {
+            *weight_counts.entry(weights[vertex_id] as i32).or_default() += 1;
+        }
+        // the top and bottom face each have 4 corners, 4 edge vertices and 1 diagonal vertex
+        assert_eq!(weight_counts, HashMap::from([(-1, 9), (0, 8), (1, 9)]));
+
+        // only the diagonal vertices of the top and bottom face are in the center of the UVs
+        assert_eq!(
+            mesh_graph
+                .vertices
+                .keys()
+                .filter(|vertex_id| uvs[*vertex_id] == Vec2::ZERO)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_loop_subdivide_sharp_cube() {
+        let cube = MeshGraph::from(Cuboid {
+            half_extents: Vec3::ONE,
+        });
+
+        // without creases the corners are rounded off
+        let mut mesh_graph = cube.clone();
+        mesh_graph.loop_subdivide();
+        assert!(max_radius(&mesh_graph) < 3.0f32.sqrt() - 0.1);
+
+        let mut mesh_graph = cube;
+        let cube_edges = mesh_graph
+            .halfedges
+            .iter()
+            .filter(|(_, he)| {
+                let start = mesh_graph.positions[he.start_vertex(&mesh_graph).unwrap()];
+                let end = mesh_graph.positions[he.end_vertex];
+                // the diagonals of the faces change two coordinates
+                (start - end).abs().cmpgt(Vec3::ZERO).bitmask().count_ones() == 1
+            })
+            .map(|(he_id, _)| he_id)
+            .collect_vec();
+        assert_eq!(cube_edges.len(), 24);
+        for he_id in cube_edges {
+            mesh_graph.set_edge_sharp(he_id, true);
+        }
+
+        mesh_graph.loop_subdivide();
+        mesh_graph.loop_subdivide();
+
+        assert_eq!(mesh_graph.faces.len(), 12 * 16);
+        assert_eq!(mesh_graph.sharp_edges.len(), 24 * 4);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        // everything stays on the faces of the cube
+        for pos in mesh_graph.positions.values() {
+            assert!((pos.abs().max_element() - 1.0).abs() < 1e-6);
+        }
+
+        // the corners and the three new vertices on every cube edge stay on the edges
+        let on_edges = mesh_graph
+            .positions
+            .values()
+            .filter(|pos| {
+                pos.abs()
+                    .to_array()
+                    .into_iter()
+                    .filter(|c| (c - 1.0).abs() < 1e-6)
+                    .count()
+                    >= 2
+            })
+            .count();
+        assert_eq!(on_edges, 8 + 12 * 3);
+        assert_eq!(max_radius(&mesh_graph), 3.0f32.sqrt());
+    }
+}
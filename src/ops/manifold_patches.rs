@@ -0,0 +1,182 @@
+use glam::Vec3;
+use hashbrown::HashMap;
+use itertools::Itertools;
+
+use crate::{MeshGraph, VertexId, disjoint_sets::DisjointSets};
+
+impl MeshGraph {
+    /// Cuts the mesh at every non-manifold edge and vertex and returns the maximal manifold
+    /// pieces as independent mesh graphs (each with its own BVH).
+    ///
+    /// Two faces stay connected if they are the only faces at their shared edge and traverse it
+    /// in opposite directions (consistent orientation). Vertices are duplicated for every fan of
+    /// faces around them that isn't connected through such an edge, so bowtie vertices are
+    /// separated as well.
+    ///
+    /// The mesh itself isn't changed. This makes it possible to run algorithms that require a
+    /// manifold (like volume or curvature) on each piece of a non-manifold input.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn split_into_manifold_patches(&self) -> Vec<MeshGraph> {
+        let faces = self
+            .faces
+            .values()
+            .filter_map(|face| face.vertices(self).collect_array::<3>())
+            .collect_vec();
+
+        // all faces at every (undirected) edge together with the direction they traverse it
+        let mut edge_faces = HashMap::<(VertexId, VertexId), Vec<(usize, usize, usize)>>::new();
+        for (face_idx, vertex_ids) in faces.iter().enumerate() {
+            for (i, j) in [(0, 1), (1, 2), (2, 0)] {
+                let (a, b) = (vertex_ids[i], vertex_ids[j]);
+                edge_faces
+                    .entry((a.min(b), a.max(b)))
+                    .or_default()
+                    .push((face_idx, i, j));
+            }
+        }
+
+        let mut face_sets = DisjointSets::new(faces.len());
+        // every face has three corners with the index `face_idx * 3 + i`
+        let mut corner_sets = DisjointSets::new(faces.len() * 3);
+
+        for entries in edge_faces.values() {
+            let &[(face1, i1, j1), (face2, i2, j2)] = entries.as_slice() else {
+                // boundary or non-manifold edge
+                continue;
+            };
+
+            // only consistently oriented faces can be connected
+            if face1 == face2 || faces[face1][i1] != faces[face2][j2] {
+                continue;
+            }
+
+            face_sets.union(face1, face2);
+            corner_sets.union(face1 * 3 + i1, face2 * 3 + j2);
+            corner_sets.union(face1 * 3 + j1, face2 * 3 + i2);
+        }
+
+        let mut patches = Vec::<(Vec<usize>, Vec<Vec3>, HashMap<usize, usize>)>::new();
+        let mut patch_index = HashMap::new();
+
+        for (face_idx, vertex_ids) in faces.iter().enumerate() {
+            let patch = *patch_index
+                .entry(face_sets.find(face_idx))
+                .or_insert_with(|| {
+                    patches.push(Default::default());
+                    patches.len() - 1
+                });
+            let (indices, positions, vertex_index) = &mut patches[patch];
+
+            for (i, vertex_id) in vertex_ids.iter().enumerate() {
+                let index = *vertex_index
+                    .entry(corner_sets.find(face_idx * 3 + i))
+                    .or_insert_with(|| {
+                        positions.push(self.positions.get(*vertex_id).copied().unwrap_or_default());
+                        positions.len() - 1
+                    });
+
+                indices.push(index);
+            }
+        }
+
+        patches
+            .into_iter()
+            .map(|(indices, positions, _)| {
+                let mut mesh_graph = MeshGraph::indexed_triangles(&positions, &indices);
+
+                if self.vertex_normals.is_some() {
+                    mesh_graph.compute_vertex_normals();
+                }
+
+                mesh_graph
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    /// Adds a face without reusing edges that already have two faces
+    fn add_face(mesh_graph: &mut MeshGraph, a: VertexId, b: VertexId, c: VertexId) {
+        let he_a = mesh_graph.add_or_get_boundary_edge(a, b).unwrap();
+        let he_b = mesh_graph.add_or_get_boundary_edge(b, c).unwrap();
+        let he_c = mesh_graph.add_or_get_boundary_edge(c, a).unwrap();
+
+        mesh_graph.add_face(
+            he_a.start_to_end_he_id,
+            he_b.start_to_end_he_id,
+            he_c.start_to_end_he_id,
+        );
+    }
+
+    #[test]
+    fn test_split_book_into_manifold_patches() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let bottom = mesh_graph.add_vertex(Vec3::ZERO);
+        let top = mesh_graph.add_vertex(Vec3::Z);
+
+        // three rectangular pages that share the spine from `bottom` to `top`
+        for i in 0..3 {
+            let (sin, cos) = (i as f32 * std::f32::consts::TAU / 3.0).sin_cos();
+            let dir = Vec3::new(cos, sin, 0.0);
+
+            let page_bottom = mesh_graph.add_vertex(dir);
+            let page_top = mesh_graph.add_vertex(dir + Vec3::Z);
+
+            add_face(&mut mesh_graph, bottom, page_bottom, page_top);
+            add_face(&mut mesh_graph, bottom, page_top, top);
+        }
+
+        assert_eq!(mesh_graph.faces.len(), 6);
+
+        let patches = mesh_graph.split_into_manifold_patches();
+
+        assert_eq!(patches.len(), 3);
+
+        for patch in patches {
+            assert_eq!(patch.faces.len(), 2);
+            assert_eq!(patch.vertices.len(), 4);
+            assert_eq!(patch.boundary_loops().len(), 1);
+            assert!(patch.verify_twins().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_split_bowtie_into_manifold_patches() {
+        let positions = [
+            Vec3::ZERO,
+            Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(-1.0, 1.0, 0.0),
+            Vec3::new(-1.0, -1.0, 0.0),
+        ];
+
+        // two triangles that only share the center vertex
+        let mesh_graph = MeshGraph::indexed_triangles(&positions, &[0, 1, 2, 0, 3, 4]);
+
+        let patches = mesh_graph.split_into_manifold_patches();
+
+        assert_eq!(patches.len(), 2);
+        assert!(patches.iter().all(|patch| patch.vertices.len() == 3));
+    }
+
+    #[test]
+    fn test_split_manifold_mesh_into_manifold_patches() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let patches = mesh_graph.split_into_manifold_patches();
+
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].faces.len(), mesh_graph.faces.len());
+        assert_eq!(patches[0].vertices.len(), mesh_graph.vertices.len());
+        assert!(patches[0].halfedges.values().all(|he| !he.is_boundary()));
+    }
+}
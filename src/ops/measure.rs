@@ -0,0 +1,163 @@
+use glam::Vec3;
+use parry3d::bounding_volume::{Aabb, BoundingSphere};
+
+use crate::MeshGraph;
+
+impl MeshGraph {
+    /// Total area of all faces (see [`crate::Face::area`]).
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn surface_area(&self) -> f32 {
+        self.faces
+            .values()
+            .map(|face| face.area(self))
+            .filter(|area| area.is_finite())
+            .sum()
+    }
+
+    /// Volume enclosed by the mesh regardless of the orientation of the faces.
+    ///
+    /// This is the absolute value of [`MeshGraph::signed_volume`] and only defined for closed
+    /// meshes (see [`MeshGraph::is_closed`]). For open meshes the result is meaningless.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn volume(&self) -> f32 {
+        self.signed_volume().abs()
+    }
+
+    /// Axis aligned bounding box of all vertex positions.
+    ///
+    /// An empty mesh returns a degenerate box at the origin.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn aabb(&self) -> Aabb {
+        if self.positions.is_empty() {
+            return Aabb::new(Vec3::ZERO, Vec3::ZERO);
+        }
+
+        Aabb::from_points(self.positions.values().copied())
+    }
+
+    /// Sphere around the center of [`MeshGraph::aabb`] that contains all vertex positions.
+    ///
+    /// An empty mesh returns a sphere of radius zero at the origin.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        let center = self.aabb().center();
+
+        let radius = self
+            .positions
+            .values()
+            .map(|pos| pos.distance_squared(center))
+            .fold(0.0, f32::max)
+            .sqrt();
+
+        BoundingSphere::new(center, radius)
+    }
+
+    /// Whether the mesh is watertight, i.e. no halfedge is a boundary halfedge.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn is_closed(&self) -> bool {
+        self.halfedges.values().all(|he| !he.is_boundary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use glam::{UVec2, Vec2};
+
+    use crate::primitives::{Cuboid, Grid, IcoSphere, Triangle};
+
+    use super::*;
+
+    #[test]
+    fn test_surface_area() {
+        let radius = 2.0;
+        let sphere_area = 4.0 * PI * radius * radius;
+
+        let mut prev_error = f32::INFINITY;
+
+        for subdivisions in 0..5 {
+            let mesh_graph = MeshGraph::from(IcoSphere {
+                radius,
+                subdivisions,
+            });
+
+            // the inscribed polyhedron is always a bit smaller than the sphere
+            let error = (sphere_area - mesh_graph.surface_area()) / sphere_area;
+            assert!(error > 0.0);
+            assert!(error < prev_error);
+
+            prev_error = error;
+        }
+
+        assert!(prev_error < 0.002);
+    }
+
+    #[test]
+    fn test_face_area() {
+        let mesh_graph = MeshGraph::from(Triangle(Vec3::ZERO, Vec3::X * 2.0, Vec3::Y * 3.0));
+        assert_eq!(mesh_graph.surface_area(), 3.0);
+
+        // degenerate
+        let mesh_graph = MeshGraph::from(Triangle(Vec3::ZERO, Vec3::X, Vec3::X * 2.0));
+        assert_eq!(mesh_graph.surface_area(), 0.0);
+    }
+
+    #[test]
+    fn test_volume() {
+        let radius = 2.0;
+        let sphere_volume = 4.0 / 3.0 * PI * radius * radius * radius;
+
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius,
+            subdivisions: 4,
+        });
+
+        assert!(mesh_graph.is_closed());
+        assert!((mesh_graph.volume() - sphere_volume).abs() / sphere_volume < 0.005);
+
+        let mesh_graph = MeshGraph::from(Cuboid {
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+        });
+
+        assert!(mesh_graph.is_closed());
+        assert!((mesh_graph.volume() - 48.0).abs() < 1e-4);
+
+        let grid = MeshGraph::from(Grid {
+            size: Vec2::ONE,
+            subdivisions: UVec2::splat(4),
+        });
+
+        assert!(!grid.is_closed());
+    }
+
+    #[test]
+    fn test_aabb_and_bounding_sphere() {
+        let radius = 2.0;
+        let offset = Vec3::new(1.0, -2.0, 3.0);
+
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius,
+            subdivisions: 2,
+        });
+
+        let aabb = mesh_graph.aabb();
+        assert!(aabb.center().length() < 1e-5);
+        assert!(aabb.half_extents().distance(Vec3::splat(radius)) < 1e-5);
+
+        let bounding_sphere = mesh_graph.bounding_sphere();
+        assert!(bounding_sphere.center().length() < 1e-5);
+        assert!((bounding_sphere.radius() - radius).abs() < 1e-5);
+
+        for pos in mesh_graph.positions.values_mut() {
+            *pos += offset;
+        }
+
+        assert!(mesh_graph.aabb().center().distance(offset) < 1e-5);
+        assert!(mesh_graph.bounding_sphere().center().distance(offset) < 1e-5);
+
+        let empty = MeshGraph::new();
+        assert_eq!(empty.aabb().half_extents(), Vec3::ZERO);
+        assert_eq!(empty.bounding_sphere().radius(), 0.0);
+    }
+}
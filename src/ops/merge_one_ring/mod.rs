@@ -3,9 +3,9 @@ mod tests;
 
 use std::{f32, ops::RangeInclusive};
 
+use crate::utils::error;
 use hashbrown::HashSet;
 use itertools::Itertools;
-use tracing::{error, instrument};
 
 use crate::{
     AddEdge, FaceId, HalfedgeId, MeshGraph, Vertex, VertexId, error_none,
@@ -31,7 +31,10 @@ impl MeshGraph {
     ///
     /// See [Freestyle: Sculpting meshes with self-adaptive topology DOI 10.1016/j.cag.2011.03.033](https://inria.hal.science/inria-00606516v1/document)
     /// Chapters 3.2 and 5.1
-    #[instrument(skip(self, marked_halfedges, marked_vertices))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, marked_halfedges, marked_vertices))
+    )]
     pub fn merge_vertices_one_rings(
         &mut self,
         vertex_id1: VertexId,
@@ -136,7 +139,7 @@ impl MeshGraph {
             return result;
         }
 
-        tracing::info!("Range pairs to connect: {range_pairs_to_connect:#?}");
+        crate::utils::info!("Range pairs to connect: {range_pairs_to_connect:#?}");
 
         let planned_faces =
             self.plan_new_faces(&range_pairs_to_connect, &one_ring_v_ids1, &one_ring_v_ids2);
@@ -704,7 +707,7 @@ impl MeshGraph {
         }
     }
 
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn flip_and_collapse_single_shared_edge_if_below_threshold(
         &mut self,
         single_shared_he_id: HalfedgeId,
@@ -728,7 +731,7 @@ impl MeshGraph {
         let pos2 = *unwrap_or_return!(self.positions.get(other_v_id2), "Position not found", false);
 
         if pos1.distance_squared(pos2) <= flip_threshold_sqr {
-            tracing::info!("Flipping edge {single_shared_he_id:?}");
+            crate::utils::info!("Flipping edge {single_shared_he_id:?}");
 
             self.flip_edge(single_shared_he_id);
 
@@ -763,7 +766,7 @@ impl MeshGraph {
 
             let pairings = range_pair_to_connect.compute_pairings();
 
-            tracing::info!("Pairings: {:#?}", pairings);
+            crate::utils::info!("Pairings: {:#?}", pairings);
 
             if pairings.is_empty() {
                 continue;
@@ -863,7 +866,7 @@ impl MeshGraph {
         planned_faces
     }
 
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     fn check_and_flip_single_shared_he(
         &mut self,
         shared_he_ids: &HashSet<HalfedgeId>,
@@ -886,7 +889,7 @@ impl MeshGraph {
         false
     }
 
-    #[instrument(skip_all)]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     #[allow(clippy::too_many_arguments)]
     fn compute_range_pairs_to_connect(
         &mut self,
@@ -916,7 +919,7 @@ impl MeshGraph {
             range_pairs_to_connect
         );
 
-        tracing::info!(
+        crate::utils::info!(
             "start idx1: {}, start idx2: {}",
             orig_start_idx1,
             orig_start_idx2
@@ -1133,7 +1136,7 @@ impl MeshGraph {
         range_pairs_to_connect
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     #[allow(clippy::too_many_arguments)]
     fn remember_range_pair_connections(
         &mut self,
@@ -1195,7 +1198,7 @@ impl MeshGraph {
         }
     }
 
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     #[allow(clippy::too_many_arguments)]
     fn find_shared_start_indices_from_ring(
         &self,
@@ -1272,7 +1275,7 @@ impl MeshGraph {
     }
 
     #[allow(clippy::too_many_arguments)]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn find_start_indices(
         &self,
         one_ring_v_ids1: &[VertexId],
@@ -1647,7 +1650,7 @@ impl PlannedFace {
         }
     }
 
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     fn add_to_mesh_graph(
         &self,
         mesh_graph: &mut MeshGraph,
@@ -1694,7 +1697,7 @@ impl PlannedFace {
         ))
     }
 
-    #[instrument(skip(mesh_graph))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     fn add_to_mesh_graph_and_he(
         &self,
         mesh_graph: &mut MeshGraph,
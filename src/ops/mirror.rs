@@ -0,0 +1,99 @@
+use glam::Vec3;
+
+use crate::{MeshGraph, Selection, SelectionOps, point_grid::PointGrid, utils::error};
+
+impl MeshGraph {
+    /// Finds the mirror counterparts of the selected vertices.
+    ///
+    /// Every vertex of `selection` (see [`Selection::resolve_to_vertices`]) is reflected across
+    /// the plane defined by `plane_normal` and `plane_constant`. The vertex closest to the
+    /// reflected position is part of the returned selection if it is within `tolerance`.
+    /// Selected vertices without a counterpart are skipped.
+    ///
+    /// This is the basis for symmetric editing: apply an edit to one side and the mirrored edit
+    /// to the counterparts.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, selection)))]
+    pub fn mirror_selection(
+        &self,
+        selection: &Selection,
+        plane_normal: Vec3,
+        plane_constant: f32,
+        tolerance: f32,
+    ) -> Selection {
+        let mut mirrored = Selection::default();
+
+        let Some(plane_normal) = plane_normal.try_normalize() else {
+            error!("Plane normal is zero");
+            return mirrored;
+        };
+
+        let mut grid = PointGrid::new(tolerance);
+        for (vertex_id, pos) in &self.positions {
+            grid.insert(*pos, vertex_id);
+        }
+
+        for vertex_id in selection.resolve_to_vertices(self) {
+            let Some(pos) = self.positions.get(vertex_id) else {
+                continue;
+            };
+
+            let distance = plane_normal.dot(*pos) - plane_constant;
+            let reflected = pos - plane_normal * (2.0 * distance);
+
+            if let Some((_, counterpart_id)) = grid.closest_within(reflected, tolerance) {
+                mirrored.insert(counterpart_id);
+            }
+        }
+
+        mirrored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashSet;
+
+    use crate::utils::grid_positions_and_indices;
+
+    use super::*;
+
+    #[test]
+    fn test_mirror_selection() {
+        let (width, height) = (5, 3);
+
+        // symmetric to the plane x = 1 apart from a little noise
+        let (mut positions, indices) = grid_positions_and_indices(width, height);
+        for (i, pos) in positions.iter_mut().enumerate() {
+            let noise = if i % 3 == 0 { 1e-3 } else { 0.0 };
+            pos.x += noise - 1.0;
+        }
+
+        let (mesh_graph, vertex_ids) =
+            MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices);
+
+        // left two columns and one vertex on the plane
+        let selection = (0..height)
+            .flat_map(|y| [y * width, y * width + 1])
+            .chain([2])
+            .map(|i| vertex_ids[i])
+            .collect::<Selection>();
+
+        let mirrored = mesh_graph.mirror_selection(&selection, Vec3::X * 2.0, 1.0, 0.01);
+
+        let expected = (0..height)
+            .flat_map(|y| [y * width + 4, y * width + 3])
+            .chain([2])
+            .map(|i| vertex_ids[i])
+            .collect::<HashSet<_>>();
+
+        assert_eq!(mirrored.vertices, expected);
+
+        // nothing is found without tolerance for the noise
+        let mirrored = mesh_graph.mirror_selection(&selection, Vec3::X, 1.0, 0.0);
+        assert!(mirrored.vertices.len() < expected.len());
+
+        // no counterparts on the other side of a plane outside of the mesh
+        let mirrored = mesh_graph.mirror_selection(&selection, Vec3::X, -5.0, 0.01);
+        assert!(mirrored.vertices.is_empty());
+    }
+}
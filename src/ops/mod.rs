@@ -1,16 +1,63 @@
 mod add;
+mod append;
+mod boundary;
+mod brush;
+mod cap;
+mod catmull_clark;
+mod centroids;
+mod changes;
 mod cleanup;
 mod collapse;
+mod compact;
+mod components;
+mod contract;
+mod corners;
+mod cut;
+mod dual;
 mod edit;
+mod extract;
+mod hausdorff;
+mod laplacian;
+mod loop_limit;
+mod loop_subdivide;
+mod manifold_patches;
+mod measure;
 mod merge_one_ring;
+mod mirror;
+mod normals;
+mod occlusion;
+mod polygons;
+mod principal_axes;
+mod progressive;
+mod quads;
 mod query;
 mod remove;
+mod sharp_edges;
+mod silhouette;
+mod simplify;
+mod stitch;
 mod subdivide;
+mod t_vertices;
 mod transform;
+mod uniformity;
+mod verify;
 
 pub use add::*;
+pub use boundary::*;
+pub use brush::*;
+pub use changes::*;
+pub use compact::*;
+pub use corners::*;
 use hashbrown::HashMap;
+pub use hausdorff::*;
+pub use laplacian::*;
 pub use merge_one_ring::*;
+pub use normals::*;
+pub use principal_axes::*;
+pub use progressive::*;
+pub use quads::*;
+pub use simplify::*;
+pub use stitch::*;
 
 use crate::{HalfedgeId, MeshGraph, utils::unwrap_or_return};
 
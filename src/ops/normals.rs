@@ -0,0 +1,154 @@
+use glam::Vec3;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
+
+use crate::{MeshGraph, VertexId};
+
+/// How the normals of the faces around a vertex are weighted in
+/// [`MeshGraph::compute_vertex_normals_weighted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalWeighting {
+    /// Every face contributes equally
+    Uniform,
+    /// Faces contribute proportionally to their area
+    #[default]
+    Area,
+    /// Faces contribute proportionally to their interior angle at the vertex.
+    /// This is independent of how the faces around the vertex are triangulated and gives
+    /// better results for irregular triangle sizes.
+    Angle,
+}
+
+impl MeshGraph {
+    /// Computes the vertex normals by averaging the normals of the adjacent faces weighted
+    /// according to `weighting` and stores them in [`MeshGraph::vertex_normals`].
+    ///
+    /// [`MeshGraph::compute_vertex_normals`] is the same as using [`NormalWeighting::Area`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn compute_vertex_normals_weighted(&mut self, weighting: NormalWeighting) {
+        self.vertex_normals = Some(self.weighted_vertex_normals(weighting));
+    }
+
+    /// Same as [`MeshGraph::compute_vertex_normals_weighted`] but returns the normalized
+    /// normals instead of storing them.
+    pub(crate) fn weighted_vertex_normals(
+        &self,
+        weighting: NormalWeighting,
+    ) -> SecondaryMap<VertexId, Vec3> {
+        let mut normals = SecondaryMap::with_capacity(self.vertices.len());
+
+        for face in self.faces.values() {
+            let Some(corners) = face.vertices(self).collect_array::<3>() else {
+                continue;
+            };
+            let Some(positions) = corners
+                .iter()
+                .map(|vertex_id| self.positions.get(*vertex_id).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            // the length is twice the area
+            let area_normal = (positions[1] - positions[0]).cross(positions[2] - positions[0]);
+            let unit_normal = area_normal.normalize_or_zero();
+
+            for (i, vertex_id) in corners.into_iter().enumerate() {
+                let weighted_normal = match weighting {
+                    NormalWeighting::Uniform => unit_normal,
+                    NormalWeighting::Area => area_normal,
+                    NormalWeighting::Angle => {
+                        let pos = positions[i];
+                        let to_next = positions[(i + 1) % 3] - pos;
+                        let to_prev = positions[(i + 2) % 3] - pos;
+
+                        unit_normal * to_next.angle_between(to_prev)
+                    }
+                };
+
+                *normals.entry(vertex_id).unwrap().or_insert(Vec3::ZERO) += weighted_normal;
+            }
+        }
+
+        for normal in normals.values_mut() {
+            *normal = normal.normalize_or_zero();
+        }
+
+        normals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec2, Vec2};
+
+    use crate::primitives::{Grid, IcoSphere};
+
+    use super::*;
+
+    #[test]
+    fn test_compute_vertex_normals_weighted() {
+        // single quad stretched along x and bent along its diagonal
+        let mut mesh_graph = MeshGraph::from(Grid {
+            size: Vec2::new(10.0, 1.0),
+            subdivisions: UVec2::ONE,
+        });
+
+        let corner_id = mesh_graph
+            .positions
+            .iter()
+            .find(|(_, pos)| pos.x < 0.0 && pos.z < 0.0)
+            .unwrap()
+            .0;
+        let lifted_id = mesh_graph
+            .positions
+            .iter()
+            .find(|(_, pos)| pos.x > 0.0 && pos.z < 0.0)
+            .unwrap()
+            .0;
+        mesh_graph.positions[lifted_id].y = 1.0;
+
+        // both triangles of the quad meet at the corner
+        assert_eq!(mesh_graph.vertices[corner_id].faces(&mesh_graph).count(), 2);
+
+        mesh_graph.compute_vertex_normals_weighted(NormalWeighting::Area);
+        let area_normal = mesh_graph.vertex_normals.as_ref().unwrap()[corner_id];
+
+        mesh_graph.compute_vertex_normals_weighted(NormalWeighting::Angle);
+        let angle_normal = mesh_graph.vertex_normals.as_ref().unwrap()[corner_id];
+
+        mesh_graph.compute_vertex_normals_weighted(NormalWeighting::Uniform);
+        let uniform_normal = mesh_graph.vertex_normals.as_ref().unwrap()[corner_id];
+
+        for normal in [area_normal, angle_normal, uniform_normal] {
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(normal.y > 0.0);
+        }
+
+        // the wide angle of the flat triangle at the corner dominates
+        assert!(angle_normal.dot(Vec3::Y) > area_normal.dot(Vec3::Y) + 0.1);
+        assert!(angle_normal.dot(Vec3::Y) > uniform_normal.dot(Vec3::Y));
+    }
+
+    #[test]
+    fn test_vertex_normals_of_sphere() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        for weighting in [
+            NormalWeighting::Uniform,
+            NormalWeighting::Area,
+            NormalWeighting::Angle,
+        ] {
+            mesh_graph.compute_vertex_normals_weighted(weighting);
+            let normals = mesh_graph.vertex_normals.as_ref().unwrap();
+
+            assert_eq!(normals.len(), mesh_graph.vertices.len());
+            for (vertex_id, normal) in normals {
+                assert!(normal.dot(mesh_graph.positions[vertex_id]) > 0.99);
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::f32::consts::PI;
+
+use glam::Vec3;
+use parry3d::query::{Ray, RayCast};
+use slotmap::SecondaryMap;
+
+use crate::{FaceId, MeshGraph};
+
+/// Distance along the face normal by which the ray origins are lifted off the surface.
+const RAY_ORIGIN_OFFSET: f32 = 1e-4;
+
+impl MeshGraph {
+    /// Computes the ambient occlusion of every face.
+    ///
+    /// From the center of each face `samples` rays are cast into the hemisphere around the
+    /// face normal. The returned value is the fraction of rays that don't hit the mesh, i.e.
+    /// `1.0` means the face is completely open and `0.0` that it is completely occluded.
+    ///
+    /// The ray directions are distributed evenly over the hemisphere so the result is
+    /// deterministic. Faces without a valid normal are skipped. `samples` is clamped to at
+    /// least one.
+    ///
+    /// Make sure the BVH is up to date (see `refit_bvh()`) before calling this.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn bake_face_occlusion(&self, samples: usize) -> SecondaryMap<FaceId, f32> {
+        let directions = hemisphere_directions(samples.max(1));
+
+        let mut occlusion = SecondaryMap::with_capacity(self.faces.len());
+
+        for (face_id, face) in &self.faces {
+            let Some(normal) = face.compute_normal(self) else {
+                continue;
+            };
+            let (tangent, bitangent) = normal.any_orthonormal_pair();
+
+            let origin = face.center(self) + normal * RAY_ORIGIN_OFFSET;
+
+            let open_count = directions
+                .iter()
+                .filter(|dir| {
+                    let dir = tangent * dir.x + bitangent * dir.y + normal * dir.z;
+                    self.cast_local_ray(&Ray::new(origin, dir), f32::MAX, true)
+                        .is_none()
+                })
+                .count();
+
+            occlusion.insert(face_id, open_count as f32 / directions.len() as f32);
+        }
+
+        occlusion
+    }
+}
+
+/// Returns `count` unit vectors distributed evenly over the hemisphere around +Z
+/// by following a Fibonacci spiral.
+fn hemisphere_directions(count: usize) -> Vec<Vec3> {
+    let golden_angle = PI * (3.0 - 5.0_f32.sqrt());
+
+    (0..count)
+        .map(|i| {
+            let z = 1.0 - (i as f32 + 0.5) / count as f32;
+            let radius = (1.0 - z * z).sqrt();
+            let (sin, cos) = (i as f32 * golden_angle).sin_cos();
+
+            Vec3::new(cos * radius, sin * radius, z)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use crate::utils::grid_positions_and_indices;
+
+    use super::*;
+
+    #[test]
+    fn test_bake_face_occlusion() {
+        let size = 13;
+
+        let pit = Vec2::new(3.0, 6.0);
+        let bump = Vec2::new(9.0, 6.0);
+
+        let (mut positions, indices) = grid_positions_and_indices(size, size);
+        for pos in &mut positions {
+            let xy = pos.truncate();
+            pos.z = (-bump.distance_squared(xy) / 16.0).exp()
+                - 3.0 * (-pit.distance_squared(xy) / 4.0).exp();
+        }
+
+        let mesh_graph = MeshGraph::indexed_triangles(&positions, &indices);
+
+        let occlusion = mesh_graph.bake_face_occlusion(32);
+
+        assert_eq!(occlusion.len(), mesh_graph.faces.len());
+        assert!(occlusion.values().all(|open| (0.0..=1.0).contains(open)));
+
+        let open_fraction_near = |xy: Vec2| {
+            let (face_id, _) = mesh_graph
+                .faces
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    let a = a.center(&mesh_graph).truncate().distance_squared(xy);
+                    let b = b.center(&mesh_graph).truncate().distance_squared(xy);
+                    a.total_cmp(&b)
+                })
+                .unwrap();
+
+            occlusion[face_id]
+        };
+
+        let pit_open = open_fraction_near(pit);
+        let bump_open = open_fraction_near(bump);
+
+        assert!(pit_open < 0.8);
+        assert!(bump_open > 0.95);
+        assert!(pit_open < bump_open);
+    }
+}
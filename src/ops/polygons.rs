@@ -0,0 +1,193 @@
+use crate::utils::error;
+use glam::Vec3;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+
+use crate::{
+    FaceId, MeshGraph, VertexId, plane_slice::project_onto_best_fit_plane, triangulate_polygon,
+};
+
+impl MeshGraph {
+    /// Create a triangle mesh graph from polygons with an arbitrary number of vertices.
+    ///
+    /// Every polygon is a list of indices into `vertex_positions` in counter-clockwise order.
+    /// Polygons with more than three vertices are triangulated. The index of the polygon every
+    /// face originates from is stored in [`MeshGraph::polygon_ids`] so the original structure can
+    /// be restored with [`MeshGraph::merge_back_to_polygons`].
+    #[inline]
+    pub fn from_polygons(vertex_positions: &[Vec3], polygons: &[Vec<usize>]) -> Self {
+        Self::from_polygons_and_vertex_ids(vertex_positions, polygons).0
+    }
+
+    /// Same as [`MeshGraph::from_polygons`] but also returns the vertex IDs in the same order as
+    /// `vertex_positions`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn from_polygons_and_vertex_ids(
+        vertex_positions: &[Vec3],
+        polygons: &[Vec<usize>],
+    ) -> (Self, Vec<VertexId>) {
+        let mut face_indices = Vec::with_capacity(polygons.len() * 3);
+        let mut triangle_polygon_ids = Vec::with_capacity(polygons.len());
+
+        for (polygon_id, polygon) in polygons.iter().enumerate() {
+            if polygon.len() < 3 {
+                error!("Polygon {polygon_id} has less than three vertices");
+                continue;
+            }
+
+            if polygon.len() == 3 {
+                face_indices.extend(polygon);
+                triangle_polygon_ids.push(polygon_id);
+                continue;
+            }
+
+            let points = polygon.iter().map(|i| vertex_positions[*i]).collect_vec();
+
+            let Some(polygon_2d) = project_onto_best_fit_plane(&points) else {
+                error!("Polygon {polygon_id} is degenerate");
+                continue;
+            };
+
+            for triangle in triangulate_polygon(&polygon_2d) {
+                face_indices.extend(triangle.map(|i| polygon[i]));
+                triangle_polygon_ids.push(polygon_id);
+            }
+        }
+
+        let (mut mesh_graph, vertex_ids) =
+            Self::indexed_triangles_and_vertex_ids(vertex_positions, &face_indices);
+
+        for (triangle, polygon_id) in face_indices.chunks_exact(3).zip(triangle_polygon_ids) {
+            if let Some(face_id) = mesh_graph.face_with_vertices(
+                vertex_ids[triangle[0]],
+                vertex_ids[triangle[1]],
+                vertex_ids[triangle[2]],
+            ) {
+                mesh_graph.polygon_ids.insert(face_id, polygon_id);
+            }
+        }
+
+        (mesh_graph, vertex_ids)
+    }
+
+    /// Returns the index of the source polygon the face was triangulated from.
+    /// `None` if the mesh graph wasn't created by [`MeshGraph::from_polygons`] or the face
+    /// was created later.
+    #[inline]
+    pub fn faces_from_polygon(&self, face_id: FaceId) -> Option<usize> {
+        self.polygon_ids.get(face_id).copied()
+    }
+
+    /// Groups the faces that originate from the same source polygon back into polygons.
+    ///
+    /// Every returned polygon is the list of its boundary vertices in counter-clockwise order.
+    /// Faces without a polygon id are returned as triangles. Useful for exporting to formats
+    /// that support quads or n-gons.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn merge_back_to_polygons(&self) -> Vec<Vec<VertexId>> {
+        let mut groups = HashMap::<usize, Vec<FaceId>>::new();
+        let mut polygons = vec![];
+
+        for face_id in self.faces.keys() {
+            if let Some(polygon_id) = self.faces_from_polygon(face_id) {
+                groups.entry(polygon_id).or_default().push(face_id);
+            } else {
+                polygons.push(self.faces[face_id].vertices(self).collect_vec());
+            }
+        }
+
+        for (_, face_ids) in groups.into_iter().sorted_unstable_by_key(|(id, _)| *id) {
+            let face_set = face_ids.iter().copied().collect::<HashSet<_>>();
+
+            // the outline consists of the halfedges whose twin isn't part of the group
+            let mut outline = HashMap::<VertexId, VertexId>::new();
+            for face_id in &face_ids {
+                for he_id in self.faces[*face_id].halfedges(self) {
+                    let he = self.halfedges[he_id];
+                    let twin_face = he
+                        .twin
+                        .and_then(|twin_id| self.halfedges.get(twin_id))
+                        .and_then(|twin| twin.face);
+
+                    if twin_face.is_some_and(|twin_face| face_set.contains(&twin_face)) {
+                        continue;
+                    }
+
+                    if let Some(start_vertex) = he.start_vertex(self) {
+                        outline.insert(start_vertex, he.end_vertex);
+                    }
+                }
+            }
+
+            let Some(&start_vertex) = outline.keys().min() else {
+                continue;
+            };
+
+            let mut polygon = vec![start_vertex];
+            let mut vertex = start_vertex;
+            while let Some(&next_vertex) = outline.get(&vertex) {
+                if next_vertex == start_vertex || polygon.len() > outline.len() {
+                    break;
+                }
+                polygon.push(next_vertex);
+                vertex = next_vertex;
+            }
+
+            if polygon.len() != outline.len() {
+                error!("Faces of a polygon don't form a single outline. Returning its triangles");
+                polygons.extend(
+                    face_ids
+                        .iter()
+                        .map(|face_id| self.faces[*face_id].vertices(self).collect_vec()),
+                );
+                continue;
+            }
+
+            polygons.push(polygon);
+        }
+
+        polygons
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quad_merged_back() {
+        let positions = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+        ];
+
+        let mesh_graph = MeshGraph::from_polygons(&positions, &[vec![0, 1, 2, 3], vec![1, 4, 2]]);
+
+        assert_eq!(mesh_graph.faces.len(), 3);
+        assert_eq!(
+            mesh_graph
+                .faces
+                .keys()
+                .filter(|face_id| mesh_graph.faces_from_polygon(*face_id) == Some(0))
+                .count(),
+            2
+        );
+
+        let polygons = mesh_graph.merge_back_to_polygons();
+
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].len(), 4);
+        assert_eq!(polygons[1].len(), 3);
+
+        let quad_positions = polygons[0]
+            .iter()
+            .map(|v| mesh_graph.positions[*v])
+            .collect_vec();
+        for pos in &positions[0..4] {
+            assert!(quad_positions.contains(pos));
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use glam::{Mat3, Vec3};
+use itertools::Itertools;
+
+use crate::MeshGraph;
+
+/// Maximum number of sweeps of the Jacobi eigenvalue algorithm
+const MAX_JACOBI_SWEEPS: usize = 32;
+
+/// Oriented bounding box. Return value of [`MeshGraph::oriented_bounding_box`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obb {
+    /// Center of the box
+    pub center: Vec3,
+    /// The normalized, orthogonal axes of the box. They form a right-handed coordinate system.
+    pub axes: [Vec3; 3],
+    /// Half of the size of the box along each of the `axes`
+    pub half_extents: Vec3,
+}
+
+impl Default for Obb {
+    fn default() -> Self {
+        Self {
+            center: Vec3::ZERO,
+            axes: [Vec3::X, Vec3::Y, Vec3::Z],
+            half_extents: Vec3::ZERO,
+        }
+    }
+}
+
+impl Obb {
+    /// Returns the eight corners of the box.
+    pub fn corners(&self) -> [Vec3; 8] {
+        std::array::from_fn(|i| {
+            let signs = Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            ) * self.half_extents;
+
+            self.center + self.axes[0] * signs.x + self.axes[1] * signs.y + self.axes[2] * signs.z
+        })
+    }
+}
+
+impl MeshGraph {
+    /// Computes the centroid and the principal axes of the surface by principal component
+    /// analysis of the area weighted face centers.
+    ///
+    /// Returns `(centroid, first_axis, second_axis, third_axis)` where the axes are normalized,
+    /// orthogonal to each other and sorted by decreasing variance. This can be used to align a
+    /// model with its natural axes.
+    ///
+    /// For an empty mesh this returns the origin and the coordinate axes.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn principal_axes(&self) -> (Vec3, Vec3, Vec3, Vec3) {
+        let weighted_centers = self
+            .faces
+            .values()
+            .map(|face| (face.center(self), face.area(self)))
+            .collect_vec();
+
+        let total_area = weighted_centers.iter().map(|(_, area)| area).sum::<f32>();
+        if total_area <= 0.0 {
+            return (Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z);
+        }
+
+        let centroid = weighted_centers
+            .iter()
+            .map(|(center, area)| center * *area)
+            .sum::<Vec3>()
+            / total_area;
+
+        let covariance = weighted_centers
+            .iter()
+            .fold(Mat3::ZERO, |acc, (center, area)| {
+                let d = *center - centroid;
+                acc + Mat3::from_cols(d * d.x, d * d.y, d * d.z) * *area
+            })
+            * (1.0 / total_area);
+
+        let (eigenvalues, eigenvectors) = symmetric_eigen(covariance);
+
+        let [first, second, third] = [0, 1, 2]
+            .into_iter()
+            .sorted_by(|a, b| eigenvalues[*b].total_cmp(&eigenvalues[*a]))
+            .map(|i| eigenvectors.col(i).normalize_or_zero())
+            .collect_array()
+            .unwrap(); // always three elements
+
+        (centroid, first, second, third)
+    }
+
+    /// Computes the bounding box that is aligned with the [`MeshGraph::principal_axes`].
+    ///
+    /// All vertices are projected onto the principal axes to find the extents of the box. For
+    /// elongated or rotated models this is much tighter than the axis aligned bounding box,
+    /// which makes it useful for packing and as a collision proxy.
+    ///
+    /// For a mesh without vertices the default (empty) box at the origin is returned.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn oriented_bounding_box(&self) -> Obb {
+        if self.positions.is_empty() {
+            return Obb::default();
+        }
+
+        let (_, first, second, _) = self.principal_axes();
+        let rotation = Mat3::from_cols(first, second, first.cross(second));
+        let inverse_rotation = rotation.transpose();
+
+        let (min, max) = self.positions.values().fold(
+            (Vec3::INFINITY, Vec3::NEG_INFINITY),
+            |(min, max), pos| {
+                let local = inverse_rotation * *pos;
+                (min.min(local), max.max(local))
+            },
+        );
+
+        Obb {
+            center: rotation * ((min + max) * 0.5),
+            axes: [rotation.x_axis, rotation.y_axis, rotation.z_axis],
+            half_extents: (max - min) * 0.5,
+        }
+    }
+}
+
+/// Eigen decomposition of a symmetric matrix with the cyclic Jacobi method.
+///
+/// Returns the eigenvalues and a matrix with the corresponding eigenvectors as columns.
+fn symmetric_eigen(matrix: Mat3) -> ([f32; 3], Mat3) {
+    // row major, but `matrix` is symmetric anyway
+    let mut a = matrix.to_cols_array_2d();
+    let mut v = Mat3::IDENTITY.to_cols_array_2d();
+
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let off_diagonal = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        if off_diagonal < 1e-12 {
+            break;
+        }
+
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() < f32::EPSILON {
+                continue;
+            }
+
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            // a = Jᵀ a J
+            for row in &mut a {
+                let (a_p, a_q) = (row[p], row[q]);
+                row[p] = c * a_p - s * a_q;
+                row[q] = s * a_p + c * a_q;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            for (k, (a_p, a_q)) in row_p.into_iter().zip(row_q).enumerate() {
+                a[p][k] = c * a_p - s * a_q;
+                a[q][k] = s * a_p + c * a_q;
+            }
+
+            // v = v J
+            for row in &mut v {
+                let (v_p, v_q) = (row[p], row[q]);
+                row[p] = c * v_p - s * v_q;
+                row[q] = s * v_p + c * v_q;
+            }
+        }
+    }
+
+    // the columns of `v` are the eigenvectors
+    let eigenvectors = Mat3::from_cols_array_2d(&v).transpose();
+
+    ([a[0][0], a[1][1], a[2][2]], eigenvectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Quat;
+
+    use crate::utils::fan_cuboid;
+
+    use super::*;
+
+    /// A cuboid of `size` that is rotated by `rotation` and then moved by `offset`
+    fn cuboid(size: Vec3, rotation: Quat, offset: Vec3) -> MeshGraph {
+        let mut mesh_graph = fan_cuboid(size);
+        assert!(mesh_graph.signed_volume() > 0.0);
+
+        mesh_graph.apply_quat(rotation);
+        for pos in mesh_graph.positions.values_mut() {
+            *pos += offset;
+        }
+
+        mesh_graph
+    }
+
+    #[test]
+    fn test_principal_axes() {
+        let rotation = Quat::from_euler(glam::EulerRot::XYZ, 0.3, -0.7, 1.1);
+        let offset = Vec3::new(1.0, 2.0, 3.0);
+        let mesh_graph = cuboid(Vec3::new(4.0, 1.0, 0.5), rotation, offset);
+
+        let (centroid, first, second, third) = mesh_graph.principal_axes();
+
+        assert!(centroid.distance(offset) < 1e-4);
+        assert!(first.dot(rotation * Vec3::X).abs() > 0.999);
+        assert!(second.dot(rotation * Vec3::Y).abs() > 0.999);
+        assert!(third.dot(rotation * Vec3::Z).abs() > 0.999);
+    }
+
+    #[test]
+    fn test_oriented_bounding_box() {
+        let size = Vec3::new(4.0, 1.0, 0.5);
+        let rotation = Quat::from_euler(glam::EulerRot::XYZ, 0.3, -0.7, 1.1);
+        let offset = Vec3::new(1.0, 2.0, 3.0);
+        let mesh_graph = cuboid(size, rotation, offset);
+
+        let obb = mesh_graph.oriented_bounding_box();
+
+        assert!(obb.center.distance(offset) < 1e-4);
+        assert!(obb.half_extents.distance(size * 0.5) < 1e-4);
+        assert!(obb.axes[0].dot(rotation * Vec3::X).abs() > 0.999);
+        assert!(obb.axes[2].dot(obb.axes[0].cross(obb.axes[1])) > 0.999);
+
+        for corner in obb.corners() {
+            let local = rotation.inverse() * (corner - offset);
+            assert!((local.abs() - size * 0.5).abs().max_element() < 1e-4);
+        }
+
+        let (aabb_min, aabb_max) = mesh_graph
+            .positions
+            .values()
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), pos| {
+                (min.min(*pos), max.max(*pos))
+            });
+        let aabb_volume = (aabb_max - aabb_min).element_product();
+        let obb_volume = (obb.half_extents * 2.0).element_product();
+
+        assert!((obb_volume - size.element_product()).abs() < 1e-3);
+        assert!(obb_volume < aabb_volume * 0.5);
+    }
+}
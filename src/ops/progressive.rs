@@ -0,0 +1,264 @@
+use glam::Vec3;
+use hashbrown::{HashMap, HashSet};
+use itertools::Itertools;
+
+use crate::{MeshGraph, VertexId};
+
+/// Reverses a single edge collapse of [`MeshGraph::build_progressive`].
+///
+/// Faces are given by their vertices in counter-clockwise order starting with the smallest
+/// vertex id.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexSplitRecord {
+    /// Positions of the vertices before the collapse. These are the vertices that are restored
+    /// by the split as well as the ones that have been moved by the collapse.
+    pub positions: Vec<(VertexId, Vec3)>,
+    /// Faces that have been created by the collapse and are removed by the split
+    pub removed_faces: Vec<[VertexId; 3]>,
+    /// Faces that have been removed by the collapse and are restored by the split
+    pub added_faces: Vec<[VertexId; 3]>,
+}
+
+/// Return value of [`MeshGraph::build_progressive`]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgressiveMesh {
+    /// The coarsest level of detail
+    pub base: MeshGraph,
+    /// The vertex splits that refine `base` step by step back into the original mesh
+    pub splits: Vec<VertexSplitRecord>,
+}
+
+/// The faces around an edge before it is collapsed. Used to compute the [`VertexSplitRecord`].
+pub(crate) struct CollapseSnapshot {
+    vertices: Vec<VertexId>,
+    positions: Vec<(VertexId, Vec3)>,
+    faces: HashSet<[VertexId; 3]>,
+}
+
+impl MeshGraph {
+    /// Decimates a copy of the mesh like [`MeshGraph::simplify_to_face_count`] and records
+    /// every collapse as a vertex split that reverses it.
+    ///
+    /// The result can be streamed: send the base mesh first and then the splits which
+    /// reconstruct any level of detail between `target_faces` and the original mesh with
+    /// [`ProgressiveMesh::to_lod`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn build_progressive(&self, target_faces: usize) -> ProgressiveMesh {
+        let mut base = self.clone();
+        let mut splits = vec![];
+
+        base.simplify_qem(
+            |mesh_graph, _| mesh_graph.faces.len() <= target_faces,
+            Some(&mut splits),
+        );
+        base.refit_bvh();
+
+        // the last collapse has to be reversed first
+        splits.reverse();
+
+        ProgressiveMesh { base, splits }
+    }
+
+    /// Records the faces around the edge between `start_v_id` and `end_v_id` before it is
+    /// collapsed.
+    pub(crate) fn collapse_snapshot(
+        &self,
+        start_v_id: VertexId,
+        end_v_id: VertexId,
+    ) -> CollapseSnapshot {
+        // the collapse and the following cleanup only touch faces around the one-rings
+        let vertices = [start_v_id, end_v_id]
+            .into_iter()
+            .filter_map(|vertex_id| self.vertices.get(vertex_id))
+            .flat_map(|vertex| vertex.neighbours(self))
+            .chain([start_v_id, end_v_id])
+            .unique()
+            .collect_vec();
+
+        CollapseSnapshot {
+            positions: vertices
+                .iter()
+                .filter_map(|vertex_id| Some((*vertex_id, *self.positions.get(*vertex_id)?)))
+                .collect(),
+            faces: self.face_triples_around(&vertices),
+            vertices,
+        }
+    }
+
+    /// Computes the vertex split that reverses the changes since `snapshot` was taken.
+    pub(crate) fn vertex_split_record(
+        &self,
+        snapshot: CollapseSnapshot,
+        added_vertices: &[VertexId],
+    ) -> VertexSplitRecord {
+        let vertices = snapshot
+            .vertices
+            .iter()
+            .chain(added_vertices)
+            .copied()
+            .filter(|vertex_id| self.vertices.contains_key(*vertex_id))
+            .collect_vec();
+
+        let faces = self.face_triples_around(&vertices);
+
+        VertexSplitRecord {
+            positions: snapshot
+                .positions
+                .into_iter()
+                .filter(|(vertex_id, pos)| self.positions.get(*vertex_id) != Some(pos))
+                .collect(),
+            removed_faces: faces
+                .difference(&snapshot.faces)
+                .copied()
+                .sorted()
+                .collect(),
+            added_faces: snapshot
+                .faces
+                .difference(&faces)
+                .copied()
+                .sorted()
+                .collect(),
+        }
+    }
+
+    fn face_triples_around(&self, vertex_ids: &[VertexId]) -> HashSet<[VertexId; 3]> {
+        vertex_ids
+            .iter()
+            .filter_map(|vertex_id| self.vertices.get(*vertex_id))
+            .flat_map(|vertex| vertex.faces(self))
+            .filter_map(|face_id| {
+                let vertex_ids = self.faces.get(face_id)?.vertices(self).collect_array()?;
+                Some(canonical_face(vertex_ids))
+            })
+            .collect()
+    }
+}
+
+impl ProgressiveMesh {
+    /// Reconstructs the level of detail with at least `faces` faces by replaying the vertex
+    /// splits on the base mesh. If `faces` is more than the original face count, the original
+    /// mesh is returned.
+    ///
+    /// The vertex ids of the returned mesh differ from the ones in the records.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn to_lod(&self, faces: usize) -> MeshGraph {
+        let mut positions = self
+            .base
+            .positions
+            .iter()
+            .map(|(vertex_id, pos)| (vertex_id, *pos))
+            .collect::<HashMap<_, _>>();
+
+        let mut face_triples = self
+            .base
+            .faces
+            .values()
+            .filter_map(|face| Some(canonical_face(face.vertices(&self.base).collect_array()?)))
+            .collect::<HashSet<_>>();
+
+        for split in &self.splits {
+            if face_triples.len() >= faces {
+                break;
+            }
+
+            for face in &split.removed_faces {
+                face_triples.remove(face);
+            }
+            face_triples.extend(split.added_faces.iter().copied());
+            positions.extend(split.positions.iter().copied());
+        }
+
+        let vertex_ids = face_triples
+            .iter()
+            .flatten()
+            .copied()
+            .unique()
+            .sorted()
+            .collect_vec();
+        let index_map = vertex_ids
+            .iter()
+            .enumerate()
+            .map(|(index, vertex_id)| (*vertex_id, index))
+            .collect::<HashMap<_, _>>();
+
+        let vertex_positions = vertex_ids
+            .iter()
+            .map(|vertex_id| positions.get(vertex_id).copied().unwrap_or_default())
+            .collect_vec();
+        let face_indices = face_triples
+            .into_iter()
+            .sorted()
+            .flatten()
+            .map(|vertex_id| index_map[&vertex_id])
+            .collect_vec();
+
+        let mut mesh_graph = MeshGraph::indexed_triangles(&vertex_positions, &face_indices);
+
+        if self.base.vertex_normals.is_some() {
+            mesh_graph.compute_vertex_normals();
+        }
+
+        mesh_graph
+    }
+}
+
+/// Rotates the face vertices such that the smallest vertex id is first while keeping the winding.
+fn canonical_face(vertex_ids: [VertexId; 3]) -> [VertexId; 3] {
+    let [a, b, c] = vertex_ids;
+
+    if a < b && a < c {
+        [a, b, c]
+    } else if b < c {
+        [b, c, a]
+    } else {
+        [c, a, b]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    fn sorted_positions(mesh_graph: &MeshGraph) -> Vec<Vec3> {
+        mesh_graph
+            .positions
+            .values()
+            .copied()
+            .sorted_by(|a, b| {
+                a.x.total_cmp(&b.x)
+                    .then(a.y.total_cmp(&b.y))
+                    .then(a.z.total_cmp(&b.z))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_progressive() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let progressive = mesh_graph.build_progressive(100);
+
+        assert!(progressive.base.faces.len() <= 100);
+        assert!(!progressive.splits.is_empty());
+
+        let coarsest = progressive.to_lod(0);
+        assert_eq!(coarsest.faces.len(), progressive.base.faces.len());
+
+        let intermediate = progressive.to_lod(200);
+        assert!(intermediate.faces.len() >= 200);
+        assert!(intermediate.faces.len() < mesh_graph.faces.len());
+
+        let original = progressive.to_lod(usize::MAX);
+        assert_eq!(original.faces.len(), mesh_graph.faces.len());
+        assert_eq!(original.vertices.len(), mesh_graph.vertices.len());
+        assert_eq!(sorted_positions(&original), sorted_positions(&mesh_graph));
+        assert!(original.halfedges.values().all(|he| !he.is_boundary()));
+    }
+}
@@ -0,0 +1,193 @@
+use std::f32::consts::FRAC_PI_2;
+
+use glam::Vec3;
+use hashbrown::HashSet;
+use itertools::Itertools;
+
+use crate::{FaceId, HalfedgeId, MeshGraph, VertexId};
+
+/// Return value of [`MeshGraph::quad_pairs`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuadPairs {
+    /// The vertex loops of the quads in counter-clockwise order together with the diagonal
+    /// halfedge that is shared by the two triangles of the quad
+    pub quads: Vec<([VertexId; 4], HalfedgeId)>,
+    /// Triangles that couldn't be paired with a neighbour
+    pub unpaired_faces: Vec<FaceId>,
+}
+
+impl MeshGraph {
+    /// Greedily pairs adjacent triangles into quads.
+    ///
+    /// Every interior edge is a candidate diagonal. Candidates are rated by how planar the
+    /// resulting quad is and how close its corner angles are to right angles. Starting with
+    /// the best one, each candidate is accepted if none of its two triangles is already part
+    /// of a quad. Diagonals that would result in a non-convex quad are never accepted.
+    ///
+    /// This is the basis for tri-to-quad conversion, e.g. for quad based exporters.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn quad_pairs(&self) -> QuadPairs {
+        let mut candidates = self
+            .halfedges
+            .iter()
+            .filter_map(|(he_id, he)| {
+                // visit every edge only once
+                if he.twin? < he_id {
+                    return None;
+                }
+
+                let quad = self.quad_around_diagonal(he_id)?;
+                let quality = self.quad_quality(quad)?;
+
+                Some((quality, he_id, quad))
+            })
+            .collect_vec();
+
+        candidates.sort_by(|(a, ..), (b, ..)| b.total_cmp(a));
+
+        let mut result = QuadPairs::default();
+        let mut paired_faces = HashSet::new();
+
+        for (_, he_id, quad) in candidates {
+            let he = self.halfedges[he_id];
+            let (Some(face_id), Some(twin_face_id)) = (
+                he.face,
+                he.twin
+                    .and_then(|twin_id| self.halfedges.get(twin_id))
+                    .and_then(|twin| twin.face),
+            ) else {
+                continue;
+            };
+
+            if paired_faces.contains(&face_id) || paired_faces.contains(&twin_face_id) {
+                continue;
+            }
+
+            paired_faces.insert(face_id);
+            paired_faces.insert(twin_face_id);
+
+            result.quads.push((quad, he_id));
+        }
+
+        result.unpaired_faces = self
+            .faces
+            .keys()
+            .filter(|face_id| !paired_faces.contains(face_id))
+            .collect();
+
+        result
+    }
+
+    /// Returns the counter-clockwise vertex loop of the quad that is formed by the two faces
+    /// adjacent to `he_id`.
+    fn quad_around_diagonal(&self, he_id: HalfedgeId) -> Option<[VertexId; 4]> {
+        let he = self.halfedges.get(he_id)?;
+        let twin = self.halfedges.get(he.twin?)?;
+
+        if he.face.is_none() || twin.face.is_none() {
+            return None;
+        }
+
+        let start_vertex = twin.end_vertex;
+        let end_vertex = he.end_vertex;
+
+        let quad = [
+            start_vertex,
+            twin.opposite_vertex(self)?,
+            end_vertex,
+            he.opposite_vertex(self)?,
+        ];
+
+        // two triangles that share more than the diagonal would form a degenerate quad
+        quad.iter().all_unique().then_some(quad)
+    }
+
+    /// Rates the quad between `0` (unusable) and `1` (planar rectangle).
+    /// Returns `None` if the quad is non-convex or degenerate.
+    fn quad_quality(&self, quad: [VertexId; 4]) -> Option<f32> {
+        let positions = quad
+            .iter()
+            .map(|vertex_id| self.positions.get(*vertex_id).copied())
+            .collect::<Option<Vec<Vec3>>>()?;
+
+        let corner_normals = positions
+            .iter()
+            .circular_tuple_windows()
+            .map(|(prev, pos, next)| (next - pos).cross(prev - pos).try_normalize())
+            .collect::<Option<Vec<_>>>()?;
+
+        let normal = corner_normals.iter().sum::<Vec3>().try_normalize()?;
+
+        let mut quality = 1.0_f32;
+
+        for ((prev, pos, next), corner_normal) in positions
+            .iter()
+            .circular_tuple_windows()
+            .zip(corner_normals)
+        {
+            let planarity = corner_normal.dot(normal);
+            if planarity <= 0.0 {
+                // non-convex corner
+                return None;
+            }
+
+            let angle = (prev - pos).angle_between(next - pos);
+            let squareness = 1.0 - (angle - FRAC_PI_2).abs() / FRAC_PI_2;
+
+            quality = quality.min(planarity * squareness);
+        }
+
+        Some(quality.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{primitives::IcoSphere, utils::grid};
+
+    use super::*;
+
+    #[test]
+    fn test_quad_pairs_grid() {
+        let (mesh_graph, _) = grid(6);
+
+        let quad_pairs = mesh_graph.quad_pairs();
+
+        assert!(quad_pairs.unpaired_faces.is_empty());
+        assert_eq!(quad_pairs.quads.len(), 25);
+
+        for (quad, diagonal_id) in quad_pairs.quads {
+            let positions = quad.map(|vertex_id| mesh_graph.positions[vertex_id]);
+
+            // every quad is a unit square
+            for (a, b) in positions.iter().circular_tuple_windows() {
+                assert!((a.distance(*b) - 1.0).abs() < 1e-5);
+            }
+
+            // counter-clockwise
+            let area = (positions[1] - positions[0]).cross(positions[2] - positions[0]);
+            assert!(area.z > 0.0);
+
+            // the diagonal connects the first and the third vertex
+            let diagonal = mesh_graph.halfedges[diagonal_id];
+            assert_eq!(diagonal.end_vertex, quad[2]);
+            assert_eq!(diagonal.start_vertex(&mesh_graph), Some(quad[0]));
+        }
+    }
+
+    #[test]
+    fn test_quad_pairs_ico_sphere() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let quad_pairs = mesh_graph.quad_pairs();
+
+        assert_eq!(
+            quad_pairs.quads.len() * 2 + quad_pairs.unpaired_faces.len(),
+            mesh_graph.faces.len()
+        );
+        assert!(!quad_pairs.quads.is_empty());
+    }
+}
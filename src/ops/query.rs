@@ -1,4 +1,5 @@
-use glam::Vec3;
+use crate::{Face, FaceId, MeshGraph, Selection, error_none, utils::unwrap_or_return};
+use glam::{Vec2, Vec3};
 use itertools::Itertools;
 use parry3d::{
     math::Pose,
@@ -9,13 +10,10 @@ use parry3d::{
     },
     shape::{CompositeShape, CompositeShapeRef, FeatureId, Shape, Triangle, TypedCompositeShape},
 };
-use tracing::instrument;
-
-use crate::{Face, MeshGraph, error_none, utils::unwrap_or_return};
 
 impl PointQuery for MeshGraph {
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn project_local_point(&self, point: Vec3, solid: bool) -> PointProjection {
         self.project_local_point_and_get_location(point, solid).0
     }
@@ -29,7 +27,7 @@ impl PointQueryWithLocation for MeshGraph {
     type Location = Face;
 
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn project_local_point_and_get_location(
         &self,
         point: Vec3,
@@ -40,7 +38,7 @@ impl PointQueryWithLocation for MeshGraph {
     }
 
     /// Projects a point on `self`, with a maximum projection distance.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn project_local_point_and_get_location_with_max_dist(
         &self,
         point: Vec3,
@@ -81,7 +79,7 @@ impl PointQueryWithLocation for MeshGraph {
 
 impl RayCast for MeshGraph {
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn cast_local_ray(&self, ray: &Ray, max_time_of_impact: f32, solid: bool) -> Option<f32> {
         CompositeShapeRef(self)
             .cast_local_ray(ray, max_time_of_impact, solid)
@@ -89,7 +87,7 @@ impl RayCast for MeshGraph {
     }
 
     #[inline]
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn cast_local_ray_and_get_normal(
         &self,
         ray: &Ray,
@@ -103,7 +101,7 @@ impl RayCast for MeshGraph {
 }
 
 impl CompositeShape for MeshGraph {
-    #[instrument(skip(self, f))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, f)))]
     fn map_part_at(
         &self,
         shape_id: u32,
@@ -123,7 +121,7 @@ impl TypedCompositeShape for MeshGraph {
     type PartShape = Triangle;
     type PartNormalConstraints = ();
 
-    #[instrument(skip(self, f))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, f)))]
     fn map_typed_part_at<T>(
         &self,
         shape_id: u32,
@@ -134,7 +132,7 @@ impl TypedCompositeShape for MeshGraph {
         Some(f(None, &tri, pseudo_normals.as_ref()))
     }
 
-    #[instrument(skip(self, f))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, f)))]
     fn map_untyped_part_at<T>(
         &self,
         shape_id: u32,
@@ -147,7 +145,60 @@ impl TypedCompositeShape for MeshGraph {
 }
 
 impl MeshGraph {
-    #[instrument(skip(self))]
+    /// Casts a ray straight down (along -Z) starting at `(xy.x, xy.y, from_height)`.
+    ///
+    /// Returns the face that is hit first and the Z coordinate of the hit point or `None` if
+    /// nothing is hit. This is the typical "sample the surface height at (x, y)" query for
+    /// terrains and heightfields.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn raycast_down(&self, xy: Vec2, from_height: f32) -> Option<(FaceId, f32)> {
+        let ray = Ray::new(xy.extend(from_height), Vec3::NEG_Z);
+
+        let (shape_id, intersection) =
+            CompositeShapeRef(self).cast_local_ray_and_get_normal(&ray, f32::MAX, true)?;
+
+        let face_id = *self
+            .index_to_face_id
+            .get(&shape_id)
+            .or_else(error_none!("Face not found"))?;
+
+        Some((face_id, ray.point_at(intersection.time_of_impact).z))
+    }
+
+    /// Projects `point` onto the closest of the selected faces (see
+    /// [`Selection::resolve_to_faces`]) instead of the whole mesh.
+    ///
+    /// Returns the projected point and the face it lies on or `None` if the selection doesn't
+    /// contain any faces. This is useful to snap to a specific region of the mesh while editing.
+    /// The selected faces are checked one by one so this is linear in their number.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, selection)))]
+    pub fn project_onto_selection(
+        &self,
+        point: Vec3,
+        selection: &Selection,
+    ) -> Option<(Vec3, FaceId)> {
+        selection
+            .resolve_to_faces(self)
+            .into_iter()
+            .filter_map(|face_id| {
+                let [a, b, c] = self
+                    .faces
+                    .get(face_id)
+                    .or_else(error_none!("Face not found"))?
+                    .vertex_positions(self)
+                    .collect_array()?;
+
+                let projection = Triangle::new(a, b, c).project_local_point(point, false);
+
+                Some((projection.point, face_id))
+            })
+            .min_by(|(a, _), (b, _)| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn triangle(&self, shape_id: u32) -> Triangle {
         let face_id = unwrap_or_return!(
             self.index_to_face_id.get(&shape_id),
@@ -203,3 +254,66 @@ impl MeshGraph {
     //     }
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_raycast_down() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 3,
+        });
+
+        let (face_id, z) = mesh_graph.raycast_down(Vec2::ZERO, 10.0).unwrap();
+
+        assert!(z > 1.9 && z <= 2.0 + 1e-5);
+        assert!(mesh_graph.faces[face_id].center(&mesh_graph).z > 0.0);
+
+        assert!(mesh_graph.raycast_down(Vec2::new(5.0, 5.0), 10.0).is_none());
+    }
+
+    #[test]
+    fn test_project_onto_selection() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        assert!(
+            mesh_graph
+                .project_onto_selection(Vec3::ZERO, &Selection::default())
+                .is_none()
+        );
+
+        // the cap at the top of the sphere
+        let selection = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| face.center(&mesh_graph).z > 0.7)
+            .map(|(face_id, _)| face_id)
+            .collect::<Selection>();
+
+        let (projected, face_id) = mesh_graph
+            .project_onto_selection(Vec3::new(0.1, 0.0, 2.0), &selection)
+            .unwrap();
+        assert!(selection.faces.contains(&face_id));
+        assert!(projected.z > 0.9);
+
+        // a point next to the equator snaps to the rim of the cap, not to the closest face
+        let (projected, face_id) = mesh_graph
+            .project_onto_selection(Vec3::new(2.0, 0.0, 0.0), &selection)
+            .unwrap();
+        assert!(selection.faces.contains(&face_id));
+        assert!(projected.z > 0.6);
+
+        let face_positions = mesh_graph.faces[face_id]
+            .vertex_positions(&mesh_graph)
+            .collect_vec();
+        let normal = Face::normal_from_positions(&face_positions);
+        assert!((projected - face_positions[0]).dot(normal).abs() < 1e-5);
+    }
+}
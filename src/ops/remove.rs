@@ -1,8 +1,6 @@
+use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, utils::unwrap_or_return};
 use hashbrown::HashSet;
 use itertools::Itertools;
-use tracing::instrument;
-
-use crate::{FaceId, HalfedgeId, MeshGraph, VertexId, utils::unwrap_or_return};
 
 impl MeshGraph {
     /// Deletes a face from the mesh graph.
@@ -11,8 +9,30 @@ impl MeshGraph {
     /// longer connected to any other faces.
     ///
     /// Returns the ids of the removed vertices and halfedges.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn remove_face(&mut self, face_id: FaceId) -> (Vec<VertexId>, Vec<HalfedgeId>) {
+        self.remove_face_inner(face_id, false)
+    }
+
+    /// Deletes a face from the mesh graph like [`MeshGraph::remove_face`] but keeps
+    /// the vertices.
+    ///
+    /// In contrast to [`MeshGraph::remove_face`], vertices that are no longer connected to any
+    /// other faces stay in `vertices` and `positions` as isolated vertices with
+    /// `outgoing_halfedge` set to `None`. This is useful to keep the original vertices as a
+    /// point cloud.
+    ///
+    /// Returns the ids of the removed halfedges.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn remove_face_keep_vertices(&mut self, face_id: FaceId) -> Vec<HalfedgeId> {
+        self.remove_face_inner(face_id, true).1
+    }
+
+    fn remove_face_inner(
+        &mut self,
+        face_id: FaceId,
+        keep_vertices: bool,
+    ) -> (Vec<VertexId>, Vec<HalfedgeId>) {
         if !self.faces.contains_key(face_id) {
             return (vec![], vec![]);
         }
@@ -81,6 +101,10 @@ impl MeshGraph {
                 );
 
                 v.outgoing_halfedge = Some(self.outgoing_halfedges[start_v_id][0]);
+            } else if keep_vertices {
+                if let Some(v) = self.vertices.get_mut(start_v_id) {
+                    v.outgoing_halfedge = None;
+                }
             } else {
                 self.remove_only_vertex(start_v_id);
                 removed_vertices.push(start_v_id);
@@ -89,11 +113,17 @@ impl MeshGraph {
 
         for he_id in &removed_halfedges {
             self.halfedges.remove(*he_id);
+            self.sharp_edges.remove(he_id);
         }
 
         // already checked at the start of the function
         self.bvh.remove(self.faces[face_id].index);
         self.faces.remove(face_id);
+        if let Some(normals) = &mut self.face_normals {
+            normals.remove(face_id);
+        }
+        self.face_user_data_maps.remove(face_id);
+        self.change_tracker.mark_face(face_id);
 
         (removed_vertices, Vec::from_iter(removed_halfedges))
     }
@@ -105,6 +135,10 @@ impl MeshGraph {
         if let Some(normals) = &mut self.vertex_normals {
             normals.remove(vertex_id);
         }
+        if let Some(uvs) = &mut self.uvs {
+            uvs.remove(vertex_id);
+        }
+        self.vertex_attributes.remove(vertex_id);
         self.vertices.remove(vertex_id);
     }
 
@@ -119,6 +153,7 @@ impl MeshGraph {
             }
 
             self.halfedges.remove(he_id);
+            self.sharp_edges.remove(&he_id);
         }
     }
 
@@ -135,6 +170,7 @@ impl MeshGraph {
             }
 
             self.halfedges.remove(he_id);
+            self.sharp_edges.remove(&he_id);
         }
     }
 }
@@ -183,4 +219,36 @@ mod tests {
 
         assert_eq!(meshgraph.faces.len(), face_count - 1);
     }
+
+    #[test]
+    fn test_remove_face_keep_vertices() {
+        let positions = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+
+        let mut removed = MeshGraph::indexed_triangles(&positions, &[0, 1, 2]);
+        let face_id = removed.faces.keys().next().unwrap();
+        let (removed_vertices, _) = removed.remove_face(face_id);
+
+        assert_eq!(removed_vertices.len(), 3);
+        assert!(removed.vertices.is_empty());
+        assert!(removed.positions.is_empty());
+
+        let mut kept = MeshGraph::indexed_triangles(&positions, &[0, 1, 2]);
+        let face_id = kept.faces.keys().next().unwrap();
+        let removed_halfedges = kept.remove_face_keep_vertices(face_id);
+
+        assert_eq!(removed_halfedges.len(), 6);
+        assert!(kept.faces.is_empty());
+        assert!(kept.halfedges.is_empty());
+        assert_eq!(kept.vertices.len(), 3);
+        assert_eq!(kept.positions.len(), 3);
+        assert!(
+            kept.vertices
+                .values()
+                .all(|vertex| vertex.outgoing_halfedge.is_none())
+        );
+    }
 }
@@ -0,0 +1,88 @@
+use crate::{HalfedgeId, MeshGraph, error_none};
+
+impl MeshGraph {
+    /// Tags or untags the edge of the given halfedge (and its twin) as a sharp crease.
+    ///
+    /// When a sharp edge is split by [`MeshGraph::subdivide_edge`] or
+    /// [`MeshGraph::loop_subdivide`] both halves stay sharp. Removing an edge removes its tag,
+    /// and if an edge collapse merges two edges the result is sharp if either of them was.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn set_edge_sharp(&mut self, he_id: HalfedgeId, sharp: bool) {
+        let Some(twin_id) = self
+            .halfedges
+            .get(he_id)
+            .or_else(error_none!("Halfedge not found"))
+            .and_then(|he| he.twin.or_else(error_none!("Twin missing")))
+        else {
+            return;
+        };
+
+        if sharp {
+            self.sharp_edges.extend([he_id, twin_id]);
+        } else {
+            self.sharp_edges.remove(&he_id);
+            self.sharp_edges.remove(&twin_id);
+        }
+    }
+
+    /// Returns `true` if the edge of the given halfedge is tagged as a sharp crease.
+    #[inline]
+    pub fn is_edge_sharp(&self, he_id: HalfedgeId) -> bool {
+        self.sharp_edges.contains(&he_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid;
+
+    use super::*;
+
+    fn assert_sharp_edges_exist(mesh_graph: &MeshGraph) {
+        for he_id in &mesh_graph.sharp_edges {
+            let twin_id = mesh_graph.halfedges[*he_id].twin.unwrap();
+            assert!(mesh_graph.is_edge_sharp(twin_id));
+        }
+    }
+
+    #[test]
+    fn test_remove_face_removes_sharp_edges() {
+        let (mut mesh_graph, vertex_ids) = grid(3);
+
+        let boundary_he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[0], vertex_ids[1])
+            .unwrap();
+        mesh_graph.set_edge_sharp(boundary_he_id, true);
+        assert_eq!(mesh_graph.sharp_edges.len(), 2);
+
+        let face_id = mesh_graph.halfedges[boundary_he_id].face.unwrap();
+        mesh_graph.remove_face(face_id);
+
+        assert!(mesh_graph.sharp_edges.is_empty());
+    }
+
+    #[test]
+    fn test_collapse_edge_merges_sharp_edges() {
+        let (mut mesh_graph, vertex_ids) = grid(3);
+
+        let sharp_he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[1], vertex_ids[4])
+            .unwrap();
+        mesh_graph.set_edge_sharp(sharp_he_id, true);
+
+        // removes the triangle 0, 1, 4 which merges the edges 1-4 and 1-0
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[4], vertex_ids[0])
+            .unwrap();
+        mesh_graph.collapse_edge(he_id);
+
+        assert_eq!(mesh_graph.sharp_edges.len(), 2);
+        assert_sharp_edges_exist(&mesh_graph);
+        assert!(
+            mesh_graph
+                .sharp_edges
+                .iter()
+                .any(|he_id| { mesh_graph.halfedges[*he_id].end_vertex == vertex_ids[1] })
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{HalfedgeId, MeshGraph};
+use glam::Vec3;
+
+impl MeshGraph {
+    /// Returns the silhouette edges as seen from the direction `view_dir`.
+    ///
+    /// An edge is part of the silhouette if one of its adjacent faces is front-facing and the
+    /// other one is back-facing, i.e. the signs of `face_normal.dot(view_dir)` differ.
+    /// Boundary edges are always part of the silhouette. Only one halfedge per edge is returned.
+    ///
+    /// Useful for outline rendering and shadow volumes.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn silhouette_edges(&self, view_dir: Vec3) -> Vec<HalfedgeId> {
+        let is_front_facing = |he_id: HalfedgeId| {
+            self.halfedges
+                .get(he_id)
+                .and_then(|he| he.face)
+                .and_then(|face_id| self.faces.get(face_id))
+                .and_then(|face| face.compute_normal(self))
+                .map(|normal| normal.dot(view_dir) < 0.0)
+        };
+
+        self.halfedges
+            .iter()
+            .filter_map(|(he_id, he)| {
+                let twin_id = he.twin?;
+
+                if twin_id < he_id {
+                    return None;
+                }
+
+                match (is_front_facing(he_id), is_front_facing(twin_id)) {
+                    (Some(front), Some(twin_front)) => (front != twin_front).then_some(he_id),
+                    (None, None) => None,
+                    _ => Some(he_id),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_ico_sphere_silhouette_is_equator() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        });
+
+        let silhouette = mesh_graph.silhouette_edges(Vec3::Z);
+
+        assert!(silhouette.len() >= 16);
+
+        for he_id in silhouette {
+            let he = mesh_graph.halfedges[he_id];
+            let start = mesh_graph.positions[he.start_vertex(&mesh_graph).unwrap()];
+            let end = mesh_graph.positions[he.end_vertex];
+
+            let center = (start + end) * 0.5;
+
+            assert!(center.z.abs() < 0.2);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::utils::error;
+use glam::{Mat4, Vec3, Vec4, Vec4Swizzles};
+use hashbrown::HashMap;
+use slotmap::SecondaryMap;
+
+use crate::{HalfedgeId, MeshGraph, VertexId, VertexSplitRecord};
+
+/// Return value of [`MeshGraph::simplify_to_face_count`] and [`MeshGraph::simplify_to_max_error`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Simplify {
+    /// Number of edges that have been collapsed
+    pub collapsed_edges: usize,
+    /// The largest quadric error of all performed collapses
+    pub max_error: f32,
+}
+
+impl MeshGraph {
+    /// Decimates the mesh by collapsing the edges with the smallest quadric error
+    /// (Garland and Heckbert 1997) until at most `face_count` faces are left or no edge
+    /// can be collapsed anymore.
+    ///
+    /// Boundary edges and collapses that would flip faces are skipped.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn simplify_to_face_count(&mut self, face_count: usize) -> Simplify {
+        self.simplify_qem(|mesh_graph, _| mesh_graph.faces.len() <= face_count, None)
+    }
+
+    /// Decimates the mesh by collapsing the edges with the smallest quadric error
+    /// (Garland and Heckbert 1997) until the next collapse would exceed `max_error`.
+    ///
+    /// In contrast to [`MeshGraph::simplify_to_face_count`] this guarantees a fidelity bound
+    /// instead of a size.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn simplify_to_max_error(&mut self, max_error: f32) -> Simplify {
+        self.simplify_qem(|_, error| error > max_error, None)
+    }
+
+    /// Runs the decimation. If `splits` is given, a [`VertexSplitRecord`] is pushed for every
+    /// performed collapse.
+    pub(crate) fn simplify_qem(
+        &mut self,
+        stop: impl Fn(&MeshGraph, f32) -> bool,
+        mut splits: Option<&mut Vec<VertexSplitRecord>>,
+    ) -> Simplify {
+        let mut result = Simplify::default();
+
+        let mut quadrics = self.vertex_quadrics();
+
+        let mut queue = BinaryHeap::new();
+        let mut current_errors = HashMap::new();
+
+        for he_id in self.halfedges.keys() {
+            self.enqueue_edge_collapse(he_id, &quadrics, &mut queue, &mut current_errors);
+        }
+
+        while let Some(candidate) = queue.pop() {
+            // skip outdated entries
+            if current_errors.get(&candidate.he_id) != Some(&candidate.error) {
+                continue;
+            }
+
+            if stop(self, candidate.error) {
+                break;
+            }
+
+            current_errors.remove(&candidate.he_id);
+
+            let Some((twin_id, start_v_id, end_v_id, pos)) =
+                self.can_collapse_edge_to(candidate.he_id, |_, _| candidate.pos)
+            else {
+                continue;
+            };
+
+            let quadric = quadrics.get(start_v_id).copied().unwrap_or_default()
+                + quadrics.get(end_v_id).copied().unwrap_or_default();
+
+            let snapshot = splits
+                .is_some()
+                .then(|| self.collapse_snapshot(start_v_id, end_v_id));
+
+            let collapse_edge_result =
+                self.collapse_edge_inner(candidate.he_id, twin_id, start_v_id, end_v_id, pos);
+
+            if let (Some(splits), Some(snapshot)) = (splits.as_deref_mut(), snapshot) {
+                splits
+                    .push(self.vertex_split_record(snapshot, &collapse_edge_result.added_vertices));
+            }
+
+            result.collapsed_edges += 1;
+            result.max_error = result.max_error.max(candidate.error);
+
+            for removed_he_id in &collapse_edge_result.removed_halfedges {
+                current_errors.remove(removed_he_id);
+            }
+
+            let mut affected_vertices = collapse_edge_result.added_vertices;
+            if self.vertices.contains_key(start_v_id) {
+                quadrics.insert(start_v_id, quadric);
+                affected_vertices.push(start_v_id);
+            }
+
+            for vertex_id in affected_vertices {
+                if !quadrics.contains_key(vertex_id)
+                    && let Some(quadric) = self.vertex_quadric(vertex_id)
+                {
+                    quadrics.insert(vertex_id, quadric);
+                }
+
+                let Some(he_ids) = self.outgoing_halfedges.get(vertex_id).cloned() else {
+                    continue;
+                };
+
+                for he_id in he_ids {
+                    self.enqueue_edge_collapse(he_id, &quadrics, &mut queue, &mut current_errors);
+
+                    if let Some(face) = self
+                        .halfedges
+                        .get(he_id)
+                        .and_then(|he| he.face)
+                        .and_then(|face_id| self.faces.get(face_id))
+                    {
+                        self.bvh
+                            .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                        self.change_tracker.mark_face(face.id);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    fn enqueue_edge_collapse(
+        &self,
+        he_id: HalfedgeId,
+        quadrics: &SecondaryMap<VertexId, Mat4>,
+        queue: &mut BinaryHeap<CollapseCandidate>,
+        current_errors: &mut HashMap<HalfedgeId, f32>,
+    ) {
+        let Some(he) = self.halfedges.get(he_id) else {
+            return;
+        };
+        let Some(twin_id) = he.twin else {
+            error!("Twin missing");
+            return;
+        };
+
+        // every edge is only queued once
+        let (he_id, twin_id) = (he_id.min(twin_id), he_id.max(twin_id));
+        let (Some(he), Some(twin)) = (self.halfedges.get(he_id), self.halfedges.get(twin_id))
+        else {
+            return;
+        };
+
+        if he.is_boundary() || twin.is_boundary() {
+            current_errors.remove(&he_id);
+            return;
+        }
+
+        let start_v_id = twin.end_vertex;
+        let end_v_id = he.end_vertex;
+
+        let (Some(start_pos), Some(end_pos), Some(start_quadric), Some(end_quadric)) = (
+            self.positions.get(start_v_id),
+            self.positions.get(end_v_id),
+            quadrics.get(start_v_id),
+            quadrics.get(end_v_id),
+        ) else {
+            return;
+        };
+
+        let quadric = *start_quadric + *end_quadric;
+        let (pos, error) = optimal_collapse_position(&quadric, *start_pos, *end_pos);
+
+        current_errors.insert(he_id, error);
+        queue.push(CollapseCandidate { error, he_id, pos });
+    }
+
+    /// Computes the fundamental error quadrics of all vertices
+    fn vertex_quadrics(&self) -> SecondaryMap<VertexId, Mat4> {
+        let mut quadrics = SecondaryMap::<VertexId, Mat4>::with_capacity(self.vertices.len());
+
+        for face in self.faces.values() {
+            let Some(quadric) = self.face_quadric(face) else {
+                continue;
+            };
+
+            for vertex_id in face.vertices(self) {
+                if let Some(entry) = quadrics.entry(vertex_id) {
+                    *entry.or_insert(Mat4::ZERO) += quadric;
+                }
+            }
+        }
+
+        quadrics
+    }
+
+    fn vertex_quadric(&self, vertex_id: VertexId) -> Option<Mat4> {
+        Some(
+            self.vertices
+                .get(vertex_id)?
+                .faces(self)
+                .filter_map(|face_id| self.face_quadric(self.faces.get(face_id)?))
+                .fold(Mat4::ZERO, |acc, quadric| acc + quadric),
+        )
+    }
+
+    fn face_quadric(&self, face: &crate::Face) -> Option<Mat4> {
+        let normal = face.compute_normal(self)?;
+        let pos = face.vertex_positions(self).next()?;
+
+        let plane = normal.extend(-normal.dot(pos));
+
+        Some(Mat4::from_cols(
+            plane * plane.x,
+            plane * plane.y,
+            plane * plane.z,
+            plane * plane.w,
+        ))
+    }
+}
+
+/// Returns the position with the minimal quadric error for collapsing the edge between
+/// `start_pos` and `end_pos` together with that error.
+fn optimal_collapse_position(quadric: &Mat4, start_pos: Vec3, end_pos: Vec3) -> (Vec3, f32) {
+    let error = |pos: Vec3| {
+        let pos = pos.extend(1.0);
+        pos.dot(*quadric * pos).max(0.0)
+    };
+
+    let center = (start_pos + end_pos) * 0.5;
+
+    let system = Mat4::from_cols(
+        quadric.x_axis.with_w(0.0),
+        quadric.y_axis.with_w(0.0),
+        quadric.z_axis.with_w(0.0),
+        quadric.w_axis.with_w(1.0),
+    );
+
+    if system.determinant().abs() > 1e-8 {
+        let pos = (system.inverse() * Vec4::W).xyz();
+
+        // badly conditioned systems can place the vertex far away
+        if pos.is_finite() && pos.distance_squared(center) <= start_pos.distance_squared(end_pos) {
+            return (pos, error(pos));
+        }
+    }
+
+    [center, start_pos, end_pos]
+        .into_iter()
+        .map(|pos| (pos, error(pos)))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap() // array is not empty
+}
+
+/// Entry of the priority queue. Ordered such that the smallest error is popped first.
+struct CollapseCandidate {
+    error: f32,
+    he_id: HalfedgeId,
+    pos: Vec3,
+}
+
+impl PartialEq for CollapseCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CollapseCandidate {}
+
+impl PartialOrd for CollapseCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CollapseCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .error
+            .total_cmp(&self.error)
+            .then_with(|| other.he_id.cmp(&self.he_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    fn ico_sphere() -> MeshGraph {
+        MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 3,
+        })
+    }
+
+    #[test]
+    fn test_simplify_to_max_error() {
+        let face_count = ico_sphere().faces.len();
+
+        let mut fine = ico_sphere();
+        let small_error = 2e-4;
+        let fine_result = fine.simplify_to_max_error(small_error);
+
+        let mut coarse = ico_sphere();
+        let large_error = 1e-3;
+        let coarse_result = coarse.simplify_to_max_error(large_error);
+
+        assert!(fine_result.max_error <= small_error);
+        assert!(coarse_result.max_error <= large_error);
+
+        assert!(fine.faces.len() < face_count);
+        assert!(fine.faces.len() > face_count * 3 / 4);
+        assert!(coarse.faces.len() < face_count / 2);
+        assert!(coarse_result.collapsed_edges > fine_result.collapsed_edges);
+    }
+
+    #[test]
+    fn test_simplify_to_face_count() {
+        let mut mesh_graph = ico_sphere();
+
+        let result = mesh_graph.simplify_to_face_count(400);
+
+        assert!(result.collapsed_edges > 0);
+        assert!(mesh_graph.faces.len() <= 400);
+    }
+
+    #[test]
+    fn test_simplify_ignores_face_normal_cache() {
+        let deform = |mesh_graph: &mut MeshGraph| {
+            for pos in mesh_graph.positions.values_mut() {
+                *pos *= Vec3::new(2.0, 1.0, 0.5);
+            }
+        };
+
+        let mut uncached = ico_sphere();
+        deform(&mut uncached);
+        let uncached_result = uncached.simplify_to_face_count(400);
+
+        // the cache is stale after moving the vertices and must not affect the quadrics
+        let mut cached = ico_sphere();
+        cached.compute_face_normals();
+        deform(&mut cached);
+        let cached_result = cached.simplify_to_face_count(400);
+
+        assert_eq!(
+            cached_result.collapsed_edges,
+            uncached_result.collapsed_edges
+        );
+        assert!(cached.positions.values().eq(uncached.positions.values()));
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use hashbrown::HashSet;
+use itertools::Itertools;
+
+use crate::{HalfedgeId, MeshGraph, VertexId};
+
+/// Error of [`MeshGraph::stitch_edges`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StitchError {
+    /// The halfedge doesn't exist
+    HalfedgeNotFound(HalfedgeId),
+    /// The halfedge has a face, so it can't be stitched to another edge
+    NotBoundary(HalfedgeId),
+    /// Both halfedges belong to the same edge
+    SameEdge,
+}
+
+impl fmt::Display for StitchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StitchError::HalfedgeNotFound(he_id) => write!(f, "Halfedge {he_id:?} not found"),
+            StitchError::NotBoundary(he_id) => {
+                write!(f, "Halfedge {he_id:?} is not a boundary halfedge")
+            }
+            StitchError::SameEdge => write!(f, "Both halfedges belong to the same edge"),
+        }
+    }
+}
+
+impl std::error::Error for StitchError {}
+
+impl MeshGraph {
+    /// Merges the two boundary halfedges `he1` and `he2` into a single interior edge.
+    ///
+    /// The halfedges have to run in opposite directions along the seam, i.e. the start vertex of
+    /// `he1` is welded to the end vertex of `he2` and vice versa (see
+    /// [`MeshGraph::merge_vertices`]). Afterwards the two faces on the other sides become
+    /// neighbours and the boundary halfedges are removed.
+    ///
+    /// This closes a seam precisely edge by edge, in contrast to welding everything that is
+    /// within some tolerance.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn stitch_edges(&mut self, he1: HalfedgeId, he2: HalfedgeId) -> Result<(), StitchError> {
+        let (start1, end1, twin1) = self.boundary_halfedge_vertices(he1)?;
+        let (start2, end2, twin2) = self.boundary_halfedge_vertices(he2)?;
+
+        if he1 == he2 || twin1 == he2 {
+            return Err(StitchError::SameEdge);
+        }
+
+        // welding the vertices joins the duplicate halfedges of the seam
+        // (see `merge_vertices`) unless both vertex pairs are already welded
+        let survivors = [(start1, end2), (end1, start2)]
+            .into_iter()
+            .map(|(v1, v2)| {
+                if v1 != v2 {
+                    self.merge_vertices([v1, v2]);
+                }
+                v1
+            })
+            .collect_vec();
+
+        if self.halfedges.contains_key(he1) && self.halfedges.contains_key(he2) {
+            self.join_seam_halfedges([he1, he2], [twin1, twin2]);
+        }
+
+        let mut faces = HashSet::new();
+        for vertex_id in survivors {
+            if !self.vertices.contains_key(vertex_id) {
+                continue;
+            }
+
+            // the outgoing halfedge left by `merge_vertices` can be an interior one from which
+            // rotating around the vertex doesn't reach the boundary, so search the cache instead
+            if let Some(boundary_he_id) = self
+                .outgoing_halfedges
+                .get(vertex_id)
+                .into_iter()
+                .flatten()
+                .copied()
+                .find(|he_id| {
+                    self.halfedges
+                        .get(*he_id)
+                        .is_some_and(|he| he.is_boundary())
+                })
+            {
+                self.vertices[vertex_id].outgoing_halfedge = Some(boundary_he_id);
+            }
+
+            faces.extend(self.vertices[vertex_id].faces(self));
+        }
+
+        for face_id in faces {
+            if let Some(face) = self.faces.get(face_id) {
+                self.bvh
+                    .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                self.change_tracker.mark_face(face.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the start and end vertex as well as the twin of the boundary halfedge `he_id`.
+    fn boundary_halfedge_vertices(
+        &self,
+        he_id: HalfedgeId,
+    ) -> Result<(VertexId, VertexId, HalfedgeId), StitchError> {
+        let he = self
+            .halfedges
+            .get(he_id)
+            .ok_or(StitchError::HalfedgeNotFound(he_id))?;
+
+        if !he.is_boundary() {
+            return Err(StitchError::NotBoundary(he_id));
+        }
+
+        let twin_id = he.twin.ok_or(StitchError::HalfedgeNotFound(he_id))?;
+        let start_v_id = he
+            .start_vertex(self)
+            .ok_or(StitchError::HalfedgeNotFound(twin_id))?;
+
+        Ok((start_v_id, he.end_vertex, twin_id))
+    }
+
+    /// Removes the two boundary halfedges `he_ids` of an edge that is present twice and makes
+    /// their former twins `twin_ids` twins of each other.
+    fn join_seam_halfedges(&mut self, he_ids: [HalfedgeId; 2], twin_ids: [HalfedgeId; 2]) {
+        let start_vertices: Vec<Option<VertexId>> = he_ids
+            .iter()
+            .map(|he_id| self.halfedges[*he_id].start_vertex(self))
+            .collect();
+
+        for (he_id, start_v_id) in he_ids.into_iter().zip(start_vertices) {
+            if let Some(start_v_id) = start_v_id {
+                self.remove_outgoing_halfedge(start_v_id, he_id);
+            }
+            self.halfedges.remove(he_id);
+            self.sharp_edges.remove(&he_id);
+        }
+
+        let [twin1, twin2] = twin_ids;
+        if let Some(twin) = self.halfedges.get_mut(twin1) {
+            twin.twin = Some(twin2);
+        }
+        if let Some(twin) = self.halfedges.get_mut(twin2) {
+            twin.twin = Some(twin1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    /// Two unit squares next to each other that don't share any vertices
+    fn two_patches() -> (MeshGraph, Vec<VertexId>) {
+        let positions = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+        ];
+
+        MeshGraph::indexed_triangles_and_vertex_ids(
+            &positions,
+            &[0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7],
+        )
+    }
+
+    #[test]
+    fn test_stitch_edges() {
+        let (mut mesh_graph, vertex_ids) = two_patches();
+
+        assert_eq!(mesh_graph.boundary_loops().len(), 2);
+
+        // the right side of the first and the left side of the second square
+        let he1 = mesh_graph
+            .halfedge_from_to(vertex_ids[2], vertex_ids[1])
+            .unwrap();
+        let he2 = mesh_graph
+            .halfedge_from_to(vertex_ids[4], vertex_ids[7])
+            .unwrap();
+
+        mesh_graph.stitch_edges(he1, he2).unwrap();
+
+        assert_eq!(mesh_graph.vertices.len(), 6);
+        assert_eq!(mesh_graph.faces.len(), 4);
+        assert_eq!(mesh_graph.halfedges.len(), 18);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        let boundary_loops = mesh_graph.boundary_loops();
+        assert_eq!(boundary_loops.len(), 1);
+        assert_eq!(boundary_loops[0].len(), 6);
+
+        // the seam is an interior edge now
+        let seam = mesh_graph
+            .halfedge_from_to(vertex_ids[1], vertex_ids[2])
+            .unwrap();
+        let seam_he = mesh_graph.halfedges[seam];
+        assert!(!seam_he.is_boundary());
+        assert!(!mesh_graph.halfedges[seam_he.twin.unwrap()].is_boundary());
+
+        for vertex_id in [vertex_ids[1], vertex_ids[2]] {
+            assert!(mesh_graph.vertices[vertex_id].is_boundary(&mesh_graph));
+            assert_eq!(mesh_graph.vertices[vertex_id].faces(&mesh_graph).count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_stitch_edges_errors() {
+        let (mut mesh_graph, vertex_ids) = two_patches();
+
+        let interior = mesh_graph
+            .halfedge_from_to(vertex_ids[0], vertex_ids[2])
+            .unwrap();
+        let boundary = mesh_graph
+            .halfedge_from_to(vertex_ids[4], vertex_ids[7])
+            .unwrap();
+
+        assert_eq!(
+            mesh_graph.stitch_edges(interior, boundary),
+            Err(StitchError::NotBoundary(interior))
+        );
+        assert_eq!(
+            mesh_graph.stitch_edges(boundary, boundary),
+            Err(StitchError::SameEdge)
+        );
+        assert_eq!(mesh_graph.vertices.len(), 8);
+    }
+}
@@ -1,9 +1,9 @@
-use hashbrown::HashSet;
-use tracing::instrument;
-
 use crate::{
-    HalfedgeId, MeshGraph, Selection, SelectionOps, VertexId, error_none, utils::unwrap_or_return,
+    HalfedgeId, MeshGraph, Selection, SelectionOps, VertexId, error_none,
+    utils::{error, unwrap_or_return},
 };
+use hashbrown::HashSet;
+use itertools::Itertools;
 
 #[cfg(feature = "rerun")]
 use crate::utils::vec3_array;
@@ -14,7 +14,7 @@ impl MeshGraph {
     ///
     /// This will schedule necessary updates to the QBVH but you have to call
     /// `refit_bvh()` after the operation.
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn subdivide_until_edges_below_max_length(
         &mut self,
         max_length_squared: f32,
@@ -140,7 +140,10 @@ impl MeshGraph {
     /// (The one from dividing the halfedge and at most 2 from dividing the two adjacent faces).
     ///
     /// Also returns the created vertex id.
-    #[instrument(skip(self))]
+    ///
+    /// On a boundary edge only the single adjacent face is split and the boundary halfedge is
+    /// split into two consecutive boundary halfedges.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub fn subdivide_edge(&mut self, halfedge_id: HalfedgeId) -> Option<SubdivideEdge> {
         let mut added_halfedges = Vec::with_capacity(3);
 
@@ -188,6 +191,15 @@ impl MeshGraph {
                 .or_else(error_none!("End normal not found"))?;
             normals.insert(center_v, (start_normal + end_normal).normalize());
         }
+        if let Some(uvs) = &mut self.uvs {
+            let start_uv = uvs
+                .get(start_v)
+                .or_else(error_none!("Start UV not found"))?;
+            let end_uv = uvs.get(end_v).or_else(error_none!("End UV not found"))?;
+            uvs.insert(center_v, (start_uv + end_uv) * 0.5);
+        }
+        self.vertex_attributes
+            .interpolate(center_v, start_v, end_v, 0.5);
 
         let new_he = self.add_halfedge(center_v, end_v)?;
         // inserted just above
@@ -195,13 +207,22 @@ impl MeshGraph {
 
         added_halfedges.push(new_he);
 
-        if let Some(new_face_he) = self.subdivide_face(halfedge_id, new_he, center_v) {
+        if let Some(new_face_he) = self.subdivide_face_or_boundary(halfedge_id, new_he, center_v) {
             added_halfedges.push(new_face_he);
         }
 
         let new_twin = self.add_halfedge(center_v, start_v)?;
 
-        if let Some(new_face_he) = self.subdivide_face(twin_id, new_twin, center_v) {
+        if self
+            .halfedges
+            .get(twin_id)
+            .is_some_and(|twin| twin.is_boundary())
+        {
+            // the outgoing halfedge of a boundary vertex has to be the boundary halfedge
+            self.vertices[center_v].outgoing_halfedge = Some(new_twin);
+        }
+
+        if let Some(new_face_he) = self.subdivide_face_or_boundary(twin_id, new_twin, center_v) {
             added_halfedges.push(new_face_he);
         }
 
@@ -217,6 +238,11 @@ impl MeshGraph {
         // inserted above
         self.halfedges[new_twin].twin = Some(halfedge_id);
 
+        // the two halves of a sharp edge stay sharp
+        if self.sharp_edges.contains(&halfedge_id) {
+            self.sharp_edges.extend([new_he, new_twin]);
+        }
+
         // self.vertices[end_v].outgoing_halfedge = Some(new_twin);
         // self.vertices[start_v].outgoing_halfedge = Some(new_he);
 
@@ -226,8 +252,118 @@ impl MeshGraph {
         })
     }
 
+    /// Subdivides an edge into `segments` parts of equal length by inserting `segments - 1`
+    /// vertices. The adjacent triangles are split into fans from their opposite vertex to each
+    /// new vertex. Works only on triangle meshes.
+    ///
+    /// Returns the halfedges that make up the original halfedge afterwards ordered from its start
+    /// to its end vertex.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn subdivide_edge_n(
+        &mut self,
+        halfedge_id: HalfedgeId,
+        segments: usize,
+    ) -> Vec<HalfedgeId> {
+        if segments == 0 {
+            error!("Cannot subdivide an edge into zero segments");
+            return vec![];
+        }
+
+        let he = *unwrap_or_return!(
+            self.halfedges.get(halfedge_id),
+            "Halfedge not found",
+            vec![]
+        );
+        let start_v = unwrap_or_return!(he.start_vertex(self), "Start vertex not found", vec![]);
+        let start_pos = *unwrap_or_return!(
+            self.positions.get(start_v),
+            "Start position not found",
+            vec![]
+        );
+        let end_pos = *unwrap_or_return!(
+            self.positions.get(he.end_vertex),
+            "End position not found",
+            vec![]
+        );
+
+        let end_v = he.end_vertex;
+        let endpoint_uvs = self
+            .uvs
+            .as_ref()
+            .and_then(|uvs| Some((*uvs.get(start_v)?, *uvs.get(end_v)?)));
+
+        let mut segment_he_ids = vec![halfedge_id];
+        let mut added_vertices = Vec::with_capacity(segments - 1);
+
+        for i in 1..segments {
+            // always split the remaining part of the edge
+            let remaining_he_id = segment_he_ids[segment_he_ids.len() - 1];
+
+            let Some(subdivide_edge_result) = self.subdivide_edge(remaining_he_id) else {
+                error!("Couldn't subdivide edge");
+                break;
+            };
+
+            // `subdivide_edge` interpolated at the center of the remaining part of the edge
+            let vertex_id = subdivide_edge_result.added_vertex;
+            let t = i as f32 / segments as f32;
+            self.positions[vertex_id] = start_pos.lerp(end_pos, t);
+
+            if let (Some(uvs), Some((start_uv, end_uv))) = (&mut self.uvs, endpoint_uvs) {
+                uvs.insert(vertex_id, start_uv.lerp(end_uv, t));
+            }
+            self.vertex_attributes
+                .interpolate(vertex_id, start_v, end_v, t);
+
+            added_vertices.push(vertex_id);
+            // the first added halfedge goes from the new vertex to the end vertex
+            segment_he_ids.push(subdivide_edge_result.added_halfedges[0]);
+        }
+
+        // the new vertices have been moved away from the centers used by `subdivide_edge`
+        for vertex_id in added_vertices {
+            for face_id in self.vertices[vertex_id].faces(self).collect_vec() {
+                if let Some(face) = self.faces.get(face_id) {
+                    self.bvh
+                        .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                    self.change_tracker.mark_face(face.id);
+                }
+            }
+        }
+
+        segment_he_ids
+    }
+
+    /// Splits the side of the edge that `existing_halfedge_id` belongs to. If it has a face, the
+    /// face is subdivided (see [Self::subdivide_face]). On the boundary there is no face, so
+    /// only the existing halfedge is shortened to end at `center_v`. Together with
+    /// `new_halfedge_id` it forms the new part of the boundary loop.
+    ///
+    /// Returns the halfedge that is created by subdividing the face, if any.
+    fn subdivide_face_or_boundary(
+        &mut self,
+        existing_halfedge_id: HalfedgeId,
+        new_halfedge_id: HalfedgeId,
+        center_v: VertexId,
+    ) -> Option<HalfedgeId> {
+        let he = self
+            .halfedges
+            .get_mut(existing_halfedge_id)
+            .or_else(error_none!("Halfedge not found"))?;
+
+        if he.is_boundary() {
+            he.end_vertex = center_v;
+            None
+        } else {
+            self.subdivide_face(existing_halfedge_id, new_halfedge_id, center_v)
+        }
+    }
+
     /// Subdivides a triangle into two halves. Used in [Self::subdivide_edge].
-    #[instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     fn subdivide_face(
         &mut self,
         existing_halfedge_id: HalfedgeId,
@@ -279,8 +415,10 @@ impl MeshGraph {
         let new_face = self.faces[new_face_id];
         self.bvh
             .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+        self.change_tracker.mark_face(face.id);
         self.bvh
             .insert_or_update_partially(new_face.aabb(self), new_face.index, 0.0);
+        self.change_tracker.mark_face(new_face.id);
 
         #[cfg(feature = "rerun")]
         {
@@ -300,4 +438,171 @@ pub struct SubdivideEdge {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use glam::{Vec2, Vec3};
+
+    use crate::utils::grid;
+
+    use super::*;
+
+    #[test]
+    fn test_subdivide_sharp_edge() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let a = mesh_graph.add_vertex(Vec3::new(0.0, 0.0, 0.0));
+        let b = mesh_graph.add_vertex(Vec3::new(1.0, 0.0, 0.0));
+        let c = mesh_graph.add_vertex(Vec3::new(1.0, 1.0, 0.0));
+        let d = mesh_graph.add_vertex(Vec3::new(0.0, 1.0, 1.0));
+
+        mesh_graph.add_face_from_vertices(a, b, c).unwrap();
+        mesh_graph.add_face_from_vertices(a, c, d).unwrap();
+
+        let he_id = mesh_graph.halfedge_from_to(a, c).unwrap();
+        mesh_graph.set_edge_sharp(he_id, true);
+
+        let center_v = mesh_graph.subdivide_edge(he_id).unwrap().added_vertex;
+
+        assert_eq!(mesh_graph.positions[center_v], Vec3::new(0.5, 0.5, 0.0));
+
+        for v_id in [a, c] {
+            let he_id = mesh_graph.halfedge_from_to(center_v, v_id).unwrap();
+            assert!(mesh_graph.is_edge_sharp(he_id));
+
+            let he_id = mesh_graph.halfedge_from_to(v_id, center_v).unwrap();
+            assert!(mesh_graph.is_edge_sharp(he_id));
+        }
+
+        for v_id in [b, d] {
+            let he_id = mesh_graph.halfedge_from_to(center_v, v_id).unwrap();
+            assert!(!mesh_graph.is_edge_sharp(he_id));
+        }
+    }
+
+    #[test]
+    fn test_subdivide_edge_n() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let a = mesh_graph.add_vertex(Vec3::new(0.0, 0.0, 0.0));
+        let b = mesh_graph.add_vertex(Vec3::new(1.0, -1.0, 0.0));
+        let c = mesh_graph.add_vertex(Vec3::new(3.0, 0.0, 0.0));
+        let d = mesh_graph.add_vertex(Vec3::new(1.0, 1.0, 0.0));
+
+        mesh_graph.add_face_from_vertices(a, b, c).unwrap();
+        mesh_graph.add_face_from_vertices(a, c, d).unwrap();
+
+        let vertex_count = mesh_graph.vertices.len();
+        let face_count = mesh_graph.faces.len();
+
+        let he_id = mesh_graph.halfedge_from_to(a, c).unwrap();
+        let segment_he_ids = mesh_graph.subdivide_edge_n(he_id, 3);
+
+        assert_eq!(segment_he_ids.len(), 3);
+        assert_eq!(mesh_graph.vertices.len(), vertex_count + 2);
+        assert_eq!(mesh_graph.faces.len(), face_count + 4);
+
+        let segment_vertices = segment_he_ids
+            .iter()
+            .map(|he_id| mesh_graph.halfedges[*he_id].end_vertex)
+            .collect_vec();
+        assert_eq!(segment_vertices[2], c);
+        assert_eq!(
+            mesh_graph.halfedges[segment_he_ids[0]].start_vertex(&mesh_graph),
+            Some(a)
+        );
+        assert_eq!(
+            mesh_graph.positions[segment_vertices[0]],
+            Vec3::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            mesh_graph.positions[segment_vertices[1]],
+            Vec3::new(2.0, 0.0, 0.0)
+        );
+
+        // fans from both opposite vertices
+        for v_id in &segment_vertices[..2] {
+            assert!(mesh_graph.halfedge_from_to(b, *v_id).is_some());
+            assert!(mesh_graph.halfedge_from_to(d, *v_id).is_some());
+        }
+    }
+
+    #[test]
+    fn test_subdivide_edge_n_interpolates() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let a = mesh_graph.add_vertex(Vec3::new(0.0, 0.0, 0.0));
+        let b = mesh_graph.add_vertex(Vec3::new(0.5, -1.0, 0.0));
+        let c = mesh_graph.add_vertex(Vec3::new(1.0, 0.0, 0.0));
+        let d = mesh_graph.add_vertex(Vec3::new(0.5, 1.0, 0.0));
+
+        mesh_graph.add_face_from_vertices(a, b, c).unwrap();
+        mesh_graph.add_face_from_vertices(a, c, d).unwrap();
+
+        mesh_graph.uvs = Some(
+            mesh_graph
+                .positions
+                .iter()
+                .map(|(vertex_id, pos)| (vertex_id, pos.truncate()))
+                .collect(),
+        );
+
+        let weight = mesh_graph.add_vertex_attribute::<f32>("weight");
+        let weights = mesh_graph.vertex_attribute_mut(weight).unwrap();
+        for (vertex_id, value) in [(a, 0.0), (b, 0.0), (c, 1.0), (d, 0.0)] {
+            weights.insert(vertex_id, value);
+        }
+
+        let he_id = mesh_graph.halfedge_from_to(a, c).unwrap();
+        let segment_he_ids = mesh_graph.subdivide_edge_n(he_id, 4);
+
+        for (i, he_id) in segment_he_ids[..3].iter().enumerate() {
+            let vertex_id = mesh_graph.halfedges[*he_id].end_vertex;
+            let t = (i + 1) as f32 / 4.0;
+
+            assert_eq!(mesh_graph.positions[vertex_id], Vec3::new(t, 0.0, 0.0));
+            assert_eq!(
+                mesh_graph.uvs.as_ref().unwrap()[vertex_id],
+                Vec2::new(t, 0.0)
+            );
+            assert_eq!(mesh_graph.vertex_attribute(weight).unwrap()[vertex_id], t);
+        }
+    }
+
+    #[test]
+    fn test_subdivide_boundary_edge() {
+        // once from the side of the face and once from the boundary side
+        for (from, to) in [(0, 1), (1, 0)] {
+            let (mut mesh_graph, vertex_ids) = grid(3);
+
+            let vertex_count = mesh_graph.vertices.len();
+            let face_count = mesh_graph.faces.len();
+            let boundary_loops = mesh_graph.boundary_loops();
+            assert_eq!(boundary_loops.len(), 1);
+            let loop_len = boundary_loops[0].len();
+
+            let he_id = mesh_graph
+                .halfedge_from_to(vertex_ids[from], vertex_ids[to])
+                .unwrap();
+            let center_v = mesh_graph.subdivide_edge(he_id).unwrap().added_vertex;
+
+            assert_eq!(mesh_graph.positions[center_v], Vec3::new(0.5, 0.0, 0.0));
+            assert_eq!(mesh_graph.vertices.len(), vertex_count + 1);
+            assert_eq!(mesh_graph.faces.len(), face_count + 1);
+            assert!(mesh_graph.verify_twins().is_empty());
+
+            let boundary_loops = mesh_graph.boundary_loops();
+            assert_eq!(boundary_loops.len(), 1);
+            assert_eq!(boundary_loops[0].len(), loop_len + 1);
+
+            assert!(mesh_graph.vertices[center_v].is_boundary(&mesh_graph));
+            assert_eq!(mesh_graph.vertices[center_v].faces(&mesh_graph).count(), 2);
+
+            for he in mesh_graph.halfedges.values() {
+                if let Some(next_id) = he.next {
+                    let next = mesh_graph.halfedges[next_id];
+                    assert_eq!(next.face, he.face);
+                    assert_eq!(next.start_vertex(&mesh_graph), Some(he.end_vertex));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use glam::Vec3;
+use hashbrown::HashSet;
+use parry3d::bounding_volume::Aabb;
+
+use crate::{HalfedgeId, MeshGraph, VertexId, error_none, geometry::closest_point_on_segment};
+
+impl MeshGraph {
+    /// Finds T-junctions, i.e. vertices that lie within `tolerance` of the interior of an edge
+    /// they are not connected to. These cause cracks and shading artifacts.
+    ///
+    /// Returns every T-vertex together with the offending edge. Of the two halfedges of that edge
+    /// the boundary halfedge is returned if there is one.
+    ///
+    /// Candidate edges are found through the BVH so make sure it is up to date.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn find_t_vertices(&self, tolerance: f32) -> Vec<(VertexId, HalfedgeId)> {
+        let mut t_vertices = vec![];
+
+        for (vertex_id, pos) in &self.positions {
+            let aabb = Aabb::new(pos - Vec3::splat(tolerance), pos + Vec3::splat(tolerance));
+            let mut checked_edges = HashSet::new();
+
+            for index in self.bvh.intersect_aabb(&aabb) {
+                let Some(face) = self
+                    .index_to_face_id
+                    .get(&index)
+                    .and_then(|face_id| self.faces.get(*face_id))
+                else {
+                    continue;
+                };
+
+                if face.vertices(self).any(|v_id| v_id == vertex_id) {
+                    continue;
+                }
+
+                for he_id in face.halfedges(self) {
+                    let Some(he_id) = self.t_junction_edge(vertex_id, *pos, he_id, tolerance)
+                    else {
+                        continue;
+                    };
+
+                    if checked_edges.insert(he_id) {
+                        t_vertices.push((vertex_id, he_id));
+                    }
+                }
+            }
+        }
+
+        t_vertices
+    }
+
+    /// Removes the T-junctions found by [`MeshGraph::find_t_vertices`] by splitting the face
+    /// on the other side of the crack at the T-vertex. This welds the T-vertex into that face
+    /// and closes the crack.
+    ///
+    /// Only junctions where the offending edge is a boundary edge are fixed.
+    /// Returns the number of fixed T-junctions.
+    ///
+    /// This will schedule necessary updates to the BVH but you have to call
+    /// `refit_bvh()` after the operation.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn fix_t_vertices(&mut self, tolerance: f32) -> usize {
+        let mut fixed_count = 0;
+
+        // fixing a junction replaces the offending edge so the others have to be found again
+        for _ in 0..self.vertices.len() {
+            let mut fixed_any = false;
+
+            for (vertex_id, he_id) in self.find_t_vertices(tolerance) {
+                if self.split_face_at_t_vertex(vertex_id, he_id).is_some() {
+                    fixed_count += 1;
+                    fixed_any = true;
+                    break;
+                }
+            }
+
+            if !fixed_any {
+                break;
+            }
+
+            self.refit_bvh();
+        }
+
+        fixed_count
+    }
+
+    /// Replaces the face of the twin of the boundary halfedge `he_id` by two faces that share
+    /// the edge from `vertex_id` to the opposite vertex.
+    fn split_face_at_t_vertex(&mut self, vertex_id: VertexId, he_id: HalfedgeId) -> Option<()> {
+        let boundary_he = *self.halfedges.get(he_id)?;
+        if !boundary_he.is_boundary() {
+            return None;
+        }
+
+        let twin_id = boundary_he.twin.or_else(error_none!("Twin missing"))?;
+        let twin = *self
+            .halfedges
+            .get(twin_id)
+            .or_else(error_none!("Twin not found"))?;
+        let face_id = twin.face.or_else(error_none!("Twin has no face"))?;
+
+        let start_v_id = boundary_he.end_vertex;
+        let end_v_id = twin.end_vertex;
+        let opposite_v_id = twin.opposite_vertex(self)?;
+
+        let next_he_id = twin.next.or_else(error_none!("Next halfedge missing"))?;
+        let prev_he_id = twin
+            .prev(self)
+            .or_else(error_none!("Prev halfedge missing"))?;
+
+        // the crack on the other side has to be made of boundary halfedges (if any)
+        for (from, to) in [(start_v_id, vertex_id), (vertex_id, end_v_id)] {
+            if self
+                .halfedge_from_to(from, to)
+                .is_some_and(|he_id| !self.halfedges[he_id].is_boundary())
+            {
+                return None;
+            }
+        }
+
+        // detach the face
+        let face = self
+            .faces
+            .remove(face_id)
+            .or_else(error_none!("Face not found"))?;
+        self.bvh.remove(face.index);
+        let polygon_id = self.polygon_ids.remove(face_id);
+
+        for he_id in [next_he_id, prev_he_id] {
+            let he = &mut self.halfedges[he_id]; // part of the face
+            he.face = None;
+            he.next = None;
+        }
+
+        self.remove_only_halfedge_and_twin(twin_id);
+
+        for v_id in [start_v_id, end_v_id] {
+            let outgoing_he_id = self.outgoing_halfedges.get(v_id)?.first().copied();
+            self.vertices.get_mut(v_id)?.outgoing_halfedge = outgoing_he_id;
+        }
+
+        let face_ids = [
+            self.add_face_from_vertices(start_v_id, vertex_id, opposite_v_id)?
+                .face_id,
+            self.add_face_from_vertices(vertex_id, end_v_id, opposite_v_id)?
+                .face_id,
+        ];
+
+        if let Some(polygon_id) = polygon_id {
+            for face_id in face_ids {
+                self.polygon_ids.insert(face_id, polygon_id);
+            }
+        }
+
+        for v_id in [start_v_id, end_v_id, opposite_v_id, vertex_id] {
+            self.make_outgoing_halfedge_boundary_if_possible(v_id);
+        }
+
+        Some(())
+    }
+
+    /// Checks if `pos` lies within `tolerance` of the interior of the edge of `he_id` and returns
+    /// the boundary halfedge of that edge (if any) or `he_id` otherwise.
+    fn t_junction_edge(
+        &self,
+        vertex_id: VertexId,
+        pos: Vec3,
+        he_id: HalfedgeId,
+        tolerance: f32,
+    ) -> Option<HalfedgeId> {
+        let he = self
+            .halfedges
+            .get(he_id)
+            .or_else(error_none!("Halfedge not found"))?;
+        let start_v_id = he.start_vertex(self)?;
+
+        if start_v_id == vertex_id || he.end_vertex == vertex_id {
+            return None;
+        }
+
+        let start_pos = *self
+            .positions
+            .get(start_v_id)
+            .or_else(error_none!("Start position not found"))?;
+        let end_pos = *self
+            .positions
+            .get(he.end_vertex)
+            .or_else(error_none!("End position not found"))?;
+
+        let closest = closest_point_on_segment(pos, start_pos, end_pos);
+        if closest.distance_squared(pos) > tolerance * tolerance {
+            return None;
+        }
+
+        // the closest point has to be in the interior of the edge
+        if closest.distance(start_pos) <= tolerance || closest.distance(end_pos) <= tolerance {
+            return None;
+        }
+
+        Some(self.boundary_he(he_id).unwrap_or(he_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Triangle on top of two triangles that split its lower edge at `(1, 0, 0)`.
+    fn t_junction() -> MeshGraph {
+        MeshGraph::indexed_triangles(
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+            ],
+            &[0, 1, 2, 0, 3, 4, 4, 3, 1],
+        )
+    }
+
+    #[test]
+    fn test_find_t_vertices() {
+        let mesh_graph = t_junction();
+        let vertex_ids = mesh_graph.vertices.keys().collect::<Vec<_>>();
+
+        let t_vertices = mesh_graph.find_t_vertices(1e-4);
+
+        assert_eq!(t_vertices.len(), 1);
+
+        let (vertex_id, he_id) = t_vertices[0];
+        assert_eq!(vertex_id, vertex_ids[4]);
+
+        let he = mesh_graph.halfedges[he_id];
+        assert!(he.is_boundary());
+        let mut edge_vertex_ids = [he.start_vertex(&mesh_graph).unwrap(), he.end_vertex];
+        edge_vertex_ids.sort();
+        assert_eq!(edge_vertex_ids, [vertex_ids[0], vertex_ids[1]]);
+    }
+
+    #[test]
+    fn test_fix_t_vertices() {
+        let mut mesh_graph = t_junction();
+        let vertex_ids = mesh_graph.vertices.keys().collect::<Vec<_>>();
+
+        assert_eq!(mesh_graph.fix_t_vertices(1e-4), 1);
+        mesh_graph.refit_bvh();
+
+        assert_eq!(mesh_graph.faces.len(), 4);
+        assert!(mesh_graph.find_t_vertices(1e-4).is_empty());
+
+        let t_vertex = mesh_graph.vertices[vertex_ids[4]];
+        assert_eq!(t_vertex.outgoing_boundary_halfedge(&mesh_graph), None);
+        assert!(mesh_graph.halfedges.values().all(|he| {
+            he.twin
+                .is_some_and(|twin_id| mesh_graph.halfedges[twin_id].twin.is_some())
+        }));
+
+        // only the outline of the two triangles is left as boundary
+        assert_eq!(mesh_graph.boundary_loops().len(), 1);
+        assert_eq!(mesh_graph.boundary_loops()[0].len(), 4);
+    }
+}
@@ -1,6 +1,10 @@
-use glam::{Mat4, Quat};
+use crate::utils::error;
+use glam::{Mat4, Quat, Vec3};
+use hashbrown::HashSet;
+use itertools::Itertools;
+use slotmap::SecondaryMap;
 
-use crate::MeshGraph;
+use crate::{HalfedgeId, MeshGraph, VertexId};
 
 impl MeshGraph {
     /// Apply a quaternion rotation to the mesh graph (positions and normals).
@@ -45,4 +49,359 @@ impl MeshGraph {
             }
         }
     }
+
+    /// Transforms the whole mesh in place by `matrix`.
+    ///
+    /// Positions are transformed as points and vertex normals by the inverse transpose of
+    /// `matrix` (and renormalized). If `matrix` mirrors the mesh (negative determinant) the
+    /// orientation of all faces is flipped so that they stay counter-clockwise when looked at
+    /// from the outside.
+    ///
+    /// Cached face normals (if present) are recomputed and the BVH is refitted.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn transform(&mut self, matrix: Mat4) {
+        for pos in self.positions.values_mut() {
+            *pos = matrix.transform_point3(*pos);
+        }
+
+        if let Some(normals) = self.vertex_normals.as_mut() {
+            let normal_matrix = matrix.inverse().transpose();
+
+            for normal in normals.values_mut() {
+                *normal = normal_matrix.transform_vector3(*normal).normalize_or_zero();
+            }
+        }
+
+        if matrix.determinant() < 0.0 {
+            self.flip_orientation();
+        }
+
+        if self.face_normals.is_some() {
+            self.compute_face_normals();
+        }
+
+        for face in self.faces.values() {
+            self.bvh
+                .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
+        }
+        self.refit_bvh();
+    }
+
+    /// Reverses the direction of all halfedges which flips the orientation of all faces.
+    fn flip_orientation(&mut self) {
+        let start_vertices = self
+            .halfedges
+            .iter()
+            .filter_map(|(he_id, he)| Some((he_id, he.start_vertex(self)?)))
+            .collect::<SecondaryMap<HalfedgeId, VertexId>>();
+
+        // the next halfedge of a face becomes the previous one
+        let face_halfedges = self
+            .faces
+            .values()
+            .filter_map(|face| face.halfedges(self).collect_array::<3>())
+            .collect_vec();
+
+        for (he_id, start_v_id) in &start_vertices {
+            self.halfedges[he_id].end_vertex = *start_v_id;
+        }
+
+        for [he_a, he_b, he_c] in face_halfedges {
+            self.halfedges[he_a].next = Some(he_c);
+            self.halfedges[he_b].next = Some(he_a);
+            self.halfedges[he_c].next = Some(he_b);
+        }
+
+        // the former outgoing halfedges are incoming now
+        for vertex in self.vertices.values_mut() {
+            vertex.outgoing_halfedge = vertex
+                .outgoing_halfedge
+                .and_then(|he_id| self.halfedges.get(he_id)?.twin);
+        }
+
+        self.rebuild_outgoing_halfedges();
+        self.make_all_outgoing_halfedges_boundary_if_possible();
+    }
+
+    /// Returns all vertex positions as a dense array together with the map from vertex ids
+    /// to indices into that array.
+    ///
+    /// Useful to hand the geometry to numerical libraries. Write the results back with
+    /// [`MeshGraph::set_positions_from_slice`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn positions_as_vec(&self) -> (Vec<Vec3>, SecondaryMap<VertexId, usize>) {
+        let mut positions = Vec::with_capacity(self.positions.len());
+        let mut index_map = SecondaryMap::with_capacity(self.positions.len());
+
+        for (vertex_id, pos) in &self.positions {
+            index_map.insert(vertex_id, positions.len());
+            positions.push(*pos);
+        }
+
+        (positions, index_map)
+    }
+
+    /// Writes back positions that have been obtained by [`MeshGraph::positions_as_vec`].
+    ///
+    /// Vertex normals (if present) are recomputed and the BVH is refitted.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn set_positions_from_slice(
+        &mut self,
+        positions: &[Vec3],
+        index_map: &SecondaryMap<VertexId, usize>,
+    ) {
+        for (vertex_id, &index) in index_map {
+            let Some(&pos) = positions.get(index) else {
+                error!("Index {index} out of bounds for vertex {vertex_id:?}");
+                continue;
+            };
+
+            let Some(vertex_pos) = self.positions.get_mut(vertex_id) else {
+                error!("Position not found for vertex {vertex_id:?}");
+                continue;
+            };
+
+            *vertex_pos = pos;
+        }
+
+        if self.vertex_normals.is_some() {
+            self.compute_vertex_normals();
+        }
+
+        for face in self.faces.values() {
+            self.bvh
+                .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+            self.change_tracker.mark_face(face.id);
+        }
+        self.refit_bvh();
+    }
+
+    /// Replaces the positions of the given vertices in bulk.
+    ///
+    /// All vertex ids are validated first. If an unknown vertex id is encountered, nothing is
+    /// changed and that id is returned as the error. Otherwise vertex normals (if present) are
+    /// recomputed and the BVH is refitted once at the end.
+    ///
+    /// Prefer this over writing to `positions` directly, e.g. when writing back the results of
+    /// an external solver, because it keeps the BVH up to date.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn set_positions(
+        &mut self,
+        positions: impl IntoIterator<Item = (VertexId, Vec3)>,
+    ) -> Result<(), VertexId> {
+        let positions = positions.into_iter().collect::<Vec<_>>();
+
+        if let Some((vertex_id, _)) = positions
+            .iter()
+            .find(|(vertex_id, _)| !self.positions.contains_key(*vertex_id))
+        {
+            return Err(*vertex_id);
+        }
+
+        let mut affected_faces = HashSet::new();
+
+        for (vertex_id, pos) in positions {
+            self.positions[vertex_id] = pos;
+
+            if let Some(vertex) = self.vertices.get(vertex_id) {
+                affected_faces.extend(vertex.faces(self));
+            }
+        }
+
+        if self.vertex_normals.is_some() {
+            self.compute_vertex_normals();
+        }
+
+        for face_id in affected_faces {
+            if let Some(face) = self.faces.get(face_id) {
+                self.bvh
+                    .insert_or_update_partially(face.aabb(self), face.index, 0.0);
+                self.change_tracker.mark_face(face.id);
+            }
+        }
+        self.refit_bvh();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use glam::{UVec2, Vec2};
+    use parry3d::{
+        bounding_volume::BoundingVolume,
+        query::{Ray, RayCast},
+    };
+
+    use crate::primitives::{Cuboid, Grid, IcoSphere};
+
+    use super::*;
+
+    #[test]
+    fn test_positions_roundtrip() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 2,
+        });
+
+        let (mut positions, index_map) = mesh_graph.positions_as_vec();
+
+        assert_eq!(positions.len(), mesh_graph.vertices.len());
+
+        for pos in &mut positions {
+            *pos += Vec3::Z;
+        }
+
+        mesh_graph.set_positions_from_slice(&positions, &index_map);
+
+        for (vertex_id, &index) in &index_map {
+            assert_eq!(mesh_graph.positions[vertex_id], positions[index]);
+        }
+
+        // the BVH has to know about the moved faces
+        let (_, z) = mesh_graph.raycast_down(Vec2::ZERO, 10.0).unwrap();
+        assert!(z > 2.9);
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+        mesh_graph.compute_vertex_normals();
+
+        // stretch along x and rotate that axis onto y
+        let matrix = Mat4::from_rotation_z(FRAC_PI_2) * Mat4::from_scale(Vec3::new(3.0, 1.0, 1.0));
+        mesh_graph.transform(matrix);
+
+        let ray = Ray::new(Vec3::Y * 10.0, Vec3::NEG_Y);
+        let time_of_impact = mesh_graph.cast_local_ray(&ray, f32::MAX, true).unwrap();
+        assert!((time_of_impact - 7.0).abs() < 1e-4);
+
+        // the BVH has to know about the moved faces
+        let (_, z) = mesh_graph.raycast_down(Vec2::new(0.0, 2.5), 10.0).unwrap();
+        assert!(z > 0.0);
+        assert!(mesh_graph.raycast_down(Vec2::new(2.5, 0.0), 10.0).is_none());
+
+        for (vertex_id, normal) in mesh_graph.vertex_normals.as_ref().unwrap() {
+            let pos = mesh_graph.positions[vertex_id];
+
+            // gradient of the ellipsoid (x / 1)² + (y / 3)² + (z / 1)² = 1
+            let expected = Vec3::new(pos.x, pos.y / 9.0, pos.z).normalize();
+            assert!((normal.length() - 1.0).abs() < 1e-5);
+            assert!(normal.dot(expected) > 0.95);
+        }
+    }
+
+    #[test]
+    fn test_transform_mirrored() {
+        let mut cuboid = MeshGraph::from(Cuboid {
+            half_extents: Vec3::new(1.0, 2.0, 3.0),
+        });
+        cuboid.compute_face_normals();
+
+        let volume = cuboid.signed_volume();
+
+        cuboid.transform(Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0)));
+
+        assert!(cuboid.verify_twins().is_empty());
+        assert!((cuboid.signed_volume() - volume).abs() < 1e-4);
+
+        for face in cuboid.faces.values() {
+            let normal = face.normal(&cuboid).unwrap();
+            assert_eq!(Some(normal), face.compute_normal(&cuboid));
+            assert!(normal.dot(face.center(&cuboid)) > 0.0);
+        }
+
+        // the whole one-ring is reachable by rotating around the vertex
+        for (vertex_id, vertex) in &cuboid.vertices {
+            let count = cuboid.outgoing_halfedges[vertex_id].len();
+            assert_eq!(vertex.outgoing_halfedges(&cuboid).count(), count);
+            assert_eq!(vertex.faces(&cuboid).count(), count);
+        }
+
+        let mut grid = MeshGraph::from(Grid {
+            size: Vec2::ONE,
+            subdivisions: UVec2::splat(3),
+        });
+
+        grid.transform(Mat4::from_scale(Vec3::new(1.0, -1.0, 1.0)));
+
+        assert!(grid.verify_twins().is_empty());
+        assert_eq!(grid.boundary_loops().len(), 1);
+        assert_eq!(grid.boundary_loops()[0].len(), 12);
+
+        for face in grid.faces.values() {
+            assert!(face.normal(&grid).unwrap().y < 0.0);
+        }
+
+        for vertex in grid.vertices.values() {
+            assert_eq!(
+                vertex.outgoing_halfedges(&grid).count(),
+                vertex.faces(&grid).count() + vertex.is_boundary(&grid) as usize
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_positions() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 2,
+        });
+
+        let aabb = |mesh_graph: &MeshGraph| {
+            mesh_graph
+                .faces
+                .values()
+                .map(|face| face.aabb(mesh_graph))
+                .reduce(|a, b| a.merged(&b))
+                .unwrap()
+        };
+
+        let aabb_before = aabb(&mesh_graph);
+
+        let offset = Vec3::new(10.0, 0.0, 3.0);
+        let translated = mesh_graph
+            .positions
+            .iter()
+            .map(|(vertex_id, pos)| (vertex_id, *pos + offset))
+            .collect::<Vec<_>>();
+
+        assert_eq!(mesh_graph.set_positions(translated), Ok(()));
+
+        let aabb_after = aabb(&mesh_graph);
+        assert!((aabb_after.mins - (aabb_before.mins + offset)).length() < 1e-5);
+        assert!((aabb_after.maxs - (aabb_before.maxs + offset)).length() < 1e-5);
+
+        // the BVH has to know about the moved faces
+        assert!(mesh_graph.raycast_down(Vec2::ZERO, 10.0).is_none());
+        let (_, z) = mesh_graph.raycast_down(Vec2::new(10.0, 0.0), 10.0).unwrap();
+        assert!(z > 4.9 && z <= 5.0 + 1e-5);
+    }
+
+    #[test]
+    fn test_set_positions_unknown_vertex() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.0,
+            subdivisions: 1,
+        });
+
+        let vertex_id = mesh_graph.vertices.keys().next().unwrap();
+        let unknown_vertex_id = mesh_graph.add_vertex(Vec3::ZERO);
+        mesh_graph.vertices.remove(unknown_vertex_id);
+        mesh_graph.positions.remove(unknown_vertex_id);
+
+        let pos = mesh_graph.positions[vertex_id];
+
+        assert_eq!(
+            mesh_graph.set_positions([(vertex_id, Vec3::ONE), (unknown_vertex_id, Vec3::ONE)]),
+            Err(unknown_vertex_id)
+        );
+        assert_eq!(mesh_graph.positions[vertex_id], pos);
+    }
 }
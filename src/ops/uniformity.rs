@@ -0,0 +1,99 @@
+use itertools::Itertools;
+
+use crate::MeshGraph;
+
+impl MeshGraph {
+    /// Rates how evenly the vertices are distributed over the surface between `0` and `1`.
+    ///
+    /// For every vertex the mean length of its edges is computed. The score is
+    /// `1 / (1 + cv)` where `cv` is the coefficient of variation (standard deviation divided by
+    /// the mean) of these lengths. A perfectly uniform mesh scores `1` and the score decreases
+    /// the more the local edge lengths vary. This helps to decide whether a mesh needs to be
+    /// remeshed.
+    ///
+    /// Isolated vertices are ignored. A mesh without edges scores `1`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn sampling_uniformity(&self) -> f32 {
+        let mean_edge_lengths = self
+            .vertices
+            .iter()
+            .filter_map(|(vertex_id, vertex)| {
+                let pos = self.positions.get(vertex_id)?;
+
+                let lengths = vertex
+                    .neighbours(self)
+                    .filter_map(|neighbour_id| {
+                        Some(pos.distance(*self.positions.get(neighbour_id)?))
+                    })
+                    .collect_vec();
+
+                if lengths.is_empty() {
+                    return None;
+                }
+
+                Some(lengths.iter().sum::<f32>() / lengths.len() as f32)
+            })
+            .collect_vec();
+
+        if mean_edge_lengths.is_empty() {
+            return 1.0;
+        }
+
+        let count = mean_edge_lengths.len() as f32;
+        let mean = mean_edge_lengths.iter().sum::<f32>() / count;
+
+        if mean <= 0.0 {
+            return 1.0;
+        }
+
+        let variance = mean_edge_lengths
+            .iter()
+            .map(|length| (length - mean).powi(2))
+            .sum::<f32>()
+            / count;
+
+        1.0 / (1.0 + variance.sqrt() / mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_sampling_uniformity() {
+        let uniform = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        let uniform_score = uniform.sampling_uniformity();
+        assert!(uniform_score > 0.9);
+        assert!(uniform_score <= 1.0);
+
+        // refine the region around the north pole a lot
+        let mut refined = uniform.clone();
+        let pole = Vec3::Y;
+
+        for _ in 0..100 {
+            let (he_id, _) = refined
+                .halfedges
+                .iter()
+                .filter(|(_, he)| refined.positions[he.end_vertex].distance(pole) < 0.4)
+                .max_by(|(_, a), (_, b)| a.length(&refined).total_cmp(&b.length(&refined)))
+                .unwrap();
+
+            refined.subdivide_edge(he_id);
+        }
+
+        let refined_score = refined.sampling_uniformity();
+        assert!(refined_score < uniform_score - 0.1);
+        assert!(refined_score > 0.0);
+
+        assert_eq!(MeshGraph::new().sampling_uniformity(), 1.0);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{FaceId, HalfedgeId, MeshGraph};
+
+impl MeshGraph {
+    /// Returns all halfedges whose twin relationship is broken.
+    ///
+    /// A halfedge is reported if it has no twin, if its twin doesn't exist, if the twin of its
+    /// twin isn't the halfedge itself or if the twin doesn't point in the reverse direction.
+    /// Useful for debugging operations that rewire halfedges.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn verify_twins(&self) -> Vec<HalfedgeId> {
+        self.halfedges
+            .iter()
+            .filter(|(he_id, he)| {
+                let Some(twin) = he.twin.and_then(|twin_id| self.halfedges.get(twin_id)) else {
+                    return true;
+                };
+
+                // The start vertex of a halfedge is the end vertex of its twin. So if the twins
+                // point to each other, they are reversed iff their end vertices differ.
+                twin.twin != Some(*he_id) || twin.end_vertex == he.end_vertex
+            })
+            .map(|(he_id, _)| he_id)
+            .collect()
+    }
+
+    /// Returns all faces whose BVH index doesn't map back to them.
+    ///
+    /// A face is reported if its `id` is not its key in `faces`, if its `index` isn't
+    /// registered in [`MeshGraph::index_to_face_id`] for this face or if the index hasn't been
+    /// handed out yet according to [`MeshGraph::next_index`]. Such faces make spatial queries
+    /// return wrong faces. Use [`MeshGraph::repair_bvh_consistency`] to fix them.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn verify_bvh_consistency(&self) -> Vec<FaceId> {
+        self.faces
+            .iter()
+            .filter(|(face_id, face)| {
+                face.id != *face_id
+                    || face.index >= self.next_index
+                    || self.index_to_face_id.get(&face.index) != Some(face_id)
+            })
+            .map(|(face_id, _)| face_id)
+            .collect()
+    }
+
+    /// Reassigns the BVH indices of all faces, rebuilds [`MeshGraph::index_to_face_id`] and
+    /// then the BVH from scratch.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn repair_bvh_consistency(&mut self) {
+        self.index_to_face_id.clear();
+        self.next_index = 0;
+
+        for (face_id, face) in &mut self.faces {
+            face.id = face_id;
+            face.index = self.next_index;
+            self.next_index += 1;
+
+            self.index_to_face_id.insert(face.index, face_id);
+        }
+
+        self.rebuild_bvh();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_twins() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        let he_id1 = mesh_graph.halfedges.keys().next().unwrap();
+        let twin_id1 = mesh_graph.halfedges[he_id1].twin.unwrap();
+        let he_id2 = mesh_graph
+            .halfedges
+            .keys()
+            .find(|he_id| ![he_id1, twin_id1].contains(he_id))
+            .unwrap();
+
+        // point to a halfedge that doesn't point back
+        mesh_graph.halfedges[he_id1].twin = Some(he_id2);
+
+        let mut broken = mesh_graph.verify_twins();
+        broken.sort();
+        let mut expected = vec![he_id1, twin_id1];
+        expected.sort();
+        assert_eq!(broken, expected);
+
+        mesh_graph.halfedges[he_id1].twin = None;
+        assert_eq!(mesh_graph.verify_twins().len(), 2);
+
+        mesh_graph.halfedges[he_id1].twin = Some(twin_id1);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        // twin in the same direction
+        let end_vertex = mesh_graph.halfedges[he_id1].end_vertex;
+        mesh_graph.halfedges[twin_id1].end_vertex = end_vertex;
+        assert_eq!(mesh_graph.verify_twins().len(), 2);
+    }
+
+    #[test]
+    fn test_verify_bvh_consistency() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        assert!(mesh_graph.verify_bvh_consistency().is_empty());
+
+        let he_id = mesh_graph.halfedges.keys().next().unwrap();
+        mesh_graph.collapse_edge(he_id);
+        mesh_graph.refit_bvh();
+
+        assert!(mesh_graph.verify_bvh_consistency().is_empty());
+
+        // two faces with the same index
+        let (face_id1, face_id2) = mesh_graph.faces.keys().next_tuple().unwrap();
+        mesh_graph.faces[face_id2].index = mesh_graph.faces[face_id1].index;
+
+        assert_eq!(mesh_graph.verify_bvh_consistency(), vec![face_id2]);
+
+        // an index that was never handed out
+        mesh_graph.faces[face_id1].index = mesh_graph.next_index;
+        assert_eq!(mesh_graph.verify_bvh_consistency().len(), 2);
+
+        mesh_graph.repair_bvh_consistency();
+
+        assert!(mesh_graph.verify_bvh_consistency().is_empty());
+        assert_eq!(mesh_graph.next_index as usize, mesh_graph.faces.len());
+        assert_eq!(
+            mesh_graph.faces_in_bvh_order().count(),
+            mesh_graph.faces.len()
+        );
+    }
+}
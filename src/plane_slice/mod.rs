@@ -1,5 +1,6 @@
 mod hash_grid;
 mod polygon;
+mod regions;
 
 #[cfg(feature = "rerun")]
 use std::iter::repeat_n;
@@ -7,17 +8,60 @@ use std::iter::repeat_n;
 use glam::{Mat4, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles};
 pub use hash_grid::*;
 pub use polygon::*;
+pub use regions::*;
 use slotmap::SecondaryMap;
 
 #[cfg(feature = "rerun")]
 use crate::utils::vec3_array;
 use crate::{MeshGraph, VertexId};
 
+/// Slices the mesh with the plane defined by `plane_normal` and `plane_constant` and returns
+/// the resulting contours.
+///
+/// The slicing is done in a 2D frame inside the plane which is computed by
+/// [`compute_transform_from_plane_into_xy`]. Use [`plane_slice_with_up_hint`] when slicing a
+/// sequence of planes to get contours in a consistent orientation.
 pub fn plane_slice(
     mesh_graph: &MeshGraph,
     plane_normal: Vec3,
     plane_constant: f32,
 ) -> impl Iterator<Item = Polygon3> {
+    plane_slice_3d(mesh_graph, plane_normal, plane_constant, None)
+}
+
+/// Same as [`plane_slice`] but the 2D frame is computed by
+/// [`compute_transform_from_plane_into_xy_with_up_hint`]. Pass the same `up_hint` when slicing
+/// a sequence of planes to get contours in a consistent orientation.
+pub fn plane_slice_with_up_hint(
+    mesh_graph: &MeshGraph,
+    plane_normal: Vec3,
+    plane_constant: f32,
+    up_hint: Vec3,
+) -> impl Iterator<Item = Polygon3> {
+    plane_slice_3d(mesh_graph, plane_normal, plane_constant, Some(up_hint))
+}
+
+fn plane_slice_3d(
+    mesh_graph: &MeshGraph,
+    plane_normal: Vec3,
+    plane_constant: f32,
+    up_hint: Option<Vec3>,
+) -> impl Iterator<Item = Polygon3> {
+    let (polygons, transform) = plane_slice_2d(mesh_graph, plane_normal, plane_constant, up_hint);
+
+    let transform_inv = transform.inverse();
+
+    polygons.map(move |p| Polygon3::from_polygon2_with_transform(p, transform_inv))
+}
+
+/// Same as [`plane_slice`] but returns the contours in the 2D frame of the plane together with
+/// the transform into this frame.
+fn plane_slice_2d(
+    mesh_graph: &MeshGraph,
+    plane_normal: Vec3,
+    plane_constant: f32,
+    up_hint: Option<Vec3>,
+) -> (impl Iterator<Item = Polygon2>, Mat4) {
     let plane_normal = plane_normal.normalize();
 
     #[cfg(feature = "rerun")]
@@ -58,7 +102,7 @@ pub fn plane_slice(
             .unwrap();
     }
 
-    let transform = compute_transform_from_plane_into_xy(plane_normal, plane_constant);
+    let transform = transform_from_plane_into_xy(plane_normal, plane_constant, up_hint);
 
     let mut transformed_positions = SecondaryMap::new();
     let mut min_bounds = Vec2::splat(f32::INFINITY);
@@ -100,11 +144,7 @@ pub fn plane_slice(
         }
     }
 
-    let transform_inv = transform.inverse();
-
-    hash_grid
-        .into_polygons()
-        .map(move |p| Polygon3::from_polygon2_with_transform(p, transform_inv))
+    (hash_grid.into_polygons(), transform)
 }
 
 fn intersect_triangle_with_xy_plane(
@@ -154,14 +194,43 @@ fn intersect_triangle_with_xy_plane(
     }
 }
 
+/// Computes the transform that maps the plane defined by `plane_normal` and `plane_constant`
+/// onto the XY plane with the plane normal becoming the Z-axis.
+///
+/// The X-axis of the 2D frame is picked heuristically which can flip the frame between similar
+/// planes. Use [`compute_transform_from_plane_into_xy_with_up_hint`] to avoid that.
 pub fn compute_transform_from_plane_into_xy(plane_normal: Vec3, plane_constant: f32) -> Mat4 {
+    transform_from_plane_into_xy(plane_normal, plane_constant, None)
+}
+
+/// Same as [`compute_transform_from_plane_into_xy`] but the projection of `up_hint` onto the
+/// plane becomes the X-axis of the 2D frame. This way a sequence of planes transformed with the
+/// same hint results in consistently oriented 2D frames.
+///
+/// If `up_hint` is (nearly) parallel to the plane normal, the X-axis is picked heuristically.
+pub fn compute_transform_from_plane_into_xy_with_up_hint(
+    plane_normal: Vec3,
+    plane_constant: f32,
+    up_hint: Vec3,
+) -> Mat4 {
+    transform_from_plane_into_xy(plane_normal, plane_constant, Some(up_hint))
+}
+
+fn transform_from_plane_into_xy(
+    plane_normal: Vec3,
+    plane_constant: f32,
+    up_hint: Option<Vec3>,
+) -> Mat4 {
     // Create an orthonormal basis where n is the Z-axis
     // Find a vector perpendicular to n
-    let up = if plane_normal.x.abs() < 0.9 {
-        Vec3::X
-    } else {
-        Vec3::Y
-    };
+    let up = up_hint
+        .and_then(|up_hint| up_hint.try_normalize())
+        .filter(|up_hint| up_hint.dot(plane_normal).abs() < 0.999)
+        .unwrap_or(if plane_normal.x.abs() < 0.9 {
+            Vec3::X
+        } else {
+            Vec3::Y
+        });
 
     // Create two perpendicular vectors in the plane
     let u = plane_normal.cross(up).normalize(); // First tangent vector
@@ -199,6 +268,7 @@ pub fn compute_transform_from_plane_into_xy(plane_normal: Vec3, plane_constant:
 #[cfg(test)]
 mod tests {
     use glam::Vec4;
+    use itertools::Itertools;
 
     use crate::primitives::IcoSphere;
 
@@ -234,6 +304,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compute_transform_from_plane_into_xy_with_up_hint() {
+        // the heuristic picks different vectors for these two nearly parallel planes
+        let plane_normal1 = Vec3::new(1.0, 0.45, 0.0).normalize();
+        let plane_normal2 = Vec3::new(1.0, 0.5, 0.0).normalize();
+
+        let x_axis = |transform: Mat4| transform.transform_vector3(Vec3::Z).xy();
+
+        let transform1 = compute_transform_from_plane_into_xy(plane_normal1, 0.0);
+        let transform2 = compute_transform_from_plane_into_xy(plane_normal2, 0.0);
+        assert!(x_axis(transform1).dot(x_axis(transform2)) < 0.5);
+
+        let transform1 =
+            compute_transform_from_plane_into_xy_with_up_hint(plane_normal1, 0.0, Vec3::Z);
+        let transform2 =
+            compute_transform_from_plane_into_xy_with_up_hint(plane_normal2, 0.0, Vec3::Z);
+        assert!(x_axis(transform1).abs_diff_eq(Vec2::X, 1e-5));
+        assert!(x_axis(transform2).abs_diff_eq(Vec2::X, 1e-5));
+
+        // a hint parallel to the normal falls back to the heuristic
+        assert_eq!(
+            compute_transform_from_plane_into_xy_with_up_hint(plane_normal1, 0.0, plane_normal1),
+            compute_transform_from_plane_into_xy(plane_normal1, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_plane_slice_with_up_hint() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 2.5,
+            subdivisions: 3,
+        });
+
+        let up_hint = Vec3::Z;
+
+        let frames = [
+            Vec3::new(1.0, 0.45, 0.0).normalize(),
+            Vec3::new(1.0, 0.5, 0.0).normalize(),
+        ]
+        .map(|plane_normal| {
+            let transform =
+                compute_transform_from_plane_into_xy_with_up_hint(plane_normal, 0.5, up_hint);
+
+            let polygons =
+                plane_slice_with_up_hint(&mesh_graph, plane_normal, 0.5, up_hint).collect_vec();
+            assert_eq!(polygons.len(), 1);
+
+            let points = polygons[0]
+                .vertices
+                .iter()
+                .map(|p| transform.transform_point3(*p).xy())
+                .collect_vec();
+
+            let signed_area: f32 = points
+                .iter()
+                .circular_tuple_windows()
+                .map(|(a, b)| a.perp_dot(*b))
+                .sum();
+
+            let topmost = points
+                .iter()
+                .copied()
+                .max_by(|a, b| a.x.total_cmp(&b.x))
+                .unwrap();
+
+            (signed_area.signum(), topmost)
+        });
+
+        assert_eq!(frames[0].0, frames[1].0);
+        // the highest point of the sphere contours is mapped to the same 2D direction
+        assert!(frames[0].1.normalize().abs_diff_eq(Vec2::X, 5e-2));
+        assert!(frames[1].1.normalize().abs_diff_eq(Vec2::X, 5e-2));
+    }
+
     #[test]
     fn test_intersect_triangle_with_xy_plane() {
         let mesh_graph = MeshGraph::from(IcoSphere {
@@ -129,6 +129,165 @@ impl Polygon2 {
 
         inside
     }
+
+    /// The area enclosed by the polygon. It is positive if the polygon is counter-clockwise
+    /// and negative if it is clockwise.
+    ///
+    /// The polygon is treated as closed even if the last vertex isn't a copy of the first one.
+    pub fn signed_area(&self) -> f32 {
+        self.vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| a.perp_dot(*b))
+            .sum::<f32>()
+            * 0.5
+    }
+
+    /// The number of times the polygon winds around `point`.
+    ///
+    /// Counter-clockwise turns count positive and clockwise turns negative. Points outside the
+    /// polygon have a winding number of `0`. For nested contours from a slice this tells holes
+    /// (opposite winding of the surrounding contour) from solid regions.
+    ///
+    /// The polygon is treated as closed even if the last vertex isn't a copy of the first one.
+    pub fn winding_number(&self, point: Vec2) -> i32 {
+        // Sunday's algorithm that counts signed crossings of a horizontal ray
+        self.vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| {
+                let side = (*b - *a).perp_dot(point - *a);
+
+                if a.y <= point.y {
+                    if b.y > point.y && side > 0.0 { 1 } else { 0 }
+                } else if b.y <= point.y && side < 0.0 {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Wether a point is inside the polygon according to the non-zero winding rule
+    /// (see [`Polygon2::winding_number`]).
+    ///
+    /// In contrast to [`Polygon2::contains_point`] the polygon doesn't have to be closed and
+    /// self overlapping regions count as inside.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.winding_number(point) != 0
+    }
+}
+
+/// Triangulates a simple polygon by ear clipping.
+///
+/// Returns the triangles as indices into `polygon.vertices`. Every triangle has the same winding
+/// as the polygon itself. If the polygon is closed the duplicated last vertex is ignored.
+pub fn triangulate_polygon(polygon: &Polygon2) -> Vec<[usize; 3]> {
+    let vertices = &polygon.vertices;
+
+    let mut len = vertices.len();
+    if polygon.is_closed() {
+        len -= 1;
+    }
+
+    if len < 3 {
+        return vec![];
+    }
+
+    let orientation = (0..len)
+        .map(|i| vertices[i].perp_dot(vertices[(i + 1) % len]))
+        .sum::<f32>()
+        .signum();
+
+    let is_convex = |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - b) * orientation > 0.0;
+
+    // Points on the border of the triangle count as inside. Otherwise a diagonal could run
+    // through a reflex vertex.
+    let is_inside = |p: Vec2, a: Vec2, b: Vec2, c: Vec2| {
+        p != a
+            && p != b
+            && p != c
+            && (b - a).perp_dot(p - a) * orientation >= 0.0
+            && (c - b).perp_dot(p - b) * orientation >= 0.0
+            && (a - c).perp_dot(p - c) * orientation >= 0.0
+    };
+
+    let mut remaining = (0..len).collect_vec();
+    let mut triangles = Vec::with_capacity(len - 2);
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            ]
+        };
+
+        let ear = (0..count).find(|&i| {
+            let [a, b, c] = corner(i);
+
+            is_convex(vertices[a], vertices[b], vertices[c])
+                && !remaining.iter().any(|&p| {
+                    p != a
+                        && p != b
+                        && p != c
+                        && is_inside(vertices[p], vertices[a], vertices[b], vertices[c])
+                })
+        });
+
+        // Only degenerate or self intersecting polygons have no ear. Clip anyway to terminate.
+        let ear = ear.unwrap_or(0);
+
+        triangles.push(corner(ear));
+        remaining.remove(ear);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    triangles
+}
+
+/// Projects the points of a (not necessarily planar) closed polygon onto its best-fit plane.
+///
+/// The plane normal is computed with Newell's method so the winding of the returned polygon
+/// is always counter-clockwise. Returns `None` for degenerate polygons.
+pub(crate) fn project_onto_best_fit_plane(points: &[Vec3]) -> Option<Polygon2> {
+    let normal = newell_normal(points).try_normalize()?;
+
+    let center = points.iter().sum::<Vec3>() / points.len() as f32;
+    let u = normal.any_orthonormal_vector();
+    let v = normal.cross(u);
+
+    Some(Polygon2 {
+        vertices: points
+            .iter()
+            .map(|p| {
+                let d = *p - center;
+                Vec2::new(d.dot(u), d.dot(v))
+            })
+            .collect(),
+    })
+}
+
+/// Computes the normal of a (not necessarily planar) closed polygon with Newell's method.
+///
+/// The result is not normalized. Its length is twice the area of the polygon projected onto
+/// the plane perpendicular to it.
+pub(crate) fn newell_normal(points: &[Vec3]) -> Vec3 {
+    points
+        .iter()
+        .circular_tuple_windows()
+        .map(|(a, b)| {
+            Vec3::new(
+                (a.y - b.y) * (a.z + b.z),
+                (a.z - b.z) * (a.x + b.x),
+                (a.x - b.x) * (a.y + b.y),
+            )
+        })
+        .sum()
 }
 
 impl Polygon3 {
@@ -169,3 +328,87 @@ impl Polygon3 {
         .unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triangulate_concave_polygon() {
+        // L-shape in counter-clockwise order
+        let polygon = Polygon2 {
+            vertices: [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, 2.0),
+                Vec2::new(0.0, 2.0),
+                Vec2::new(0.0, 0.0),
+            ]
+            .into(),
+        };
+
+        let triangles = triangulate_polygon(&polygon);
+
+        assert_eq!(triangles.len(), 4);
+
+        let mut area = 0.0;
+        for [a, b, c] in triangles {
+            let (a, b, c) = (
+                polygon.vertices[a],
+                polygon.vertices[b],
+                polygon.vertices[c],
+            );
+            let doubled_area = (b - a).perp_dot(c - a);
+
+            assert!(doubled_area > 0.0);
+            area += doubled_area * 0.5;
+        }
+
+        assert!((area - 3.0).abs() < 1e-6);
+    }
+
+    fn square(center: Vec2, half_size: f32) -> Polygon2 {
+        Polygon2 {
+            vertices: [
+                Vec2::new(-1.0, -1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(-1.0, 1.0),
+            ]
+            .into_iter()
+            .map(|corner| center + corner * half_size)
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn test_winding_number() {
+        let center = Vec2::new(3.0, -2.0);
+        let outer = square(center, 2.0);
+        let inner = square(center, 1.0);
+
+        let inner_centroid = inner.vertices.iter().sum::<Vec2>() / inner.vertices.len() as f32;
+        assert_eq!(inner.winding_number(inner_centroid), 1);
+        assert_eq!(outer.winding_number(inner_centroid), 1);
+
+        // the outer contour contains the inner one but not the other way around
+        assert!(inner.vertices.iter().all(|v| outer.contains(*v)));
+        assert!(!outer.vertices.iter().any(|v| inner.contains(*v)));
+
+        // between the contours
+        let ring_point = center + Vec2::new(1.5, 0.0);
+        assert!(outer.contains(ring_point));
+        assert!(!inner.contains(ring_point));
+        assert_eq!(outer.winding_number(center + Vec2::new(5.0, 0.0)), 0);
+
+        // clockwise and explicitly closed
+        let mut hole = inner.clone();
+        hole.vertices.make_contiguous().reverse();
+        hole.close();
+        assert_eq!(hole.winding_number(inner_centroid), -1);
+        assert!(hole.contains(inner_centroid));
+        assert!(hole.contains_point(inner_centroid));
+    }
+}
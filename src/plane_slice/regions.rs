@@ -0,0 +1,174 @@
+use glam::Vec3;
+use itertools::Itertools;
+
+use crate::{MeshGraph, Polygon3};
+
+use super::plane_slice_2d;
+
+/// A filled region of a plane slice (see [`MeshGraph::slice_regions`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceRegion {
+    /// The contour around the region
+    pub outer: Polygon3,
+    /// The contours of the holes inside of the region
+    pub holes: Vec<Polygon3>,
+}
+
+impl MeshGraph {
+    /// Slices the mesh like [`crate::plane_slice`] and groups the closed contours into filled
+    /// regions.
+    ///
+    /// Contours are nested by containment: a contour that is inside of an even number of other
+    /// contours is the outer contour of a region, one that is inside of an odd number is a hole
+    /// of the smallest contour around it. This way a solid inside of a hole of a hollow object
+    /// becomes a region of its own.
+    ///
+    /// Seen from the side the plane normal points to, outer contours run counter-clockwise and
+    /// holes clockwise. Contours that aren't closed, e.g. from slicing open meshes, are skipped.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn slice_regions(&self, normal: Vec3, constant: f32) -> Vec<SliceRegion> {
+        let (polygons, transform) = plane_slice_2d(self, normal, constant, None);
+
+        let polygons = polygons
+            .filter(|polygon| polygon.vertices.len() > 3 && polygon.is_closed())
+            .collect_vec();
+        let areas = polygons
+            .iter()
+            .map(|polygon| polygon.signed_area().abs())
+            .collect_vec();
+
+        // the contours that contain each contour
+        let containers = polygons
+            .iter()
+            .enumerate()
+            .map(|(i, polygon)| {
+                (0..polygons.len())
+                    .filter(|&j| j != i && areas[j] > areas[i])
+                    .filter(|&j| polygons[j].contains(polygon.vertices[0]))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let transform_inv = transform.inverse();
+        let to_polygon3 = |i: usize, counter_clockwise: bool| {
+            let mut polygon = polygons[i].clone();
+
+            if (polygon.signed_area() > 0.0) != counter_clockwise {
+                polygon.vertices.make_contiguous().reverse();
+            }
+
+            Polygon3::from_polygon2_with_transform(polygon, transform_inv)
+        };
+
+        let mut region_indices = vec![None; polygons.len()];
+        let mut regions = vec![];
+
+        for (i, polygon_containers) in containers.iter().enumerate() {
+            if polygon_containers.len() % 2 == 0 {
+                region_indices[i] = Some(regions.len());
+                regions.push(SliceRegion {
+                    outer: to_polygon3(i, true),
+                    holes: vec![],
+                });
+            }
+        }
+
+        for (i, polygon_containers) in containers.iter().enumerate() {
+            if polygon_containers.len() % 2 == 1 {
+                let parent = polygon_containers
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| areas[*a].total_cmp(&areas[*b]));
+
+                if let Some(region_idx) = parent.and_then(|parent| region_indices[parent]) {
+                    regions[region_idx].holes.push(to_polygon3(i, false));
+                }
+            }
+        }
+
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use glam::Vec2;
+
+    use crate::Polygon2;
+
+    use super::*;
+
+    /// Tube around the Z-axis between the radii 1 and 2 and the heights -1 and 1
+    fn thick_walled_tube(segments: usize) -> MeshGraph {
+        let profile = [
+            Vec2::new(2.0, -1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+        ];
+
+        let positions = (0..segments)
+            .cartesian_product(profile)
+            .map(|(i, p)| {
+                let angle = i as f32 / segments as f32 * TAU;
+                Vec3::new(p.x * angle.cos(), p.x * angle.sin(), p.y)
+            })
+            .collect_vec();
+
+        let index = |i: usize, j: usize| (i % segments) * profile.len() + j % profile.len();
+        let indices = (0..segments)
+            .cartesian_product(0..profile.len())
+            .flat_map(|(i, j)| {
+                [
+                    index(i, j),
+                    index(i + 1, j),
+                    index(i + 1, j + 1),
+                    index(i, j),
+                    index(i + 1, j + 1),
+                    index(i, j + 1),
+                ]
+            })
+            .collect_vec();
+
+        MeshGraph::indexed_triangles(&positions, &indices)
+    }
+
+    #[test]
+    fn test_slice_regions_of_tube() {
+        let mesh_graph = thick_walled_tube(16);
+
+        let regions = mesh_graph.slice_regions(Vec3::Z, 0.2);
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].holes.len(), 1);
+
+        let radius = |polygon: &Polygon3| {
+            polygon
+                .vertices
+                .iter()
+                .map(|v| v.truncate().length())
+                .fold(0.0, f32::max)
+        };
+        let area_around_z = |polygon: &Polygon3| {
+            Polygon2 {
+                vertices: polygon.vertices.iter().map(|v| v.truncate()).collect(),
+            }
+            .signed_area()
+        };
+
+        let outer = &regions[0].outer;
+        let hole = &regions[0].holes[0];
+
+        assert!((radius(outer) - 2.0).abs() < 1e-4);
+        assert!((radius(hole) - 1.0).abs() < 1e-4);
+        assert!(outer.vertices.iter().all(|v| (v.z - 0.2).abs() < 1e-5));
+
+        assert!(area_around_z(outer) > 0.0);
+        assert!(area_around_z(hole) < 0.0);
+
+        // the plane doesn't hit the tube
+        assert!(mesh_graph.slice_regions(Vec3::Z, 3.0).is_empty());
+    }
+}
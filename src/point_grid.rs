@@ -0,0 +1,57 @@
+use glam::{I64Vec3, Vec3};
+use hashbrown::HashMap;
+
+/// Spatial hash of points for fast lookups of nearby points.
+///
+/// Points are sorted into cubic cells of `cell_size`. Queries with a radius of at most
+/// `cell_size` only have to check the 27 cells around the query point.
+///
+/// Cell indices are 64 bit so that points far away from the origin still get distinct cells
+/// even for tiny cell sizes.
+pub(crate) struct PointGrid<T> {
+    cell_size: f32,
+    cells: HashMap<I64Vec3, Vec<(Vec3, T)>>,
+}
+
+impl<T: Copy> PointGrid<T> {
+    /// Creates an empty grid. `cell_size` should be the largest radius that is queried.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            // keeps the cell indices in range for a zero radius
+            cell_size: cell_size.max(1e-6),
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, pos: Vec3, value: T) {
+        self.cells
+            .entry(self.cell(pos))
+            .or_default()
+            .push((pos, value));
+    }
+
+    /// Iterates over all points that are within `radius` of `pos`.
+    /// `radius` must not be larger than the cell size.
+    pub fn within(&self, pos: Vec3, radius: f32) -> impl Iterator<Item = (Vec3, T)> {
+        let center = self.cell(pos);
+        let radius_squared = radius * radius;
+
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| I64Vec3::new(x, y, z))))
+            .filter_map(move |offset| self.cells.get(&center.saturating_add(offset)))
+            .flatten()
+            .filter(move |(point, _)| point.distance_squared(pos) <= radius_squared)
+            .copied()
+    }
+
+    /// Returns the point that is closest to `pos` but at most `radius` away.
+    /// `radius` must not be larger than the cell size.
+    pub fn closest_within(&self, pos: Vec3, radius: f32) -> Option<(Vec3, T)> {
+        self.within(pos, radius)
+            .min_by(|(a, _), (b, _)| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+    }
+
+    fn cell(&self, pos: Vec3) -> I64Vec3 {
+        (pos / self.cell_size).floor().as_i64vec3()
+    }
+}
@@ -1,4 +1,6 @@
-use glam::{Vec3, vec3};
+use std::f32::consts::{PI, TAU};
+
+use glam::{UVec2, Vec2, Vec3, vec2, vec3};
 use itertools::Itertools;
 
 use crate::MeshGraph;
@@ -18,6 +20,43 @@ pub struct IcoSphere {
     pub subdivisions: u8,
 }
 
+/// Sphere made of rings of latitude and segments of longitude with the poles on the y axis
+///
+/// `rings` is clamped to at least 2 and `segments` to at least 3.
+#[derive(Debug, Clone, Copy)]
+pub struct UvSphere {
+    pub radius: f32,
+    pub rings: usize,
+    pub segments: usize,
+}
+
+/// Cylinder along the y axis centered at the origin
+///
+/// If `caps` is `false` the top and bottom are left open. `segments` is clamped to at least 3.
+#[derive(Debug, Clone, Copy)]
+pub struct Cylinder {
+    pub radius: f32,
+    pub height: f32,
+    pub segments: usize,
+    pub caps: bool,
+}
+
+/// Flat grid of quads on the XZ plane centered at the origin and facing up
+///
+/// Every quad is split into two triangles along the same diagonal. Both `subdivisions` are
+/// clamped to at least 1.
+#[derive(Debug, Clone, Copy)]
+pub struct Grid {
+    pub size: Vec2,
+    pub subdivisions: UVec2,
+}
+
+/// Axis aligned box centered at the origin
+#[derive(Debug, Clone, Copy)]
+pub struct Cuboid {
+    pub half_extents: Vec3,
+}
+
 impl From<Triangle> for MeshGraph {
     fn from(triangle: Triangle) -> Self {
         let Triangle(a, b, c) = triangle;
@@ -159,6 +198,260 @@ impl From<IcoSphere> for MeshGraph {
     }
 }
 
+impl From<Cylinder> for MeshGraph {
+    fn from(cylinder: Cylinder) -> Self {
+        let Cylinder {
+            radius,
+            height,
+            segments,
+            caps,
+        } = cylinder;
+
+        let segments = segments.max(3);
+        let half_height = height * 0.5;
+
+        // bottom ring followed by the top ring
+        let mut vertices = [-half_height, half_height]
+            .into_iter()
+            .flat_map(|y| {
+                (0..segments).map(move |segment| {
+                    let phi = TAU * segment as f32 / segments as f32;
+                    vec3(phi.cos() * radius, y, phi.sin() * radius)
+                })
+            })
+            .collect_vec();
+
+        let bottom = |segment: usize| segment % segments;
+        let top = |segment: usize| segments + segment % segments;
+
+        let mut indices = (0..segments)
+            .flat_map(|i| {
+                [
+                    bottom(i),
+                    top(i),
+                    top(i + 1),
+                    bottom(i),
+                    top(i + 1),
+                    bottom(i + 1),
+                ]
+            })
+            .collect_vec();
+
+        if caps {
+            let bottom_center = vertices.len();
+            let top_center = bottom_center + 1;
+            vertices.extend([vec3(0.0, -half_height, 0.0), vec3(0.0, half_height, 0.0)]);
+
+            // fans around the centers that share the ring vertices with the side wall
+            for i in 0..segments {
+                indices.extend([bottom_center, bottom(i), bottom(i + 1)]);
+                indices.extend([top_center, top(i + 1), top(i)]);
+            }
+        }
+
+        MeshGraph::indexed_triangles(&vertices, &indices)
+    }
+}
+
+impl From<Grid> for MeshGraph {
+    fn from(grid: Grid) -> Self {
+        let Grid { size, subdivisions } = grid;
+
+        let subdivisions = subdivisions.max(UVec2::ONE).as_usizevec2();
+        let row_length = subdivisions.x + 1;
+
+        let vertices = (0..=subdivisions.y)
+            .cartesian_product(0..=subdivisions.x)
+            .map(|(z, x)| {
+                let uv = vec2(x as f32, z as f32) / subdivisions.as_vec2() - 0.5;
+                vec3(uv.x * size.x, 0.0, uv.y * size.y)
+            })
+            .collect_vec();
+
+        let indices = (0..subdivisions.y)
+            .cartesian_product(0..subdivisions.x)
+            .flat_map(|(z, x)| {
+                let a = z * row_length + x;
+                let (b, c, d) = (a + 1, a + row_length + 1, a + row_length);
+
+                // counter-clockwise when looking from above
+                [a, d, c, a, c, b]
+            })
+            .collect_vec();
+
+        MeshGraph::indexed_triangles(&vertices, &indices)
+    }
+}
+
+impl From<Cuboid> for MeshGraph {
+    fn from(cuboid: Cuboid) -> Self {
+        let Cuboid { half_extents } = cuboid;
+
+        // the bits of the index select the positive side of the x, y and z axes
+        let vertices = (0..8)
+            .map(|i| {
+                let sign = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+                vec3(sign(0), sign(1), sign(2)) * half_extents
+            })
+            .collect_vec();
+
+        // counter-clockwise when looking from the outside
+        let quads = [
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+        ];
+
+        MeshGraph::indexed_triangles(
+            &vertices,
+            &quads
+                .into_iter()
+                .flat_map(|[a, b, c, d]| [a, b, c, a, c, d])
+                .collect_vec(),
+        )
+    }
+}
+
+impl From<UvSphere> for MeshGraph {
+    fn from(uv_sphere: UvSphere) -> Self {
+        let UvSphere {
+            radius,
+            rings,
+            segments,
+        } = uv_sphere;
+
+        let rings = rings.max(2);
+        let segments = segments.max(3);
+
+        // north pole, the rings between the poles and the south pole
+        let mut vertices = vec![vec3(0.0, radius, 0.0)];
+        for ring in 1..rings {
+            let theta = PI * ring as f32 / rings as f32;
+            let (ring_radius, y) = (theta.sin() * radius, theta.cos() * radius);
+
+            vertices.extend((0..segments).map(|segment| {
+                let phi = TAU * segment as f32 / segments as f32;
+                vec3(phi.cos() * ring_radius, y, phi.sin() * ring_radius)
+            }));
+        }
+        vertices.push(vec3(0.0, -radius, 0.0));
+
+        let south_pole = vertices.len() - 1;
+        // the seam reuses the first vertex of each ring
+        let ring_vertex = |ring: usize, segment: usize| match ring {
+            0 => 0,
+            _ if ring == rings => south_pole,
+            _ => 1 + (ring - 1) * segments + segment % segments,
+        };
+
+        let mut indices = Vec::with_capacity(6 * segments * (rings - 1));
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let a = ring_vertex(ring, segment);
+                let b = ring_vertex(ring + 1, segment);
+                let c = ring_vertex(ring + 1, segment + 1);
+                let d = ring_vertex(ring, segment + 1);
+
+                // the quads next to the poles degenerate to triangles
+                if ring != 0 {
+                    indices.extend([a, d, c]);
+                }
+                if ring != rings - 1 {
+                    indices.extend([a, c, b]);
+                }
+            }
+        }
+
+        MeshGraph::indexed_triangles(&vertices, &indices)
+    }
+}
+
+/// Distance below which points are considered to lie on a plane in [`tri_tri_intersection`].
+const TRI_TRI_EPSILON: f32 = 1e-6;
+
+/// Computes the segment in which the triangles `a` and `b` intersect following
+/// Möller (1997). Returns `None` if they don't intersect.
+///
+/// If the triangles only touch in a single point, both end points of the segment are equal.
+///
+/// Coplanar triangles are not supported yet and always return `None`.
+pub fn tri_tri_intersection(a: [Vec3; 3], b: [Vec3; 3]) -> Option<(Vec3, Vec3)> {
+    let normal_a = (a[1] - a[0]).cross(a[2] - a[0]).try_normalize()?;
+    let normal_b = (b[1] - b[0]).cross(b[2] - b[0]).try_normalize()?;
+
+    let dist_a = a.map(|p| normal_b.dot(p - b[0]));
+    let dist_b = b.map(|p| normal_a.dot(p - a[0]));
+
+    let segment_a = triangle_plane_segment(&a, &dist_a)?;
+    let segment_b = triangle_plane_segment(&b, &dist_b)?;
+
+    // both segments lie on the intersection line of the two planes
+    let direction = normal_a.cross(normal_b);
+    if direction.length_squared() < TRI_TRI_EPSILON * TRI_TRI_EPSILON {
+        // coplanar
+        return None;
+    }
+
+    let sorted = |(start, end): (Vec3, Vec3)| {
+        if direction.dot(start) <= direction.dot(end) {
+            (start, end)
+        } else {
+            (end, start)
+        }
+    };
+    let (start_a, end_a) = sorted(segment_a);
+    let (start_b, end_b) = sorted(segment_b);
+
+    let start = if direction.dot(start_a) >= direction.dot(start_b) {
+        start_a
+    } else {
+        start_b
+    };
+    let end = if direction.dot(end_a) <= direction.dot(end_b) {
+        end_a
+    } else {
+        end_b
+    };
+
+    if direction.dot(end - start) < -TRI_TRI_EPSILON {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+/// Returns the segment in which the triangle with the given signed distances of its corners
+/// crosses a plane.
+fn triangle_plane_segment(triangle: &[Vec3; 3], dist: &[f32; 3]) -> Option<(Vec3, Vec3)> {
+    if dist.iter().all(|d| *d > TRI_TRI_EPSILON) || dist.iter().all(|d| *d < -TRI_TRI_EPSILON) {
+        return None;
+    }
+
+    let mut points = Vec::with_capacity(3);
+
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let (d_i, d_j) = (dist[i], dist[j]);
+
+        if d_i.abs() <= TRI_TRI_EPSILON {
+            points.push(triangle[i]);
+        } else if d_j.abs() > TRI_TRI_EPSILON && d_i.signum() != d_j.signum() {
+            points.push(triangle[i].lerp(triangle[j], d_i / (d_i - d_j)));
+        }
+    }
+
+    match points.as_slice() {
+        [] => None,
+        [p] => Some((*p, *p)),
+        [p, q] => Some((*p, *q)),
+        // all corners on the plane
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +484,240 @@ mod tests {
         assert_eq!(mesh_graph.faces.len(), 2);
         assert_eq!(mesh_graph.halfedges.len(), 10);
     }
+
+    #[test]
+    fn test_cuboid_conversion() {
+        let half_extents = vec3(1.0, 2.0, 3.0);
+        let mesh_graph = MeshGraph::from(Cuboid { half_extents });
+
+        assert_eq!(mesh_graph.vertices.len(), 8);
+        assert_eq!(mesh_graph.faces.len(), 12);
+        assert_eq!(mesh_graph.halfedges.len(), 36);
+        assert!(mesh_graph.verify_twins().is_empty());
+        assert!(mesh_graph.boundary_loops().is_empty());
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            assert_eq!(mesh_graph.positions[vertex_id].abs(), half_extents);
+
+            // the whole one-ring is reachable by rotating around the vertex
+            let outgoing = vertex.outgoing_halfedges(&mesh_graph).collect_vec();
+            assert_eq!(
+                outgoing.len(),
+                mesh_graph.outgoing_halfedges[vertex_id].len()
+            );
+            assert_eq!(outgoing.len(), vertex.faces(&mesh_graph).count());
+        }
+
+        // all faces point outwards
+        for face in mesh_graph.faces.values() {
+            let [a, b, c] = face.vertex_positions(&mesh_graph).collect_array().unwrap();
+            assert!((b - a).cross(c - a).dot(a + b + c) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_uv_sphere_conversion() {
+        let (rings, segments) = (8, 16);
+        let mesh_graph = MeshGraph::from(UvSphere {
+            radius: 2.0,
+            rings,
+            segments,
+        });
+
+        assert_eq!(mesh_graph.vertices.len(), (rings - 1) * segments + 2);
+        assert_eq!(mesh_graph.faces.len(), 2 * (rings - 1) * segments);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        // watertight
+        assert!(
+            mesh_graph
+                .halfedges
+                .values()
+                .all(|halfedge| !halfedge.is_boundary())
+        );
+        assert!(mesh_graph.boundary_loops().is_empty());
+
+        // single vertex at each pole
+        for pole in [Vec3::Y * 2.0, Vec3::NEG_Y * 2.0] {
+            let (pole_id, pole_vertex) = mesh_graph
+                .vertices
+                .iter()
+                .filter(|(vertex_id, _)| mesh_graph.positions[*vertex_id].distance(pole) < 1e-5)
+                .exactly_one()
+                .ok()
+                .unwrap();
+            assert_eq!(pole_vertex.faces(&mesh_graph).count(), segments);
+            assert_eq!(mesh_graph.outgoing_halfedges[pole_id].len(), segments);
+        }
+
+        for pos in mesh_graph.positions.values() {
+            assert!((pos.length() - 2.0).abs() < 1e-5);
+        }
+
+        // all faces point outwards
+        for face in mesh_graph.faces.values() {
+            let [a, b, c] = face.vertex_positions(&mesh_graph).collect_array().unwrap();
+            assert!((b - a).cross(c - a).dot(a + b + c) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_cylinder_conversion() {
+        let segments = 12;
+
+        let open = MeshGraph::from(Cylinder {
+            radius: 1.0,
+            height: 2.0,
+            segments,
+            caps: false,
+        });
+
+        assert_eq!(open.vertices.len(), 2 * segments);
+        assert_eq!(open.faces.len(), 2 * segments);
+        assert!(open.verify_twins().is_empty());
+        assert_eq!(
+            open.halfedges
+                .values()
+                .filter(|halfedge| halfedge.is_boundary())
+                .count(),
+            2 * segments
+        );
+        assert_eq!(open.boundary_loops().len(), 2);
+
+        let closed = MeshGraph::from(Cylinder {
+            radius: 1.0,
+            height: 2.0,
+            segments,
+            caps: true,
+        });
+
+        assert_eq!(closed.vertices.len(), 2 * segments + 2);
+        assert_eq!(closed.faces.len(), 4 * segments);
+        assert!(closed.verify_twins().is_empty());
+        assert!(closed.boundary_loops().is_empty());
+
+        // all faces point outwards
+        for mesh_graph in [&open, &closed] {
+            for face in mesh_graph.faces.values() {
+                let [a, b, c] = face.vertex_positions(mesh_graph).collect_array().unwrap();
+                let center = (a + b + c) / 3.0;
+                assert!((b - a).cross(c - a).dot(center) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_conversion() {
+        let mesh_graph = MeshGraph::from(Grid {
+            size: Vec2::new(2.0, 4.0),
+            subdivisions: UVec2::new(4, 4),
+        });
+
+        assert_eq!(mesh_graph.vertices.len(), 5 * 5);
+        assert_eq!(mesh_graph.faces.len(), 2 * 4 * 4);
+        assert!(mesh_graph.verify_twins().is_empty());
+
+        // only the outer rim is open
+        let boundary_loops = mesh_graph.boundary_loops();
+        assert_eq!(boundary_loops.len(), 1);
+        assert_eq!(boundary_loops[0].len(), 4 * 4);
+
+        for (vertex_id, vertex) in &mesh_graph.vertices {
+            let pos = mesh_graph.positions[vertex_id];
+            assert_eq!(pos.y, 0.0);
+            assert!(pos.x.abs() <= 1.0 && pos.z.abs() <= 2.0);
+
+            if !vertex.is_boundary(&mesh_graph) {
+                assert_eq!(vertex.neighbours(&mesh_graph).count(), 6);
+            }
+        }
+
+        for face in mesh_graph.faces.values() {
+            let [a, b, c] = face.vertex_positions(&mesh_graph).collect_array().unwrap();
+            assert!((b - a).cross(c - a).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_tri_tri_intersection_crossing() {
+        let a = [
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+        let b = [
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 0.0, 1.0),
+            vec3(0.0, -2.0, 0.0),
+        ];
+
+        let (start, end) = tri_tri_intersection(a, b).unwrap();
+
+        // b crosses the plane of a in the segment from (0, -2, 0) to (0, 0, 0)
+        // which is clipped by a at (0, -1, 0)
+        let (low, high) = if start.y < end.y {
+            (start, end)
+        } else {
+            (end, start)
+        };
+        assert!(low.distance(vec3(0.0, -1.0, 0.0)) < 1e-5);
+        assert!(high.distance(Vec3::ZERO) < 1e-5);
+
+        assert_eq!(
+            tri_tri_intersection(b, a).map(|(s, e)| s.distance(e)),
+            Some(start.distance(end))
+        );
+    }
+
+    #[test]
+    fn test_tri_tri_intersection_touching() {
+        let a = [
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+        let b = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 1.0),
+            vec3(-1.0, 0.0, 1.0),
+        ];
+
+        let (start, end) = tri_tri_intersection(a, b).unwrap();
+
+        assert!(start.distance(Vec3::ZERO) < 1e-5);
+        assert!(end.distance(Vec3::ZERO) < 1e-5);
+    }
+
+    #[test]
+    fn test_tri_tri_intersection_separate() {
+        let a = [
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+
+        // above the plane of a
+        let b = [
+            vec3(0.0, 0.0, 1.0),
+            vec3(1.0, 0.0, 2.0),
+            vec3(-1.0, 0.0, 2.0),
+        ];
+        assert_eq!(tri_tri_intersection(a, b), None);
+
+        // crosses the plane of a but outside of a
+        let b = [
+            vec3(5.0, 0.0, -1.0),
+            vec3(5.0, 0.0, 1.0),
+            vec3(6.0, 0.0, 0.0),
+        ];
+        assert_eq!(tri_tri_intersection(a, b), None);
+
+        // coplanar
+        let b = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ];
+        assert_eq!(tri_tri_intersection(a, b), None);
+    }
 }
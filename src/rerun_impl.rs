@@ -1,7 +1,7 @@
+use crate::utils::error;
 use glam::Vec3;
 use hashbrown::HashMap;
 use itertools::Itertools;
-use tracing::error;
 
 use crate::RR;
 use crate::error_none;
@@ -1,7 +1,27 @@
+use std::collections::VecDeque;
+
+use crate::utils::error;
+use glam::Vec3;
 use hashbrown::HashSet;
-use tracing::{error, instrument};
+use itertools::Itertools;
+
+use super::{Face, FaceId, HalfedgeId, MeshGraph, VertexId};
 
-use super::{FaceId, HalfedgeId, MeshGraph, VertexId};
+/// The normals of a selection are considered to cancel each other out if the length of their
+/// area-weighted sum is below this fraction of the total area.
+const CANCELLED_NORMALS_EPSILON: f32 = 1e-4;
+
+/// The metric that is compared by [`Selection::select_similar_faces`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarCriterion {
+    /// The area of the faces. The tolerance is an absolute difference in area.
+    Area,
+    /// The normals of the faces. The tolerance is the angle between the normals in radians.
+    Normal,
+    /// The sum of the edge lengths of the faces. The tolerance is an absolute difference
+    /// in length.
+    Perimeter,
+}
 
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -53,9 +73,62 @@ impl Selection {
         vertices
     }
 
-    // TODO : also resolve to faces
+    /// Resolves the selection to faces. Selected halfedges contribute their face and selected
+    /// vertices all their adjacent faces.
+    pub fn resolve_to_faces(&self, mesh_graph: &MeshGraph) -> HashSet<FaceId> {
+        let mut faces = self.faces.clone();
+
+        for halfedge in &self.halfedges {
+            if let Some(face) = mesh_graph.halfedges.get(*halfedge).and_then(|he| he.face) {
+                faces.insert(face);
+            }
+        }
+
+        for vertex in &self.vertices {
+            if let Some(vertex) = mesh_graph.vertices.get(*vertex) {
+                faces.extend(vertex.faces(mesh_graph));
+            } else {
+                error!("Vertex not found");
+            }
+        }
+
+        faces
+    }
 
-    #[instrument(skip(mesh_graph))]
+    /// Area-weighted average of the normals of the selected faces (see
+    /// [`Selection::resolve_to_faces`]).
+    ///
+    /// Returns `None` if no faces are selected or if their normals cancel each other out
+    /// like for a closed mesh.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn average_normal(&self, mesh_graph: &MeshGraph) -> Option<Vec3> {
+        let mut normal_sum = Vec3::ZERO;
+        let mut area_sum = 0.0;
+
+        for face_id in self.resolve_to_faces(mesh_graph) {
+            let Some(face) = mesh_graph.faces.get(face_id) else {
+                error!("Face not found");
+                continue;
+            };
+            let Some([a, b, c]) = face.vertex_positions(mesh_graph).collect_array() else {
+                continue;
+            };
+
+            // the length of the cross product is twice the area of the face
+            let area_normal = (b - a).cross(c - a);
+
+            normal_sum += area_normal;
+            area_sum += area_normal.length();
+        }
+
+        if normal_sum.length() <= area_sum * CANCELLED_NORMALS_EPSILON {
+            return None;
+        }
+
+        normal_sum.try_normalize()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
     /// Grows the selection by neighboring vertices. It returns the new vertices.
     pub fn grow(&mut self, mesh_graph: &MeshGraph) -> HashSet<VertexId> {
         let existing_verts = self.resolve_to_vertices(mesh_graph);
@@ -77,6 +150,196 @@ impl Selection {
 
         new_verts
     }
+
+    /// Shrinks the selection by removing all vertices that have a neighbour outside of the
+    /// selection (see [`Selection::resolve_to_vertices`]). It returns the removed vertices.
+    ///
+    /// This is the inverse of [`Selection::grow`]. Selected faces and halfedges are kept only
+    /// if none of their vertices have been removed. The boundary of the mesh doesn't count as
+    /// outside, so shrinking a selection of the whole mesh doesn't change it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn shrink(&mut self, mesh_graph: &MeshGraph) -> HashSet<VertexId> {
+        let existing_verts = self.resolve_to_vertices(mesh_graph);
+
+        let removed_verts = existing_verts
+            .iter()
+            .copied()
+            .filter(|vert_id| {
+                let Some(vert) = mesh_graph.vertices.get(*vert_id) else {
+                    error!("Vertex not found");
+                    return true;
+                };
+
+                vert.neighbours(mesh_graph)
+                    .any(|neighbor| !existing_verts.contains(&neighbor))
+            })
+            .collect::<HashSet<_>>();
+
+        self.vertices = existing_verts.difference(&removed_verts).copied().collect();
+
+        self.faces.retain(|face_id| {
+            mesh_graph.faces.get(*face_id).is_some_and(|face| {
+                face.vertices(mesh_graph)
+                    .all(|vert_id| !removed_verts.contains(&vert_id))
+            })
+        });
+
+        self.halfedges.retain(|he_id| {
+            mesh_graph.halfedges.get(*he_id).is_some_and(|he| {
+                !removed_verts.contains(&he.end_vertex)
+                    && he
+                        .start_vertex(mesh_graph)
+                        .is_some_and(|start| !removed_verts.contains(&start))
+            })
+        });
+
+        removed_verts
+    }
+
+    /// Adds all faces that can be reached from `seed` by walking across edges, i.e. the
+    /// connected component (island) of the mesh that contains `seed`.
+    ///
+    /// Non-manifold edges (edges with more than two faces) are not crossed.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn select_connected_component(&mut self, mesh_graph: &MeshGraph, seed: FaceId) {
+        if !mesh_graph.faces.contains_key(seed) {
+            error!("Seed face not found");
+            return;
+        }
+
+        let mut visited = HashSet::<FaceId>::from_iter([seed]);
+        let mut queue = VecDeque::from([seed]);
+
+        while let Some(face_id) = queue.pop_front() {
+            self.faces.insert(face_id);
+
+            for he_id in mesh_graph.faces[face_id].halfedges(mesh_graph) {
+                let Some(neighbour_id) = manifold_neighbour_face(mesh_graph, he_id) else {
+                    continue;
+                };
+
+                if visited.insert(neighbour_id) {
+                    queue.push_back(neighbour_id);
+                }
+            }
+        }
+    }
+
+    /// Replaces the selected faces with all faces of the mesh that are not selected.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn invert_faces(&mut self, mesh_graph: &MeshGraph) {
+        self.faces = mesh_graph
+            .faces
+            .keys()
+            .filter(|face_id| !self.faces.contains(face_id))
+            .collect();
+    }
+
+    /// Replaces the selected vertices with all vertices of the mesh that are not selected.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn invert_vertices(&mut self, mesh_graph: &MeshGraph) {
+        self.vertices = mesh_graph
+            .vertices
+            .keys()
+            .filter(|vertex_id| !self.vertices.contains(vertex_id))
+            .collect();
+    }
+
+    /// Replaces the selected halfedges with all edges of the mesh that are not selected.
+    ///
+    /// An edge counts as selected if any of its two halfedges is selected. Unselected edges
+    /// are represented by one halfedge each (see [`MeshGraph::edges`]).
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn invert_halfedges(&mut self, mesh_graph: &MeshGraph) {
+        self.halfedges = mesh_graph
+            .edges()
+            .filter(|he_id| {
+                let twin_id = mesh_graph.halfedges[*he_id].twin;
+
+                !self.halfedges.contains(he_id)
+                    && twin_id.is_none_or(|twin_id| !self.halfedges.contains(&twin_id))
+            })
+            .collect();
+    }
+
+    /// Adds all faces to the selection whose `criterion` is within `tolerance` of the
+    /// `reference` face, including the reference face itself.
+    ///
+    /// This is the "select similar" command of DCC tools, e.g. selecting by normal from one
+    /// face of a subdivided cuboid selects its whole side.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(mesh_graph)))]
+    pub fn select_similar_faces(
+        &mut self,
+        mesh_graph: &MeshGraph,
+        reference: FaceId,
+        criterion: SimilarCriterion,
+        tolerance: f32,
+    ) {
+        let Some(reference_face) = mesh_graph.faces.get(reference) else {
+            error!("Reference face not found");
+            return;
+        };
+
+        match criterion {
+            SimilarCriterion::Area => {
+                let reference_area = reference_face.area(mesh_graph);
+
+                self.faces.extend(
+                    mesh_graph
+                        .faces
+                        .iter()
+                        .filter(|(_, face)| {
+                            (face.area(mesh_graph) - reference_area).abs() <= tolerance
+                        })
+                        .map(|(face_id, _)| face_id),
+                );
+            }
+            SimilarCriterion::Normal => {
+                let Some(reference_normal) = reference_face
+                    .compute_normal(mesh_graph)
+                    .filter(|normal| *normal != Vec3::ZERO)
+                else {
+                    error!("Reference face is degenerate");
+                    return;
+                };
+                let min_cos = tolerance.cos();
+
+                self.faces.extend(
+                    mesh_graph
+                        .faces
+                        .iter()
+                        .filter(|(_, face)| {
+                            face.compute_normal(mesh_graph).is_some_and(|normal| {
+                                normal != Vec3::ZERO && normal.dot(reference_normal) >= min_cos
+                            })
+                        })
+                        .map(|(face_id, _)| face_id),
+                );
+                // even if the tolerance is so small that rounding errors exclude it
+                self.faces.insert(reference);
+            }
+            SimilarCriterion::Perimeter => {
+                let reference_perimeter = face_perimeter(mesh_graph, reference_face);
+
+                self.faces.extend(
+                    mesh_graph
+                        .faces
+                        .iter()
+                        .filter(|(_, face)| {
+                            (face_perimeter(mesh_graph, face) - reference_perimeter).abs()
+                                <= tolerance
+                        })
+                        .map(|(face_id, _)| face_id),
+                );
+            }
+        }
+    }
+}
+
+fn face_perimeter(mesh_graph: &MeshGraph, face: &Face) -> f32 {
+    face.halfedges(mesh_graph)
+        .filter_map(|he_id| Some(mesh_graph.halfedges.get(he_id)?.length(mesh_graph)))
+        .sum()
 }
 
 pub trait SelectionOps<T> {
@@ -84,6 +347,29 @@ pub trait SelectionOps<T> {
     fn remove(&mut self, item: T);
 }
 
+/// Returns the face on the other side of the halfedge `he_id` unless it is a boundary or a
+/// non-manifold edge.
+fn manifold_neighbour_face(mesh_graph: &MeshGraph, he_id: HalfedgeId) -> Option<FaceId> {
+    let he = mesh_graph.halfedges.get(he_id)?;
+    let twin = mesh_graph.halfedges.get(he.twin?)?;
+    let start_vertex = twin.end_vertex;
+
+    // there are duplicate halfedges between the vertices of a non-manifold edge
+    if mesh_graph
+        .halfedges_from_to(start_vertex, he.end_vertex)
+        .len()
+        != 1
+        || mesh_graph
+            .halfedges_from_to(he.end_vertex, start_vertex)
+            .len()
+            != 1
+    {
+        return None;
+    }
+
+    twin.face
+}
+
 impl SelectionOps<VertexId> for Selection {
     fn insert(&mut self, item: VertexId) {
         self.vertices.insert(item);
@@ -181,3 +467,234 @@ macro_rules! impl_from_for_selection {
 
 impl_from_for_selection!(Vec);
 impl_from_for_selection!(HashSet);
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec2, Vec2};
+
+    use crate::{
+        primitives::{Grid, IcoSphere},
+        utils::{fan_cuboid, grid, two_spheres},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_average_normal() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 2,
+        });
+
+        assert_eq!(Selection::default().average_normal(&mesh_graph), None);
+        assert_eq!(
+            Selection::select_all(&mesh_graph).average_normal(&mesh_graph),
+            None
+        );
+
+        let (face_id, face) = mesh_graph.faces.iter().next().unwrap();
+        let face_normal = face.normal(&mesh_graph).unwrap();
+
+        let normal = Selection::from(face_id)
+            .average_normal(&mesh_graph)
+            .unwrap();
+        assert!(normal.abs_diff_eq(face_normal, 1e-5));
+
+        // the faces around a vertex of the sphere point outwards on average
+        let (vertex_id, pos) = mesh_graph.positions.iter().next().unwrap();
+        let normal = Selection::from(vertex_id)
+            .average_normal(&mesh_graph)
+            .unwrap();
+        assert!(normal.dot(pos.normalize()) > 0.99);
+    }
+
+    #[test]
+    fn test_average_normal_flat_patch() {
+        let (mesh_graph, _) = grid(4);
+
+        let normal = Selection::select_all(&mesh_graph)
+            .average_normal(&mesh_graph)
+            .unwrap();
+
+        assert!(normal.abs_diff_eq(Vec3::Z, 1e-5));
+    }
+
+    #[test]
+    fn test_grow_and_shrink() {
+        // 9x9 vertices from -4 to 4 in the XZ plane
+        let mesh_graph = MeshGraph::from(Grid {
+            size: Vec2::splat(8.0),
+            subdivisions: UVec2::splat(8),
+        });
+
+        let in_square =
+            |pos: Vec3, half_size: f32| pos.x.abs() <= half_size && pos.z.abs() <= half_size;
+
+        // 3x3 patch in the middle
+        let patch = mesh_graph
+            .positions
+            .iter()
+            .filter(|(_, pos)| in_square(**pos, 1.0))
+            .map(|(vertex_id, _)| vertex_id)
+            .collect::<HashSet<_>>();
+        assert_eq!(patch.len(), 3 * 3);
+
+        let mut selection = patch.iter().copied().collect::<Selection>();
+
+        let added = selection.grow(&mesh_graph);
+        assert!(added.is_disjoint(&patch));
+
+        let removed = selection.shrink(&mesh_graph);
+        let remaining = selection.resolve_to_vertices(&mesh_graph);
+
+        assert!(removed.is_subset(&added));
+        assert!(patch.is_subset(&remaining));
+        // the diagonal of the triangulation lets the two corners that aren't part of the
+        // 1-ring of the patch survive
+        assert!(remaining.len() <= patch.len() + 2);
+
+        // shrinking a face selection keeps only the faces in the interior
+        let mut selection = mesh_graph
+            .faces
+            .iter()
+            .filter(|(_, face)| {
+                face.vertex_positions(&mesh_graph)
+                    .all(|pos| in_square(pos, 2.0))
+            })
+            .map(|(face_id, _)| face_id)
+            .collect::<Selection>();
+        assert_eq!(selection.faces.len(), 2 * 4 * 4);
+
+        let removed = selection.shrink(&mesh_graph);
+
+        assert_eq!(removed.len(), 4 * 4);
+        assert_eq!(selection.vertices.len(), 3 * 3);
+        assert_eq!(selection.faces.len(), 2 * 2 * 2);
+    }
+
+    #[test]
+    fn test_select_connected_component() {
+        let mesh_graph = two_spheres(1);
+
+        let face_count = mesh_graph.faces.len() / 2;
+
+        let (seed, _) = mesh_graph
+            .faces
+            .iter()
+            .find(|(_, face)| face.center(&mesh_graph).x > 1.5)
+            .unwrap();
+
+        let mut selection = Selection::default();
+        selection.select_connected_component(&mesh_graph, seed);
+
+        assert_eq!(selection.faces.len(), face_count);
+        assert!(
+            selection
+                .faces
+                .iter()
+                .all(|face_id| mesh_graph.faces[*face_id].center(&mesh_graph).x > 1.5)
+        );
+
+        // terminates at boundaries
+        let mut open = mesh_graph.clone();
+        let removed_face_id = selection
+            .faces
+            .iter()
+            .copied()
+            .find(|id| *id != seed)
+            .unwrap();
+        open.remove_face(removed_face_id);
+
+        let mut selection = Selection::default();
+        selection.select_connected_component(&open, seed);
+        assert_eq!(selection.faces.len(), face_count - 1);
+    }
+
+    #[test]
+    fn test_invert() {
+        let mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let face_ids = mesh_graph.faces.keys().take(3).collect::<HashSet<_>>();
+        let mut selection = face_ids.iter().copied().collect::<Selection>();
+
+        selection.invert_faces(&mesh_graph);
+        assert_eq!(selection.faces.len(), mesh_graph.faces.len() - 3);
+        assert!(selection.faces.is_disjoint(&face_ids));
+
+        selection.invert_faces(&mesh_graph);
+        assert_eq!(selection.faces, face_ids);
+
+        // empty and full selections
+        let mut selection = Selection::default();
+
+        selection.invert_vertices(&mesh_graph);
+        assert_eq!(selection.vertices.len(), mesh_graph.vertices.len());
+        selection.invert_vertices(&mesh_graph);
+        assert!(selection.vertices.is_empty());
+
+        selection.invert_halfedges(&mesh_graph);
+        assert_eq!(selection.halfedges.len(), mesh_graph.halfedges.len() / 2);
+        selection.invert_halfedges(&mesh_graph);
+        assert!(selection.halfedges.is_empty());
+
+        // selecting either halfedge of an edge selects the edge
+        let he_id = mesh_graph.edges().next().unwrap();
+        let twin_id = mesh_graph.halfedges[he_id].twin.unwrap();
+        let mut selection = Selection::from(twin_id);
+
+        selection.invert_halfedges(&mesh_graph);
+        assert_eq!(
+            selection.halfedges.len(),
+            mesh_graph.halfedges.len() / 2 - 1
+        );
+        assert!(!selection.halfedges.contains(&he_id));
+    }
+
+    #[test]
+    fn test_select_similar_faces() {
+        // every side is subdivided into four triangles around its center
+        let mesh_graph = fan_cuboid(Vec3::new(1.0, 2.0, 3.0));
+
+        let side_faces = |normal: Vec3| {
+            mesh_graph
+                .faces
+                .iter()
+                .filter(|(_, face)| face.normal(&mesh_graph).unwrap().dot(normal) > 0.99)
+                .map(|(face_id, _)| face_id)
+                .collect::<HashSet<_>>()
+        };
+
+        let right_side = side_faces(Vec3::X);
+        assert_eq!(right_side.len(), 4);
+        let reference = *right_side.iter().next().unwrap();
+
+        let mut selection = Selection::default();
+        selection.select_similar_faces(&mesh_graph, reference, SimilarCriterion::Normal, 0.01);
+        assert_eq!(selection.faces, right_side);
+
+        // the neighbouring sides are 90° off
+        let mut selection = Selection::default();
+        selection.select_similar_faces(&mesh_graph, reference, SimilarCriterion::Normal, 1.6);
+        assert_eq!(selection.faces.len(), 20);
+
+        // all triangles of the opposite sides have the same area
+        let both_sides = right_side
+            .union(&side_faces(Vec3::NEG_X))
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut selection = Selection::default();
+        selection.select_similar_faces(&mesh_graph, reference, SimilarCriterion::Area, 1e-4);
+        assert_eq!(selection.faces, both_sides);
+
+        // but only half of them share the edge length with the reference
+        let mut selection = Selection::default();
+        selection.select_similar_faces(&mesh_graph, reference, SimilarCriterion::Perimeter, 1e-4);
+        assert_eq!(selection.faces.len(), 4);
+        assert!(selection.faces.is_subset(&both_sides));
+        assert!(selection.faces.contains(&reference));
+    }
+}
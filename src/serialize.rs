@@ -1,4 +1,5 @@
-use glam::Vec3;
+use glam::{Vec2, Vec3};
+use hashbrown::HashSet;
 use serde::Deserialize;
 use slotmap::{SecondaryMap, SlotMap};
 
@@ -12,6 +13,13 @@ pub struct MeshGraphIntermediate {
 
     pub positions: SecondaryMap<VertexId, Vec3>,
     pub vertex_normals: Option<SecondaryMap<VertexId, Vec3>>,
+    #[serde(default)]
+    pub uvs: Option<SecondaryMap<VertexId, Vec2>>,
+
+    #[serde(default)]
+    pub sharp_edges: HashSet<HalfedgeId>,
+    #[serde(default)]
+    pub polygon_ids: SecondaryMap<FaceId, usize>,
 }
 
 impl From<MeshGraphIntermediate> for MeshGraph {
@@ -26,18 +34,17 @@ impl From<MeshGraphIntermediate> for MeshGraph {
             faces: value.faces,
             positions: value.positions,
             vertex_normals: value.vertex_normals,
+            uvs: value.uvs,
+            face_normals: None,
             outgoing_halfedges: Default::default(),
+            sharp_edges: value.sharp_edges,
+            polygon_ids: value.polygon_ids,
+            face_user_data_maps: Default::default(),
+            vertex_attributes: Default::default(),
+            change_tracker: Default::default(),
         };
 
-        for (id, face) in &mut mesh_graph.faces {
-            face.index = mesh_graph.next_index;
-            mesh_graph.next_index += 1;
-
-            mesh_graph.index_to_face_id.insert(face.index, id);
-        }
-
-        mesh_graph.rebuild_bvh();
-        mesh_graph.rebuild_outgoing_halfedges();
+        mesh_graph.finalize();
 
         mesh_graph
     }
@@ -0,0 +1,180 @@
+use std::any::{Any, TypeId};
+
+use hashbrown::HashMap;
+use slotmap::SecondaryMap;
+
+use crate::{FaceId, MeshGraph};
+
+/// Type-erased storage for the per-face user data of [`MeshGraph::face_user_data`].
+///
+/// Holds at most one `SecondaryMap<FaceId, T>` per type `T`.
+#[derive(Default)]
+pub struct FaceUserDataMaps {
+    maps: HashMap<TypeId, Box<dyn FaceUserDataMap>>,
+}
+
+impl FaceUserDataMaps {
+    /// Returns the map for the type `T` if it has been created already.
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<&SecondaryMap<FaceId, T>> {
+        self.maps
+            .get(&TypeId::of::<T>())
+            .and_then(|map| map.as_any().downcast_ref())
+    }
+
+    /// Returns the map for the type `T` and creates it if necessary.
+    pub fn get_or_default<T: Clone + Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut SecondaryMap<FaceId, T> {
+        self.maps
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(SecondaryMap::<FaceId, T>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            // the map is always stored under the type id of its value type
+            .unwrap()
+    }
+
+    /// Removes the data of `face_id` from all maps.
+    pub fn remove(&mut self, face_id: FaceId) {
+        for map in self.maps.values_mut() {
+            map.remove(face_id);
+        }
+    }
+
+    /// Moves the data of all maps to the new face ids given by `face_map`.
+    /// Data of faces that aren't in `face_map` is dropped.
+    pub(crate) fn remap(&mut self, face_map: &SecondaryMap<FaceId, FaceId>) {
+        for map in self.maps.values_mut() {
+            map.remap(face_map);
+        }
+    }
+
+    /// Returns `true` if no map has been created yet.
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+}
+
+impl Clone for FaceUserDataMaps {
+    fn clone(&self) -> Self {
+        Self {
+            maps: self
+                .maps
+                .iter()
+                .map(|(type_id, map)| (*type_id, map.clone_box()))
+                .collect(),
+        }
+    }
+}
+
+/// Operations that are needed on the face user data without knowing its type.
+trait FaceUserDataMap: Send + Sync {
+    fn remove(&mut self, face_id: FaceId);
+    fn remap(&mut self, face_map: &SecondaryMap<FaceId, FaceId>);
+    fn clone_box(&self) -> Box<dyn FaceUserDataMap>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Clone + Send + Sync + 'static> FaceUserDataMap for SecondaryMap<FaceId, T> {
+    fn remove(&mut self, face_id: FaceId) {
+        SecondaryMap::remove(self, face_id);
+    }
+
+    fn remap(&mut self, face_map: &SecondaryMap<FaceId, FaceId>) {
+        *self = self
+            .drain()
+            .filter_map(|(face_id, value)| Some((*face_map.get(face_id)?, value)))
+            .collect();
+    }
+
+    fn clone_box(&self) -> Box<dyn FaceUserDataMap> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MeshGraph {
+    /// Returns the user data of type `T` that is attached to faces. The map is created on
+    /// first access.
+    ///
+    /// This allows to tag faces with arbitrary data (e.g. labels, random seeds or editor
+    /// metadata) without wrapping the mesh graph. There is one map per type, so use newtypes
+    /// to store several values of the same type. The data of a face is removed together with
+    /// the face.
+    ///
+    /// ```
+    /// # use mesh_graph::{MeshGraph, primitives::IcoSphere};
+    /// let mut mesh_graph = MeshGraph::from(IcoSphere { radius: 1.0, subdivisions: 0 });
+    /// let face_id = mesh_graph.faces.keys().next().unwrap();
+    ///
+    /// mesh_graph.face_user_data::<u32>().insert(face_id, 42);
+    ///
+    /// assert_eq!(mesh_graph.face_user_data::<u32>()[face_id], 42);
+    /// ```
+    pub fn face_user_data<T: Clone + Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut SecondaryMap<FaceId, T> {
+        self.face_user_data_maps.get_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Seed(u64);
+
+    #[test]
+    fn test_face_user_data() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let face_ids = mesh_graph.faces.keys().collect::<Vec<_>>();
+
+        for (i, face_id) in face_ids.iter().enumerate() {
+            mesh_graph
+                .face_user_data::<String>()
+                .insert(*face_id, format!("face {i}"));
+        }
+        mesh_graph
+            .face_user_data::<Seed>()
+            .insert(face_ids[0], Seed(7));
+
+        mesh_graph.remove_face(face_ids[0]);
+
+        let labels = mesh_graph.face_user_data::<String>();
+        assert_eq!(labels.len(), face_ids.len() - 1);
+        assert!(!labels.contains_key(face_ids[0]));
+        assert_eq!(labels[face_ids[1]], "face 1");
+        assert!(mesh_graph.face_user_data::<Seed>().is_empty());
+
+        // clones carry the data along
+        let mut cloned = mesh_graph.clone();
+        assert_eq!(cloned.face_user_data::<String>()[face_ids[2]], "face 2");
+
+        // the data follows the faces when the mesh is compacted
+        mesh_graph.shrink_to_fit();
+        let labels = mesh_graph
+            .face_user_data_maps
+            .get::<String>()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(labels.len(), face_ids.len() - 1);
+        assert!(!labels.contains(&"face 0".to_string()));
+    }
+}
@@ -21,11 +21,49 @@ pub fn quat_array(q: impl Borrow<Quat>) -> [f32; 4] {
     [q.borrow().x, q.borrow().y, q.borrow().z, q.borrow().w]
 }
 
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{info, warn};
+
+// Public (but hidden) because the exported `error_none!` macro expands to it.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing::error;
+
+/// Stand-in for the `tracing` macros that compiles to nothing if the `tracing` feature is
+/// disabled. The arguments are still type checked (but never evaluated) to avoid warnings
+/// about unused variables.
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __no_trace {
+    ($($args:tt)*) => {
+        if false {
+            let _ = ::std::format_args!($($args)*);
+        }
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use {crate::__no_trace as info, crate::__no_trace as warn};
+
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+pub use crate::__no_trace as error;
+
+/// Returns a closure that logs the error message and returns `None`.
+/// Meant to be used with [`Option::or_else`].
+///
+/// ```
+/// use mesh_graph::error_none;
+///
+/// let value: Option<u32> = None.or_else(error_none!("Value {} not found", 42));
+/// assert!(value.is_none());
+/// ```
 #[macro_export]
 macro_rules! error_none {
     ($msg:literal $(, $args:expr)*) => {
         || {
-            tracing::error!($msg $(, $args)*);
+            $crate::utils::error!($msg $(, $args)*);
             None
         }
     };
@@ -36,7 +74,7 @@ macro_rules! unwrap_or_return {
         match $code {
             Some(value) => value,
             None => {
-                tracing::error!($error);
+                $crate::utils::error!($error);
                 return $ret;
             }
         }
@@ -45,7 +83,7 @@ macro_rules! unwrap_or_return {
         match $code {
             Some(value) => value,
             None => {
-                tracing::error!($error);
+                $crate::utils::error!($error);
                 return;
             }
         }
@@ -60,7 +98,7 @@ pub(crate) fn get_tracing_subscriber() {
         .pretty()
         .try_init()
     {
-        tracing::warn!("Tracing subscriber already initialized: {}", e);
+        warn!("Tracing subscriber already initialized: {}", e);
     }
 }
 
@@ -196,3 +234,91 @@ pub(crate) fn extend_with(
 
     new_vertex_ids
 }
+
+/// Positions and triangle indices of a grid of unit squares in the XY plane with `width` x
+/// `height` vertices. Vertex `i` is at `(i % width, i / width, 0)` and the faces point
+/// towards +Z.
+#[cfg(test)]
+pub(crate) fn grid_positions_and_indices(width: usize, height: usize) -> (Vec<Vec3>, Vec<usize>) {
+    use itertools::Itertools;
+
+    let positions = (0..width * height)
+        .map(|i| Vec3::new((i % width) as f32, (i / width) as f32, 0.0))
+        .collect_vec();
+    let indices = (0..height - 1)
+        .cartesian_product(0..width - 1)
+        .flat_map(|(y, x)| {
+            let i = y * width + x;
+            [i, i + 1, i + width + 1, i, i + width + 1, i + width]
+        })
+        .collect_vec();
+
+    (positions, indices)
+}
+
+/// Mesh graph of a grid with `size` x `size` vertices (see [`grid_positions_and_indices`])
+/// together with the vertex IDs in the order of the positions.
+#[cfg(test)]
+pub(crate) fn grid(size: usize) -> (crate::MeshGraph, Vec<crate::VertexId>) {
+    let (positions, indices) = grid_positions_and_indices(size, size);
+    crate::MeshGraph::indexed_triangles_and_vertex_ids(&positions, &indices)
+}
+
+/// Two unconnected ico spheres of radius 1 with the given subdivisions in a single mesh graph.
+/// The first one is centered at the origin and the second one at `(3, 0, 0)`.
+#[cfg(test)]
+pub(crate) fn two_spheres(subdivisions: u8) -> crate::MeshGraph {
+    use itertools::Itertools;
+
+    let sphere = crate::MeshGraph::from(crate::primitives::IcoSphere {
+        radius: 1.0,
+        subdivisions,
+    })
+    .to_triangle_soup();
+
+    crate::MeshGraph::triangles(
+        &sphere
+            .iter()
+            .copied()
+            .chain(sphere.iter().map(|pos| pos + Vec3::X * 3.0))
+            .collect_vec(),
+    )
+}
+
+/// Cuboid of `size` centered at the origin where every side is split into four triangles
+/// around its center. Unlike [`crate::primitives::Cuboid`] this is symmetric with respect to
+/// all three coordinate planes.
+#[cfg(test)]
+pub(crate) fn fan_cuboid(size: Vec3) -> crate::MeshGraph {
+    use itertools::Itertools;
+
+    let mut positions = (0..8)
+        .map(|i| {
+            Vec3::new(
+                if i & 1 == 0 { -0.5 } else { 0.5 },
+                if i & 2 == 0 { -0.5 } else { 0.5 },
+                if i & 4 == 0 { -0.5 } else { 0.5 },
+            ) * size
+        })
+        .collect_vec();
+
+    let sides = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [1, 3, 7, 5],
+        [0, 4, 6, 2],
+    ];
+    let mut indices = vec![];
+    for side in sides {
+        let center = positions.len();
+        positions.push(side.iter().map(|i| positions[*i]).sum::<Vec3>() / 4.0);
+
+        for (a, b) in side.into_iter().circular_tuple_windows() {
+            indices.extend([a, b, center]);
+        }
+    }
+
+    crate::MeshGraph::indexed_triangles(&positions, &indices)
+}
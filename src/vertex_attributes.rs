@@ -0,0 +1,463 @@
+use std::{any::Any, marker::PhantomData};
+
+use glam::{Vec2, Vec3, Vec4};
+use slotmap::SecondaryMap;
+
+use crate::{MeshGraph, VertexId};
+
+/// Linear interpolation of vertex attribute values. See [`MeshGraph::add_vertex_attribute`].
+pub trait Lerp {
+    /// Returns `self` for `t == 0` and `other` for `t == 1`.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec2::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec3::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Vec4 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec4::lerp(*self, *other, t)
+    }
+}
+
+/// Handle of a vertex attribute with values of type `T`.
+/// See [`MeshGraph::add_vertex_attribute`].
+pub struct AttributeId<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for AttributeId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AttributeId<T> {}
+
+impl<T> PartialEq for AttributeId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for AttributeId<T> {}
+
+impl<T> std::fmt::Debug for AttributeId<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AttributeId").field(&self.index).finish()
+    }
+}
+
+/// Type-erased storage for the named per-vertex attributes of
+/// [`MeshGraph::add_vertex_attribute`].
+#[derive(Default)]
+pub struct VertexAttributes {
+    attributes: Vec<(String, Box<dyn VertexAttributeMap>)>,
+}
+
+impl VertexAttributes {
+    /// Returns the values of the attribute `id`.
+    pub fn get<T: 'static>(&self, id: AttributeId<T>) -> Option<&SecondaryMap<VertexId, T>> {
+        self.attributes
+            .get(id.index)
+            .and_then(|(_, map)| map.as_any().downcast_ref())
+    }
+
+    /// Returns the values of the attribute `id` mutably.
+    pub fn get_mut<T: 'static>(
+        &mut self,
+        id: AttributeId<T>,
+    ) -> Option<&mut SecondaryMap<VertexId, T>> {
+        self.attributes
+            .get_mut(id.index)
+            .and_then(|(_, map)| map.as_any_mut().downcast_mut())
+    }
+
+    /// Returns the id of the attribute called `name` if it has values of type `T`.
+    pub fn id<T: 'static>(&self, name: &str) -> Option<AttributeId<T>> {
+        let index = self
+            .attributes
+            .iter()
+            .position(|(attribute_name, _)| attribute_name == name)?;
+
+        self.attributes[index]
+            .1
+            .as_any()
+            .is::<SecondaryMap<VertexId, T>>()
+            .then_some(AttributeId {
+                index,
+                _marker: PhantomData,
+            })
+    }
+
+    /// Removes the values of `vertex_id` from all attributes.
+    pub fn remove(&mut self, vertex_id: VertexId) {
+        for (_, map) in &mut self.attributes {
+            map.remove(vertex_id);
+        }
+    }
+
+    /// Sets the values of `target` to the interpolation between the values of `a` and `b`
+    /// in all attributes. Attributes without values for both `a` and `b` are left unchanged.
+    pub(crate) fn interpolate(&mut self, target: VertexId, a: VertexId, b: VertexId, t: f32) {
+        for (_, map) in &mut self.attributes {
+            map.interpolate(target, a, b, t);
+        }
+    }
+
+    /// Moves the values of all attributes to the new vertex ids given by `vertex_map`.
+    /// Values of vertices that aren't in `vertex_map` are dropped.
+    pub(crate) fn remap(&mut self, vertex_map: &SecondaryMap<VertexId, VertexId>) {
+        for (_, map) in &mut self.attributes {
+            map.remap(vertex_map);
+        }
+    }
+
+    /// Returns `true` if no attribute has been added yet.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+impl Clone for VertexAttributes {
+    fn clone(&self) -> Self {
+        Self {
+            attributes: self
+                .attributes
+                .iter()
+                .map(|(name, map)| (name.clone(), map.clone_box()))
+                .collect(),
+        }
+    }
+}
+
+/// Operations that are needed on the vertex attributes without knowing their type.
+trait VertexAttributeMap: Send + Sync {
+    fn remove(&mut self, vertex_id: VertexId);
+    fn interpolate(&mut self, target: VertexId, a: VertexId, b: VertexId, t: f32);
+    fn remap(&mut self, vertex_map: &SecondaryMap<VertexId, VertexId>);
+    fn clone_box(&self) -> Box<dyn VertexAttributeMap>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Lerp + Clone + Send + Sync + 'static> VertexAttributeMap for SecondaryMap<VertexId, T> {
+    fn remove(&mut self, vertex_id: VertexId) {
+        SecondaryMap::remove(self, vertex_id);
+    }
+
+    fn interpolate(&mut self, target: VertexId, a: VertexId, b: VertexId, t: f32) {
+        if let (Some(a), Some(b)) = (self.get(a), self.get(b)) {
+            let value = a.lerp(b, t);
+            self.insert(target, value);
+        }
+    }
+
+    fn remap(&mut self, vertex_map: &SecondaryMap<VertexId, VertexId>) {
+        *self = self
+            .drain()
+            .filter_map(|(vertex_id, value)| Some((*vertex_map.get(vertex_id)?, value)))
+            .collect();
+    }
+
+    fn clone_box(&self) -> Box<dyn VertexAttributeMap> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl MeshGraph {
+    /// Adds a named per-vertex attribute with values of type `T` and returns its id.
+    ///
+    /// This allows to carry arbitrary data like colors, weights or temperatures along with the
+    /// geometry. The values are kept consistent by the mesh operations:
+    /// [`MeshGraph::subdivide_edge`] interpolates the values of the new vertex at the edge
+    /// midpoint, collapsing an edge interpolates them at the new position of the surviving
+    /// vertex, [`MeshGraph::catmull_clark`] and [`MeshGraph::loop_subdivide`] average them for
+    /// the new vertices and removing a vertex removes its values.
+    ///
+    /// If an attribute called `name` with values of type `T` exists already, its id is returned.
+    /// An attribute with the same name but another type is replaced by an empty one.
+    ///
+    /// ```
+    /// # use mesh_graph::{MeshGraph, primitives::IcoSphere};
+    /// let mut mesh_graph = MeshGraph::from(IcoSphere { radius: 1.0, subdivisions: 0 });
+    /// let temperature = mesh_graph.add_vertex_attribute::<f32>("temperature");
+    ///
+    /// let vertex_id = mesh_graph.vertices.keys().next().unwrap();
+    /// mesh_graph.vertex_attribute_mut(temperature).unwrap().insert(vertex_id, 21.5);
+    ///
+    /// assert_eq!(mesh_graph.vertex_attribute(temperature).unwrap()[vertex_id], 21.5);
+    /// ```
+    pub fn add_vertex_attribute<T: Lerp + Clone + Send + Sync + 'static>(
+        &mut self,
+        name: impl Into<String>,
+    ) -> AttributeId<T> {
+        let name = name.into();
+
+        if let Some(id) = self.vertex_attributes.id(&name) {
+            return id;
+        }
+
+        let map = Box::new(SecondaryMap::<VertexId, T>::new());
+        let attributes = &mut self.vertex_attributes.attributes;
+
+        let index = match attributes
+            .iter()
+            .position(|(attribute_name, _)| *attribute_name == name)
+        {
+            Some(index) => {
+                attributes[index].1 = map;
+                index
+            }
+            None => {
+                attributes.push((name, map));
+                attributes.len() - 1
+            }
+        };
+
+        AttributeId {
+            index,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the values of the vertex attribute `id` or `None` if it has been replaced by an
+    /// attribute of another type.
+    pub fn vertex_attribute<T: 'static>(
+        &self,
+        id: AttributeId<T>,
+    ) -> Option<&SecondaryMap<VertexId, T>> {
+        self.vertex_attributes.get(id)
+    }
+
+    /// Returns the values of the vertex attribute `id` mutably or `None` if it has been replaced
+    /// by an attribute of another type.
+    pub fn vertex_attribute_mut<T: 'static>(
+        &mut self,
+        id: AttributeId<T>,
+    ) -> Option<&mut SecondaryMap<VertexId, T>> {
+        self.vertex_attributes.get_mut(id)
+    }
+
+    /// Carries the UVs and vertex attributes of this mesh graph over to `target` which has been
+    /// rebuilt from it, e.g. by subdivision. Every entry of `sources` is a vertex of `target`
+    /// together with the vertices of this mesh graph whose values are averaged for it.
+    ///
+    /// Every vertex of this mesh graph that is the source of an averaged vertex has to be the
+    /// single source of a vertex of `target` as well.
+    pub(crate) fn transfer_vertex_data(
+        &self,
+        target: &mut MeshGraph,
+        sources: &[(VertexId, Vec<VertexId>)],
+    ) {
+        if let Some(uvs) = &self.uvs {
+            target.uvs = Some(
+                sources
+                    .iter()
+                    .filter_map(|(target_id, source_ids)| {
+                        let sum = source_ids
+                            .iter()
+                            .map(|source_id| uvs.get(*source_id).copied())
+                            .sum::<Option<Vec2>>()?;
+
+                        Some((*target_id, sum / source_ids.len() as f32))
+                    })
+                    .collect(),
+            );
+        }
+
+        if self.vertex_attributes.is_empty() {
+            return;
+        }
+
+        let vertex_map = sources
+            .iter()
+            .filter_map(|(target_id, source_ids)| match source_ids.as_slice() {
+                [source_id] => Some((*source_id, *target_id)),
+                _ => None,
+            })
+            .collect::<SecondaryMap<_, _>>();
+
+        let mut attributes = self.vertex_attributes.clone();
+        attributes.remap(&vertex_map);
+
+        for (target_id, source_ids) in sources {
+            let Some(mapped_ids) = source_ids
+                .iter()
+                .map(|source_id| vertex_map.get(*source_id).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            if let [first, second, rest @ ..] = mapped_ids.as_slice() {
+                // running average
+                attributes.interpolate(*target_id, *first, *second, 0.5);
+                for (i, mapped_id) in rest.iter().enumerate() {
+                    attributes.interpolate(
+                        *target_id,
+                        *target_id,
+                        *mapped_id,
+                        1.0 / (i + 3) as f32,
+                    );
+                }
+            }
+        }
+
+        target.vertex_attributes = attributes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::primitives::IcoSphere;
+
+    use super::*;
+
+    #[test]
+    fn test_subdivide_edge_interpolates_attributes() {
+        let (mut mesh_graph, vertex_ids) = MeshGraph::indexed_triangles_and_vertex_ids(
+            &[
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            &[0, 1, 2, 0, 2, 3],
+        );
+
+        let temperature = mesh_graph.add_vertex_attribute::<f32>("temperature");
+        let color = mesh_graph.add_vertex_attribute::<Vec3>("color");
+
+        for (i, vertex_id) in vertex_ids.iter().enumerate() {
+            mesh_graph
+                .vertex_attribute_mut(temperature)
+                .unwrap()
+                .insert(*vertex_id, 10.0 * i as f32);
+        }
+        let colors = mesh_graph.vertex_attribute_mut(color).unwrap();
+        colors.insert(vertex_ids[0], Vec3::X);
+        colors.insert(vertex_ids[2], Vec3::Y);
+
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[0], vertex_ids[2])
+            .unwrap();
+        mesh_graph.subdivide_edge(he_id);
+
+        let new_vertex_id = mesh_graph
+            .vertices
+            .keys()
+            .find(|vertex_id| !vertex_ids.contains(vertex_id))
+            .unwrap();
+
+        assert_eq!(
+            mesh_graph.vertex_attribute(temperature).unwrap()[new_vertex_id],
+            10.0
+        );
+        assert_eq!(
+            mesh_graph.vertex_attribute(color).unwrap()[new_vertex_id],
+            Vec3::new(0.5, 0.5, 0.0)
+        );
+
+        // no values are made up for an edge without values on both sides
+        let he_id = mesh_graph
+            .halfedge_from_to(vertex_ids[0], vertex_ids[1])
+            .unwrap();
+        mesh_graph.subdivide_edge(he_id);
+
+        assert_eq!(mesh_graph.vertex_attribute(color).unwrap().len(), 3);
+        assert_eq!(mesh_graph.vertex_attribute(temperature).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_collapse_edge_interpolates_attributes() {
+        let mut mesh_graph = MeshGraph::from(IcoSphere {
+            radius: 1.0,
+            subdivisions: 1,
+        });
+
+        let weight = mesh_graph.add_vertex_attribute::<f32>("weight");
+        let vertex_ids = mesh_graph.vertices.keys().collect_vec();
+        for vertex_id in &vertex_ids {
+            let height = mesh_graph.positions[*vertex_id].y;
+            mesh_graph
+                .vertex_attribute_mut(weight)
+                .unwrap()
+                .insert(*vertex_id, height);
+        }
+
+        let he_id = mesh_graph.halfedges.keys().next().unwrap();
+        let start_v_id = mesh_graph.halfedges[he_id]
+            .start_vertex(&mesh_graph)
+            .unwrap();
+        let end_v_id = mesh_graph.halfedges[he_id].end_vertex;
+        let weights = mesh_graph.vertex_attribute(weight).unwrap();
+        let expected = (weights[start_v_id] + weights[end_v_id]) * 0.5;
+
+        mesh_graph.collapse_edge(he_id);
+
+        let weights = mesh_graph.vertex_attribute(weight).unwrap();
+        assert_eq!(weights.len(), mesh_graph.vertices.len());
+        assert!((weights[start_v_id] - expected).abs() < 1e-6);
+        assert!(!weights.contains_key(end_v_id));
+
+        // the values follow the vertices through compaction and clones
+        mesh_graph.shrink_to_fit();
+        let cloned = mesh_graph.clone();
+        for (vertex_id, pos) in &cloned.positions {
+            let value = cloned.vertex_attribute(weight).unwrap()[vertex_id];
+            assert!(value == pos.y || (value - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_attribute_ids() {
+        let mut mesh_graph = MeshGraph::new();
+
+        let a = mesh_graph.add_vertex_attribute::<f32>("a");
+        let b = mesh_graph.add_vertex_attribute::<Vec2>("b");
+
+        assert_ne!(a.index, b.index);
+        assert_eq!(mesh_graph.add_vertex_attribute::<f32>("a"), a);
+        assert_eq!(mesh_graph.vertex_attributes.id::<Vec2>("b"), Some(b));
+        assert_eq!(mesh_graph.vertex_attributes.id::<f32>("b"), None);
+
+        // replacing the attribute with another type invalidates the old id
+        let replaced = mesh_graph.add_vertex_attribute::<Vec3>("a");
+        assert_eq!(replaced.index, a.index);
+        assert!(mesh_graph.vertex_attribute(a).is_none());
+        assert!(mesh_graph.vertex_attribute(replaced).is_some());
+    }
+}